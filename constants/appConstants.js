@@ -12,6 +12,7 @@ export const API_CONFIG = {
 // Session Configuration
 export const SESSION_CONFIG = {
     STORAGE_KEY: '@proteges_pyes_user',
+    PORTAL_STORAGE_KEY: '@proteges_pyes_portal', // guardian portal session token
     AUTO_LOGOUT_DURATION: 24 * 60 * 60 * 1000, // 24 hours in milliseconds
 };
 
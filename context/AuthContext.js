@@ -6,6 +6,7 @@
 import React, { createContext, useState, useEffect, useCallback, useContext } from 'react';
 import { SESSION_CONFIG } from '../constants/appConstants';
 import { storeData, getData, removeData } from '../utils/storage';
+import api from '../services/api';
 
 /**
  * Auth Context
@@ -35,6 +36,7 @@ export const AuthProvider = ({ children }) => {
         try {
             const savedUser = await getData(SESSION_CONFIG.STORAGE_KEY);
             if (savedUser) {
+                api.setSessionToken(savedUser.token);
                 setUser(savedUser);
             }
         } catch (error) {
@@ -49,6 +51,7 @@ export const AuthProvider = ({ children }) => {
      * @param {Object} userData - User data
      */
     const login = useCallback(async (userData) => {
+        api.setSessionToken(userData.token);
         setUser(userData);
         await storeData(SESSION_CONFIG.STORAGE_KEY, userData);
     }, []);
@@ -57,6 +60,12 @@ export const AuthProvider = ({ children }) => {
      * Logout user and clear storage
      */
     const logout = useCallback(async () => {
+        try {
+            await api.logout();
+        } catch (error) {
+            console.error('Error closing session:', error);
+        }
+        api.setSessionToken(null);
         setUser(null);
         await removeData(SESSION_CONFIG.STORAGE_KEY);
     }, []);
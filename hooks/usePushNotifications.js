@@ -73,13 +73,15 @@ export const usePushNotifications = () => {
         return token;
     };
 
-    const registerTokenBackend = async (studentId, deviceName) => {
+    // sessionToken: sesión del portal de padres
+    const registerTokenBackend = async (sessionToken, deviceName) => {
         if (!expoPushToken) return;
         try {
-            await api.registerPushToken(studentId, expoPushToken, deviceName || Device.modelName);
+            await api.registerPortalDevice(sessionToken, expoPushToken, deviceName || Device.modelName);
             console.log("Token registrado en backend");
         } catch (error) {
             console.error("Error registrando token en backend:", error);
+            throw error;
         }
     }
 
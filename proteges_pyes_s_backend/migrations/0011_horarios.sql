@@ -0,0 +1,20 @@
+-- Horarios de clase por grupo (un registro por materia, dia y hora)
+CREATE TABLE IF NOT EXISTS horarios (
+    id_horario                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    grupo                       TEXT NOT NULL,
+    materia                     TEXT NOT NULL,
+    docente                     INTEGER NOT NULL,
+    salon_clase                 TEXT NOT NULL,
+    dia_semana                  INTEGER NOT NULL, -- 1 = lunes ... 7 = domingo
+    hora_inicio                 TEXT NOT NULL,    -- HH:MM
+    hora_fin                    TEXT NOT NULL,    -- HH:MM
+    FOREIGN KEY (grupo)         REFERENCES grupos(id_nomenclatura),
+    FOREIGN KEY (docente)       REFERENCES usuarios(id_usuario)
+);
+
+CREATE INDEX IF NOT EXISTS idx_horarios_grupo_dia ON horarios(grupo, dia_semana);
+CREATE INDEX IF NOT EXISTS idx_horarios_docente_dia ON horarios(docente, dia_semana);
+
+-- Las asistencias tomadas en clase quedan ligadas al periodo; las del acceso quedan en NULL
+ALTER TABLE asistencias ADD COLUMN id_horario INTEGER REFERENCES horarios(id_horario);
+CREATE INDEX IF NOT EXISTS idx_asistencias_horario ON asistencias(id_horario);
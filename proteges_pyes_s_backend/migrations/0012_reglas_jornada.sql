@@ -0,0 +1,33 @@
+-- Reglas de la jornada escolar; GENERAL aplica cuando el turno del grupo no tiene regla propia
+CREATE TABLE IF NOT EXISTS reglas_jornada (
+    turno               TEXT PRIMARY KEY,           -- GENERAL, MATUTINO, VESPERTINO
+    hora_entrada        TEXT NOT NULL,              -- HH:MM
+    minutos_tolerancia  INTEGER NOT NULL DEFAULT 10,
+    hora_salida         TEXT NOT NULL               -- salidas antes de esta hora son anticipadas
+);
+
+INSERT OR IGNORE INTO reglas_jornada (turno, hora_entrada, minutos_tolerancia, hora_salida) VALUES
+    ('GENERAL', '07:00', 10, '13:00'),
+    ('MATUTINO', '07:00', 10, '13:00'),
+    ('VESPERTINO', '13:30', 10, '19:30');
+
+ALTER TABLE grupos ADD COLUMN turno TEXT;
+UPDATE grupos SET turno = 'MATUTINO' WHERE lower(descripcion) LIKE '%matut%';
+UPDATE grupos SET turno = 'VESPERTINO' WHERE lower(descripcion) LIKE '%vespert%';
+
+-- Tipo de registro del acceso y su clasificacion contra las reglas
+ALTER TABLE asistencias ADD COLUMN tipo_registro TEXT;  -- ENTRADA, SALIDA
+ALTER TABLE asistencias ADD COLUMN clasificacion TEXT;  -- A_TIEMPO, RETARDO, SALIDA_ANTICIPADA
+
+-- Los registros previos del acceso alternaban entrada/salida por dia
+UPDATE asistencias SET tipo_registro = CASE
+    WHEN (
+        SELECT count(*) FROM asistencias b
+        WHERE b.id_control_escolar = asistencias.id_control_escolar
+        AND substr(b.fecha_asistencia, 1, 10) = substr(asistencias.fecha_asistencia, 1, 10)
+        AND b.id_horario IS NULL
+        AND b.id_asistencia < asistencias.id_asistencia
+    ) % 2 = 0 THEN 'ENTRADA'
+    ELSE 'SALIDA'
+END
+WHERE id_horario IS NULL;
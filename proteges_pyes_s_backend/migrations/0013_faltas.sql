@@ -0,0 +1,16 @@
+-- Hora de corte a partir de la cual quien no registro entrada queda con falta
+ALTER TABLE reglas_jornada ADD COLUMN hora_corte_faltas TEXT NOT NULL DEFAULT '10:00';
+UPDATE reglas_jornada SET hora_corte_faltas = '16:30' WHERE turno = 'VESPERTINO';
+
+-- Las faltas se registran como asistencias con tipo_registro = 'FALTA' y presente = FALSE
+ALTER TABLE asistencias ADD COLUMN id_justificante INTEGER REFERENCES justificantes(id);
+CREATE INDEX IF NOT EXISTS idx_asistencias_justificante ON asistencias(id_justificante);
+
+-- Jornadas ya cerradas por turno para no volver a calcular las faltas
+CREATE TABLE IF NOT EXISTS cierres_jornada (
+    fecha           TEXT NOT NULL,  -- YYYY-MM-DD
+    turno           TEXT NOT NULL,
+    faltas          INTEGER NOT NULL DEFAULT 0,
+    ejecutado_en    DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (fecha, turno)
+);
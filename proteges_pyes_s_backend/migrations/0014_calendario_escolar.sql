@@ -0,0 +1,32 @@
+-- Ciclos escolares y sus periodos (semestres)
+CREATE TABLE IF NOT EXISTS ciclos_escolares (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    nombre          TEXT NOT NULL UNIQUE,   -- p. ej. 2026-2027
+    fecha_inicio    TEXT NOT NULL,          -- YYYY-MM-DD
+    fecha_fin       TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS periodos_escolares (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_ciclo        INTEGER NOT NULL,
+    nombre          TEXT NOT NULL,          -- p. ej. Semestre Agosto-Enero
+    fecha_inicio    TEXT NOT NULL,
+    fecha_fin       TEXT NOT NULL,
+    FOREIGN KEY (id_ciclo) REFERENCES ciclos_escolares(id)
+);
+
+-- Dias festivos, suspensiones y dias habiles extra; grupo NULL aplica a toda la escuela
+CREATE TABLE IF NOT EXISTS calendario_excepciones (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    fecha_inicio    TEXT NOT NULL,          -- YYYY-MM-DD
+    fecha_fin       TEXT NOT NULL,          -- inclusiva
+    tipo            TEXT NOT NULL,          -- FESTIVO, SUSPENSION, DIA_HABIL
+    grupo           TEXT,
+    descripcion     TEXT,
+    uid_ical        TEXT,                   -- evento de origen cuando se importa de iCal
+    FOREIGN KEY (grupo) REFERENCES grupos(id_nomenclatura)
+);
+
+CREATE INDEX IF NOT EXISTS idx_periodos_fechas ON periodos_escolares(fecha_inicio, fecha_fin);
+CREATE INDEX IF NOT EXISTS idx_calendario_fechas ON calendario_excepciones(fecha_inicio, fecha_fin);
+CREATE INDEX IF NOT EXISTS idx_calendario_uid ON calendario_excepciones(uid_ical);
@@ -0,0 +1,12 @@
+-- Archivos de evidencia validados (nombre generado por el servidor dentro de uploads/)
+CREATE TABLE IF NOT EXISTS evidencias (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    nombre_archivo  TEXT NOT NULL UNIQUE,
+    nombre_original TEXT,                       -- solo informativo, nunca se usa como ruta
+    tipo_mime       TEXT NOT NULL,              -- image/jpeg, image/png, application/pdf
+    tamano_bytes    INTEGER NOT NULL,
+    sha256          TEXT NOT NULL,
+    subido_en       DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE justificantes ADD COLUMN evidencia_id INTEGER REFERENCES evidencias(id);
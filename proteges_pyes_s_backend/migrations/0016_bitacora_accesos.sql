@@ -0,0 +1,36 @@
+-- Bitacora de accesos a informacion sensible (descargas de evidencias, etc.)
+CREATE TABLE IF NOT EXISTS bitacora_accesos (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    recurso     TEXT NOT NULL,      -- EVIDENCIA, ...
+    recurso_id  TEXT NOT NULL,
+    accion      TEXT NOT NULL,      -- DESCARGA, ...
+    actor       TEXT NOT NULL,      -- usuario:<id> o dispositivo:<id>
+    ip          TEXT,
+    fecha       DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_bitacora_recurso ON bitacora_accesos(recurso, recurso_id);
+
+-- Evidencias subidas antes de la validacion: se registran para poder servirlas
+-- por el nuevo endpoint (sin checksum, el archivo no se volvio a leer)
+INSERT INTO evidencias (nombre_archivo, nombre_original, tipo_mime, tamano_bytes, sha256)
+SELECT
+    substr(evidencia_url, 9),
+    substr(evidencia_url, 9),
+    CASE
+        WHEN lower(evidencia_url) LIKE '%.pdf' THEN 'application/pdf'
+        WHEN lower(evidencia_url) LIKE '%.png' THEN 'image/png'
+        ELSE 'image/jpeg'
+    END,
+    0,
+    ''
+FROM justificantes
+WHERE evidencia_id IS NULL AND evidencia_url LIKE 'uploads/%'
+GROUP BY evidencia_url;
+
+UPDATE justificantes
+SET evidencia_id = (SELECT id FROM evidencias WHERE nombre_archivo = substr(justificantes.evidencia_url, 9))
+WHERE evidencia_id IS NULL AND evidencia_url LIKE 'uploads/%';
+
+-- la ruta publica deja de existir; las descargas se piden por enlace firmado
+UPDATE justificantes SET evidencia_url = NULL;
@@ -0,0 +1,9 @@
+-- Justificantes por rango de dias, revisor y reenvio despues de un rechazo
+ALTER TABLE justificantes ADD COLUMN fecha_fin TEXT;               -- inclusiva (YYYY-MM-DD)
+ALTER TABLE justificantes ADD COLUMN revisado_por INTEGER REFERENCES usuarios(id_usuario);
+ALTER TABLE justificantes ADD COLUMN fecha_revision DATETIME;
+ALTER TABLE justificantes ADD COLUMN reenvio_de INTEGER REFERENCES justificantes(id);
+
+UPDATE justificantes SET fecha_fin = fecha_justificacion WHERE fecha_fin IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_justificantes_estudiante ON justificantes(estudiante_id, estado);
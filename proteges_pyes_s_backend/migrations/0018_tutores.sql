@@ -0,0 +1,47 @@
+-- Tutores como entidad propia, ligados a uno o varios estudiantes (hermanos comparten cuenta)
+CREATE TABLE IF NOT EXISTS tutores (
+    id_tutor                INTEGER PRIMARY KEY AUTOINCREMENT,
+    nombre                  TEXT NOT NULL,
+    telefono                TEXT UNIQUE,
+    telefono_alterno        TEXT,
+    correo                  TEXT,
+    -- preferencias de notificacion
+    notificar_push          BOOLEAN NOT NULL DEFAULT TRUE,
+    notificar_sms           BOOLEAN NOT NULL DEFAULT FALSE,
+    avisos_entrada_salida   BOOLEAN NOT NULL DEFAULT TRUE,   -- aviso por cada registro en el acceso
+    creado_en               DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS tutores_estudiantes (
+    id_tutor                INTEGER NOT NULL REFERENCES tutores(id_tutor) ON DELETE CASCADE,
+    id_control_escolar      TEXT NOT NULL REFERENCES estudiantes(id_control_escolar) ON DELETE CASCADE,
+    parentesco              TEXT NOT NULL DEFAULT 'NO_ESPECIFICADO',
+    es_principal            BOOLEAN NOT NULL DEFAULT FALSE,
+    puede_recoger           BOOLEAN NOT NULL DEFAULT TRUE,
+    recibe_notificaciones   BOOLEAN NOT NULL DEFAULT TRUE,
+    restriccion_custodia    TEXT,   -- p. ej. orden judicial; nota para el personal al entregar
+    entrega_restringida     BOOLEAN NOT NULL DEFAULT FALSE, -- no se le entrega el alumno
+    PRIMARY KEY (id_tutor, id_control_escolar)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tutores_estudiantes_alumno ON tutores_estudiantes(id_control_escolar);
+
+-- los dispositivos pasan a pertenecer a un tutor (los registrados antes quedan por alumno)
+ALTER TABLE push_tokens ADD COLUMN tutor_id INTEGER REFERENCES tutores(id_tutor);
+
+-- migrar los telefonos capturados en el estudiante; un mismo telefono es un solo tutor
+INSERT OR IGNORE INTO tutores (nombre, telefono)
+SELECT 'Tutor sin nombre', telefono FROM (
+    SELECT trim(telefono_tutor_principal) AS telefono FROM estudiantes
+    UNION
+    SELECT trim(telefono_tutor_secundario) FROM estudiantes
+)
+WHERE telefono IS NOT NULL AND telefono <> '';
+
+INSERT OR IGNORE INTO tutores_estudiantes (id_tutor, id_control_escolar, es_principal)
+SELECT t.id_tutor, e.id_control_escolar, TRUE
+FROM estudiantes e JOIN tutores t ON t.telefono = trim(e.telefono_tutor_principal);
+
+INSERT OR IGNORE INTO tutores_estudiantes (id_tutor, id_control_escolar, es_principal)
+SELECT t.id_tutor, e.id_control_escolar, FALSE
+FROM estudiantes e JOIN tutores t ON t.telefono = trim(e.telefono_tutor_secundario);
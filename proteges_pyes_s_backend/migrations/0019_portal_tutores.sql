@@ -0,0 +1,54 @@
+-- Codigos de un solo uso enviados al telefono del tutor para iniciar sesion en el portal
+CREATE TABLE IF NOT EXISTS codigos_verificacion (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_tutor    INTEGER NOT NULL REFERENCES tutores(id_tutor) ON DELETE CASCADE,
+    codigo_hash TEXT NOT NULL,              -- nunca se guarda el codigo en claro
+    expira_en   INTEGER NOT NULL,           -- epoch en segundos
+    intentos    INTEGER NOT NULL DEFAULT 0,
+    usado       BOOLEAN NOT NULL DEFAULT FALSE,
+    creado_en   INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_codigos_tutor ON codigos_verificacion(id_tutor, creado_en);
+
+-- Codigos equivocados por IP, para frenar a quien prueba codigos contra muchos telefonos
+CREATE TABLE IF NOT EXISTS intentos_portal (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    ip          TEXT NOT NULL,
+    creado_en   INTEGER NOT NULL            -- epoch en segundos
+);
+
+CREATE INDEX IF NOT EXISTS idx_intentos_portal_ip ON intentos_portal(ip, creado_en);
+
+-- Sesiones del portal (token Bearer; se guarda solo su firma)
+CREATE TABLE IF NOT EXISTS sesiones_tutores (
+    token_hash      TEXT PRIMARY KEY,
+    id_tutor        INTEGER NOT NULL REFERENCES tutores(id_tutor) ON DELETE CASCADE,
+    nombre_dispositivo TEXT,
+    creado_en       INTEGER NOT NULL,
+    expira_en       INTEGER NOT NULL,
+    revocada        BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+-- Los dispositivos registrados desde el portal pertenecen al tutor y no a un alumno:
+-- student_id deja de ser obligatorio (SQLite requiere reconstruir la tabla)
+CREATE TABLE push_tokens_nueva (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    student_id  TEXT REFERENCES estudiantes(id_control_escolar),
+    token       TEXT NOT NULL,
+    device_name TEXT,
+    created_at  DATETIME DEFAULT CURRENT_TIMESTAMP,
+    tutor_id    INTEGER REFERENCES tutores(id_tutor)
+);
+
+-- los dispositivos anonimos registrados por alumno se descartan: cualquiera podia suscribirse
+-- a los avisos de un alumno; los tutores vuelven a registrar su dispositivo desde el portal
+INSERT INTO push_tokens_nueva (id, student_id, token, device_name, created_at, tutor_id)
+SELECT id, student_id, token, device_name, created_at, tutor_id FROM push_tokens
+WHERE tutor_id IS NOT NULL;
+
+DROP TABLE push_tokens;
+ALTER TABLE push_tokens_nueva RENAME TO push_tokens;
+
+CREATE INDEX IF NOT EXISTS idx_push_tokens_tutor ON push_tokens(tutor_id);
+CREATE INDEX IF NOT EXISTS idx_push_tokens_student ON push_tokens(student_id);
@@ -0,0 +1,32 @@
+-- Personas autorizadas para recoger al alumno ademas de sus tutores (abuelos, transporte, etc.)
+CREATE TABLE IF NOT EXISTS personas_autorizadas (
+    id_persona              INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_control_escolar      TEXT NOT NULL REFERENCES estudiantes(id_control_escolar) ON DELETE CASCADE,
+    nombre                  TEXT NOT NULL,
+    parentesco              TEXT NOT NULL DEFAULT 'NO_ESPECIFICADO',
+    telefono                TEXT,
+    tipo_identificacion     TEXT,           -- INE, pasaporte, licencia...
+    numero_identificacion   TEXT,
+    foto_id                 INTEGER REFERENCES evidencias(id),
+    activa                  BOOLEAN NOT NULL DEFAULT TRUE,
+    creado_en               DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_personas_autorizadas_alumno ON personas_autorizadas(id_control_escolar);
+
+-- Entregas de alumnos: quien lo entrego, a quien y cuando
+CREATE TABLE IF NOT EXISTS entregas_estudiantes (
+    id_entrega                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_control_escolar          TEXT NOT NULL REFERENCES estudiantes(id_control_escolar),
+    id_tutor                    INTEGER REFERENCES tutores(id_tutor),
+    id_persona                  INTEGER REFERENCES personas_autorizadas(id_persona),
+    recibido_por                TEXT NOT NULL,  -- nombre al momento de la entrega
+    entregado_por               INTEGER NOT NULL REFERENCES usuarios(id_usuario),
+    identificacion_verificada   BOOLEAN NOT NULL DEFAULT FALSE,
+    durante_emergencia          BOOLEAN NOT NULL DEFAULT FALSE,
+    notas                       TEXT,
+    fecha_entrega               TEXT NOT NULL,
+    CHECK ((id_tutor IS NULL) <> (id_persona IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_entregas_alumno_fecha ON entregas_estudiantes(id_control_escolar, fecha_entrega);
@@ -0,0 +1,50 @@
+-- Cada emergencia es una sesion con fases: EVACUACION -> REUNIFICACION -> CERRADA
+CREATE TABLE IF NOT EXISTS sesiones_emergencia (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    fase                TEXT NOT NULL DEFAULT 'EVACUACION' CHECK (fase IN ('EVACUACION', 'REUNIFICACION', 'CERRADA')),
+    iniciada_por        INTEGER NOT NULL,
+    iniciada_en         TEXT NOT NULL,
+    reunificacion_en    TEXT,
+    punto_reunificacion TEXT,           -- a donde deben acudir los tutores
+    cerrada_por         INTEGER,
+    cerrada_en          TEXT
+);
+
+-- Estado de cada alumno dentro de la sesion; todos inician como FALTANTE
+CREATE TABLE IF NOT EXISTS estado_alumnos_emergencia (
+    sesion_id           INTEGER NOT NULL REFERENCES sesiones_emergencia(id) ON DELETE CASCADE,
+    id_control_escolar  TEXT NOT NULL REFERENCES estudiantes(id_control_escolar) ON DELETE CASCADE,
+    grupo               TEXT,               -- grupo al iniciar la emergencia; el reporte no cambia si despues lo mueven
+    estado              TEXT NOT NULL DEFAULT 'FALTANTE' CHECK (estado IN ('LOCALIZADO', 'LESIONADO', 'ENTREGADO', 'FALTANTE')),
+    notas               TEXT,
+    actualizado_por     INTEGER,
+    actualizado_en      TEXT NOT NULL,
+    PRIMARY KEY (sesion_id, id_control_escolar)
+);
+
+CREATE INDEX IF NOT EXISTS idx_estado_alumnos_emergencia_estado ON estado_alumnos_emergencia(sesion_id, estado);
+
+-- los escaneos y entregas quedan ligados a la sesion (ya no se borra el historial al activar)
+ALTER TABLE historial_consulta ADD COLUMN sesion_id INTEGER REFERENCES sesiones_emergencia(id);
+ALTER TABLE entregas_estudiantes ADD COLUMN sesion_id INTEGER REFERENCES sesiones_emergencia(id);
+
+CREATE INDEX IF NOT EXISTS idx_historial_consulta_sesion ON historial_consulta(sesion_id, estudiante_consultado);
+
+-- si la escuela estaba en emergencia al migrar, esa emergencia pasa a ser una sesion abierta
+-- (el historial se limpiaba al activar, lo que hay le pertenece)
+INSERT INTO sesiones_emergencia (fase, iniciada_por, iniciada_en)
+SELECT 'EVACUACION', 0, COALESCE(
+    (SELECT MAX(timestamp) FROM emergency_history WHERE action = 'ACTIVATED'),
+    datetime('now')
+)
+FROM system_state WHERE id = 1 AND emergency_active;
+
+UPDATE historial_consulta SET sesion_id = (SELECT MAX(id) FROM sesiones_emergencia);
+
+INSERT INTO estado_alumnos_emergencia (sesion_id, id_control_escolar, grupo, estado, actualizado_en)
+SELECT s.id, e.id_control_escolar, e.grupo,
+    CASE WHEN EXISTS (
+        SELECT 1 FROM historial_consulta h WHERE h.estudiante_consultado = e.id_control_escolar
+    ) THEN 'LOCALIZADO' ELSE 'FALTANTE' END,
+    datetime('now')
+FROM sesiones_emergencia s, estudiantes e;
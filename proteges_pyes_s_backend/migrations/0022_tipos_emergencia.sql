@@ -0,0 +1,21 @@
+-- Tipo de emergencia (simulacro o evento real) y notas de la sesion
+ALTER TABLE sesiones_emergencia ADD COLUMN tipo TEXT NOT NULL DEFAULT 'EVENTO_REAL'
+    CHECK (tipo IN ('SIMULACRO_INCENDIO', 'SIMULACRO_SISMO', 'EVENTO_REAL', 'CONFINAMIENTO'));
+ALTER TABLE sesiones_emergencia ADD COLUMN notas TEXT;
+
+-- momento en que el alumno quedo localizado por primera vez (base de las metricas);
+-- vuelve a NULL si se le marca otra vez como faltante
+ALTER TABLE estado_alumnos_emergencia ADD COLUMN localizado_en TEXT;
+
+UPDATE estado_alumnos_emergencia
+SET localizado_en = COALESCE(
+    (
+        SELECT MIN(h.fecha_consulta) FROM historial_consulta h
+        WHERE h.sesion_id = estado_alumnos_emergencia.sesion_id
+        AND h.estudiante_consultado = estado_alumnos_emergencia.id_control_escolar
+    ),
+    actualizado_en
+)
+WHERE estado <> 'FALTANTE';
+
+CREATE INDEX IF NOT EXISTS idx_sesiones_emergencia_tipo ON sesiones_emergencia(tipo, iniciada_en);
@@ -0,0 +1,20 @@
+-- Protocolo de la sesion: evacuar y contar en el punto de reunion, o permanecer dentro
+-- (confinamiento ante una amenaza, resguardo ante un riesgo externo)
+ALTER TABLE sesiones_emergencia ADD COLUMN protocolo TEXT NOT NULL DEFAULT 'EVACUACION'
+    CHECK (protocolo IN ('EVACUACION', 'CONFINAMIENTO', 'RESGUARDO'));
+
+-- donde se encuentra el alumno (salon reportado por el docente)
+ALTER TABLE estado_alumnos_emergencia ADD COLUMN ubicacion TEXT;
+
+-- reportes de los docentes durante un confinamiento o resguardo: quienes estan con ellos y en que salon
+CREATE TABLE IF NOT EXISTS reportes_aula (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    sesion_id       INTEGER NOT NULL REFERENCES sesiones_emergencia(id) ON DELETE CASCADE,
+    salon           TEXT NOT NULL,
+    reportado_por   INTEGER NOT NULL REFERENCES usuarios(id_usuario),
+    alumnos         INTEGER NOT NULL DEFAULT 0,
+    notas           TEXT,
+    reportado_en    TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_reportes_aula_sesion ON reportes_aula(sesion_id, salon);
@@ -0,0 +1,22 @@
+-- Zonas del plantel (edificios, patios, canchas) y puntos de reunion dentro de ellas
+CREATE TABLE IF NOT EXISTS zonas (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    nombre          TEXT NOT NULL UNIQUE,
+    descripcion     TEXT,
+    activa          BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+CREATE TABLE IF NOT EXISTS puntos_reunion (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    nombre          TEXT NOT NULL UNIQUE,
+    id_zona         INTEGER NOT NULL REFERENCES zonas(id),
+    descripcion     TEXT,
+    activo          BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+-- punto de reunion al que debe llegar cada grupo
+ALTER TABLE grupos ADD COLUMN punto_reunion INTEGER REFERENCES puntos_reunion(id);
+
+-- donde ocurrio cada escaneo (la zona se toma del punto si se indico)
+ALTER TABLE historial_consulta ADD COLUMN id_zona INTEGER REFERENCES zonas(id);
+ALTER TABLE historial_consulta ADD COLUMN id_punto INTEGER REFERENCES puntos_reunion(id);
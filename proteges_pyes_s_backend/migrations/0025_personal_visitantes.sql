@@ -0,0 +1,42 @@
+-- Entradas y salidas del personal (usuarios) en el plantel
+CREATE TABLE IF NOT EXISTS presencia_personal (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_usuario      INTEGER NOT NULL REFERENCES usuarios(id_usuario),
+    entrada         TEXT NOT NULL,
+    salida          TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_presencia_personal_usuario ON presencia_personal(id_usuario, salida);
+
+-- Bitacora de visitantes: registro de entrada y salida
+CREATE TABLE IF NOT EXISTS visitantes (
+    id                      INTEGER PRIMARY KEY AUTOINCREMENT,
+    nombre                  TEXT NOT NULL,
+    motivo                  TEXT,
+    visita_a                TEXT,           -- persona o area que visita
+    telefono                TEXT,
+    tipo_identificacion     TEXT,
+    numero_identificacion   TEXT,
+    registrado_por          INTEGER NOT NULL REFERENCES usuarios(id_usuario),
+    entrada                 TEXT NOT NULL,
+    salida                  TEXT,
+    salida_registrada_por   INTEGER REFERENCES usuarios(id_usuario)
+);
+
+CREATE INDEX IF NOT EXISTS idx_visitantes_entrada ON visitantes(entrada);
+
+-- Personal y visitantes dentro del plantel en cada emergencia; RETIRADO: salio del plantel
+-- durante la emergencia (registro su salida)
+CREATE TABLE IF NOT EXISTS estado_personas_emergencia (
+    sesion_id       INTEGER NOT NULL REFERENCES sesiones_emergencia(id) ON DELETE CASCADE,
+    tipo            TEXT NOT NULL CHECK (tipo IN ('PERSONAL', 'VISITANTE')),
+    id_persona      INTEGER NOT NULL,   -- id_usuario o id del visitante
+    nombre          TEXT NOT NULL,
+    estado          TEXT NOT NULL DEFAULT 'FALTANTE'
+        CHECK (estado IN ('LOCALIZADO', 'LESIONADO', 'FALTANTE', 'RETIRADO')),
+    notas           TEXT,
+    actualizado_por INTEGER REFERENCES usuarios(id_usuario),
+    actualizado_en  TEXT NOT NULL,
+    localizado_en   TEXT,
+    PRIMARY KEY (sesion_id, tipo, id_persona)
+);
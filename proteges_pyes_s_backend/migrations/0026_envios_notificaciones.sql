@@ -0,0 +1,16 @@
+-- Resultado de cada envio de notificaciones push (Expo); los avisos a tutores se guardan
+-- solo durante una emergencia, los avisos generales de emergencia siempre
+CREATE TABLE IF NOT EXISTS envios_notificaciones (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    sesion_id           INTEGER REFERENCES sesiones_emergencia(id) ON DELETE SET NULL,
+    id_control_escolar  TEXT,           -- NULL en los avisos generales
+    titulo              TEXT NOT NULL,
+    dispositivos        INTEGER NOT NULL DEFAULT 0,
+    entregados          INTEGER NOT NULL DEFAULT 0,  -- tickets "ok" de Expo
+    fallidos            INTEGER NOT NULL DEFAULT 0,
+    estado_http         INTEGER,
+    error               TEXT,
+    enviado_en          TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_envios_notificaciones_sesion ON envios_notificaciones(sesion_id);
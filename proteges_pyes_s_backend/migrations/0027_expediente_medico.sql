@@ -0,0 +1,40 @@
+-- Expediente medico estructurado: alergias, condiciones y medicamentos actuales
+CREATE TABLE IF NOT EXISTS antecedentes_medicos (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_control_escolar  TEXT NOT NULL REFERENCES estudiantes(id_control_escolar) ON DELETE CASCADE,
+    tipo                TEXT NOT NULL CHECK (tipo IN ('ALERGIA', 'CONDICION', 'MEDICAMENTO')),
+    nombre              TEXT NOT NULL,      -- alergeno, condicion o medicamento
+    detalle             TEXT,               -- reaccion, tratamiento o dosis y horario
+    severidad           TEXT CHECK (severidad IN ('LEVE', 'MODERADA', 'GRAVE')),
+    activo              BOOLEAN NOT NULL DEFAULT TRUE,
+    registrado_por      INTEGER REFERENCES usuarios(id_usuario),
+    actualizado_en      TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_antecedentes_alumno ON antecedentes_medicos(id_control_escolar, tipo);
+
+-- Bitacora de la enfermeria
+CREATE TABLE IF NOT EXISTS visitas_enfermeria (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_control_escolar  TEXT NOT NULL REFERENCES estudiantes(id_control_escolar),
+    atendido_por        INTEGER NOT NULL REFERENCES usuarios(id_usuario),
+    llegada             TEXT NOT NULL,
+    sintomas            TEXT NOT NULL,
+    tratamiento         TEXT,
+    enviado_a_casa      BOOLEAN NOT NULL DEFAULT FALSE,
+    notas               TEXT,
+    registrado_en       TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_visitas_enfermeria_alumno ON visitas_enfermeria(id_control_escolar, llegada);
+
+-- los textos capturados en la inscripcion pasan como primer registro de cada lista
+INSERT INTO antecedentes_medicos (id_control_escolar, tipo, nombre, actualizado_en)
+SELECT id_control_escolar, 'ALERGIA', trim(alergias), CURRENT_TIMESTAMP
+FROM estudiantes
+WHERE trim(COALESCE(alergias, '')) <> '' AND lower(trim(alergias)) NOT IN ('ninguna', 'ninguno', 'n/a', 'na', 'no');
+
+INSERT INTO antecedentes_medicos (id_control_escolar, tipo, nombre, actualizado_en)
+SELECT id_control_escolar, 'CONDICION', trim(enfermedades_cronicas), CURRENT_TIMESTAMP
+FROM estudiantes
+WHERE trim(COALESCE(enfermedades_cronicas, '')) <> '' AND lower(trim(enfermedades_cronicas)) NOT IN ('ninguna', 'ninguno', 'n/a', 'na', 'no');
@@ -0,0 +1,44 @@
+-- Reportes de incidentes disciplinarios
+CREATE TABLE IF NOT EXISTS incidentes (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    categoria       TEXT NOT NULL,      -- ver constants::INCIDENT_CATEGORIES
+    severidad       TEXT NOT NULL CHECK (severidad IN ('LEVE', 'MODERADA', 'GRAVE')),
+    descripcion     TEXT NOT NULL,
+    lugar           TEXT,
+    ocurrido_en     TEXT NOT NULL,
+    reportado_por   INTEGER NOT NULL REFERENCES usuarios(id_usuario),
+    estado          TEXT NOT NULL DEFAULT 'ABIERTO' CHECK (estado IN ('ABIERTO', 'CERRADO')),
+    creado_en       TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_incidentes_fecha ON incidentes(ocurrido_en);
+
+-- Alumnos involucrados; el acuse de recibo del tutor es por alumno
+CREATE TABLE IF NOT EXISTS incidentes_estudiantes (
+    id_incidente        INTEGER NOT NULL REFERENCES incidentes(id) ON DELETE CASCADE,
+    id_control_escolar  TEXT NOT NULL REFERENCES estudiantes(id_control_escolar),
+    acuse_tutor         INTEGER REFERENCES tutores(id_tutor),
+    acuse_en            TEXT,
+    PRIMARY KEY (id_incidente, id_control_escolar)
+);
+
+CREATE INDEX IF NOT EXISTS idx_incidentes_estudiantes_alumno ON incidentes_estudiantes(id_control_escolar);
+
+-- Evidencias del incidente (mismo almacenamiento que los justificantes)
+CREATE TABLE IF NOT EXISTS incidentes_evidencias (
+    id_incidente    INTEGER NOT NULL REFERENCES incidentes(id) ON DELETE CASCADE,
+    evidencia_id    INTEGER NOT NULL REFERENCES evidencias(id),
+    PRIMARY KEY (id_incidente, evidencia_id)
+);
+
+-- Seguimiento: citatorios, amonestaciones, canalizaciones, cierre...
+CREATE TABLE IF NOT EXISTS acciones_incidente (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_incidente    INTEGER NOT NULL REFERENCES incidentes(id) ON DELETE CASCADE,
+    accion          TEXT NOT NULL,      -- ver constants::INCIDENT_ACTIONS
+    notas           TEXT,
+    registrado_por  INTEGER NOT NULL REFERENCES usuarios(id_usuario),
+    registrado_en   TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_acciones_incidente ON acciones_incidente(id_incidente);
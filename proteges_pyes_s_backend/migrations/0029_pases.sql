@@ -0,0 +1,18 @@
+-- Pases de salida del salon: enfermeria, direccion, sanitario, salida anticipada...
+CREATE TABLE IF NOT EXISTS pases (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_control_escolar  TEXT NOT NULL REFERENCES estudiantes(id_control_escolar),
+    destino             TEXT NOT NULL,      -- ver constants::HALL_PASS_DESTINATIONS
+    motivo              TEXT,
+    salon_origen        TEXT,
+    emitido_por         INTEGER NOT NULL REFERENCES usuarios(id_usuario),
+    emitido_en          TEXT NOT NULL,
+    vence_en            TEXT NOT NULL,
+    cerrado_en          TEXT,               -- NULL mientras el alumno no regresa
+    cerrado_por         INTEGER REFERENCES usuarios(id_usuario),
+    cierre              TEXT CHECK (cierre IN ('REGRESO', 'SALIDA_PLANTEL'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_pases_alumno ON pases(id_control_escolar, emitido_en);
+-- un solo pase abierto por alumno
+CREATE UNIQUE INDEX IF NOT EXISTS idx_pases_abiertos ON pases(id_control_escolar) WHERE cerrado_en IS NULL;
@@ -0,0 +1,12 @@
+-- Datos academicos del grupo, su tutor y el archivado al terminar el ciclo
+ALTER TABLE grupos ADD COLUMN grado INTEGER;
+ALTER TABLE grupos ADD COLUMN semestre INTEGER;
+ALTER TABLE grupos ADD COLUMN id_tutor INTEGER REFERENCES usuarios(id_usuario);
+ALTER TABLE grupos ADD COLUMN archivado BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE grupos ADD COLUMN archivado_en TEXT;
+
+-- la nomenclatura empieza con el semestre (5APM -> quinto semestre, tercer grado)
+UPDATE grupos
+SET semestre = CAST(substr(id_nomenclatura, 1, 1) AS INTEGER),
+    grado = (CAST(substr(id_nomenclatura, 1, 1) AS INTEGER) + 1) / 2
+WHERE substr(id_nomenclatura, 1, 1) BETWEEN '1' AND '9';
@@ -0,0 +1,14 @@
+-- Cambios de estatus de inscripcion con fecha efectiva; sin registros el alumno esta ACTIVO.
+-- El estatus en una fecha es el del ultimo cambio con `desde` <= esa fecha, asi las bajas
+-- no borran historial y los reportes de fechas pasadas ven a quien estaba inscrito entonces
+CREATE TABLE IF NOT EXISTS historial_inscripcion (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_control_escolar  TEXT NOT NULL REFERENCES estudiantes(id_control_escolar) ON DELETE CASCADE,
+    estatus             TEXT NOT NULL CHECK (estatus IN ('ACTIVO', 'BAJA', 'EGRESADO', 'SUSPENDIDO')),
+    desde               TEXT NOT NULL,      -- YYYY-MM-DD
+    motivo              TEXT,
+    registrado_por      INTEGER NOT NULL REFERENCES usuarios(id_usuario),
+    registrado_en       TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_historial_inscripcion_alumno ON historial_inscripcion(id_control_escolar, desde);
@@ -0,0 +1,34 @@
+-- Cada asistencia guarda el grupo y el periodo en que ocurrio, asi la promocion
+-- de semestre no mueve el historial al grupo nuevo del alumno
+ALTER TABLE asistencias ADD COLUMN grupo TEXT REFERENCES grupos(id_nomenclatura);
+ALTER TABLE asistencias ADD COLUMN id_periodo INTEGER REFERENCES periodos_escolares(id);
+
+-- antes de esta migracion nadie habia cambiado de grupo por promocion
+UPDATE asistencias
+SET grupo = (SELECT e.grupo FROM estudiantes e WHERE e.id_control_escolar = asistencias.id_control_escolar),
+    id_periodo = (
+        SELECT p.id FROM periodos_escolares p
+        WHERE substr(asistencias.fecha_asistencia, 1, 10) BETWEEN p.fecha_inicio AND p.fecha_fin
+    );
+
+CREATE INDEX IF NOT EXISTS idx_asistencias_periodo ON asistencias(id_periodo, grupo);
+
+-- Promociones de semestre; una por periodo destino para no promover dos veces
+CREATE TABLE IF NOT EXISTS promociones (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_periodo          INTEGER NOT NULL UNIQUE REFERENCES periodos_escolares(id),
+    ejecutado_por       INTEGER NOT NULL REFERENCES usuarios(id_usuario),
+    ejecutado_en        TEXT NOT NULL
+);
+
+-- Lo que paso con cada alumno: PROMOVIDO, REPITE o EGRESADO
+CREATE TABLE IF NOT EXISTS promociones_alumnos (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    id_promocion        INTEGER NOT NULL REFERENCES promociones(id),
+    id_control_escolar  TEXT NOT NULL REFERENCES estudiantes(id_control_escolar) ON DELETE CASCADE,
+    grupo_anterior      TEXT NOT NULL REFERENCES grupos(id_nomenclatura),
+    grupo_nuevo         TEXT REFERENCES grupos(id_nomenclatura),
+    resultado           TEXT NOT NULL CHECK (resultado IN ('PROMOVIDO', 'REPITE', 'EGRESADO'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_promociones_alumnos_alumno ON promociones_alumnos(id_control_escolar);
@@ -0,0 +1,11 @@
+-- Sesiones del personal (token Bearer del login; se guarda solo su firma). Los permisos
+-- por rol se revisan contra el usuario de la sesion y no contra un id enviado por el cliente
+CREATE TABLE IF NOT EXISTS sesiones_usuarios (
+    token_hash      TEXT PRIMARY KEY,
+    id_usuario      INTEGER NOT NULL REFERENCES usuarios(id_usuario) ON DELETE CASCADE,
+    creado_en       INTEGER NOT NULL,
+    expira_en       INTEGER NOT NULL,
+    revocada        BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX IF NOT EXISTS idx_sesiones_usuarios_usuario ON sesiones_usuarios(id_usuario);
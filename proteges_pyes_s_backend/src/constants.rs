@@ -1 +1,135 @@
 pub const ROLES: [&str; 5] = ["Docente", "Prefecto", "Doctor", "Director", "Operador"];
+
+// roles que dan de alta usuarios del personal
+pub const USER_ADMIN_ROLES: [&str; 2] = ["Director", "Operador"];
+
+// roles que dan de alta alumnos y editan sus datos y su grupo
+pub const STUDENT_ADMIN_ROLES: [&str; 2] = ["Director", "Operador"];
+
+// roles que imprimen credenciales (el QR sirve para registrar entradas)
+pub const CARD_ROLES: [&str; 2] = ["Director", "Operador"];
+
+// roles de administracion escolar: horarios, calendario y reglas de la jornada
+pub const SCHOOL_ADMIN_ROLES: [&str; 2] = ["Director", "Operador"];
+
+// turnos con reglas de jornada; GENERAL aplica a los grupos sin turno
+pub const SHIFTS: [&str; 3] = ["GENERAL", "MATUTINO", "VESPERTINO"];
+
+// usuario con el que se registran las acciones automaticas del sistema
+pub const SYSTEM_USER_ID: i64 = 0;
+
+// roles del personal que pueden consultar evidencias de justificantes (documentos medicos)
+pub const EVIDENCE_VIEWER_ROLES: [&str; 4] = ["Director", "Operador", "Prefecto", "Doctor"];
+
+// parentesco del tutor con el estudiante
+pub const RELATIONSHIPS: [&str; 6] = [
+    "MADRE",
+    "PADRE",
+    "TUTOR_LEGAL",
+    "FAMILIAR",
+    "OTRO",
+    "NO_ESPECIFICADO",
+];
+
+// roles que administran tutores: datos de contacto, vinculos y restricciones de custodia
+pub const GUARDIAN_ADMIN_ROLES: [&str; 3] = ["Director", "Operador", "Prefecto"];
+
+// roles del personal que pueden entregar alumnos a sus tutores o personas autorizadas
+pub const RELEASE_ROLES: [&str; 3] = ["Director", "Operador", "Prefecto"];
+
+// estado de un alumno durante una emergencia; FALTANTE es el unico sin resolver
+pub const EMERGENCY_STUDENT_STATUSES: [&str; 4] =
+    ["LOCALIZADO", "LESIONADO", "ENTREGADO", "FALTANTE"];
+
+// roles que registran el estado de los alumnos durante una emergencia (el docente reporta su salon)
+pub const EMERGENCY_ROLL_CALL_ROLES: [&str; 5] =
+    ["Director", "Operador", "Prefecto", "Docente", "Doctor"];
+
+// roles que activan, pasan a la reunificacion y cierran una emergencia; tambien etiquetan
+// las sesiones y consultan sus reportes
+pub const EMERGENCY_ADMIN_ROLES: [&str; 3] = ["Director", "Operador", "Prefecto"];
+
+// tipos de emergencia; los SIMULACRO_* entran en el comparativo de simulacros
+pub const EMERGENCY_KINDS: [&str; 4] = [
+    "SIMULACRO_INCENDIO",
+    "SIMULACRO_SISMO",
+    "EVENTO_REAL",
+    "CONFINAMIENTO",
+];
+
+// protocolos de emergencia: evacuar al punto de reunion o permanecer dentro del plantel
+pub const EMERGENCY_PROTOCOLS: [&str; 3] = ["EVACUACION", "CONFINAMIENTO", "RESGUARDO"];
+
+// roles que configuran zonas, puntos de reunion y el punto de cada grupo
+pub const ASSEMBLY_ADMIN_ROLES: [&str; 2] = ["Director", "Operador"];
+
+// estado del personal y los visitantes durante una emergencia; RETIRADO: registro su salida
+pub const EMERGENCY_PERSON_STATUSES: [&str; 4] =
+    ["LOCALIZADO", "LESIONADO", "FALTANTE", "RETIRADO"];
+
+// roles que pueden consultar el resumen medico para los cuerpos de emergencia
+pub const MEDICAL_SUMMARY_ROLES: [&str; 2] = ["Doctor", "Director"];
+
+// personal medico: unico con acceso al expediente y a la bitacora de enfermeria
+pub const MEDICAL_STAFF_ROLES: [&str; 1] = ["Doctor"];
+
+// tipos de antecedente del expediente medico
+pub const MEDICAL_ITEM_KINDS: [&str; 3] = ["ALERGIA", "CONDICION", "MEDICAMENTO"];
+
+pub const ALLERGY_SEVERITIES: [&str; 3] = ["LEVE", "MODERADA", "GRAVE"];
+
+// roles que levantan reportes de incidentes (el docente que lo presencio tambien)
+pub const INCIDENT_REPORTER_ROLES: [&str; 3] = ["Prefecto", "Director", "Docente"];
+
+// roles que consultan el historial disciplinario y registran el seguimiento
+pub const INCIDENT_STAFF_ROLES: [&str; 2] = ["Prefecto", "Director"];
+
+pub const INCIDENT_CATEGORIES: [&str; 7] = [
+    "CONDUCTA",
+    "AGRESION",
+    "ACOSO",
+    "DANO_MATERIAL",
+    "SUSTANCIAS",
+    "UNIFORME",
+    "OTRO",
+];
+
+pub const INCIDENT_SEVERITIES: [&str; 3] = ["LEVE", "MODERADA", "GRAVE"];
+
+// acciones de seguimiento; CIERRE cierra el incidente
+pub const INCIDENT_ACTIONS: [&str; 6] = [
+    "AMONESTACION",
+    "CITATORIO",
+    "SUSPENSION",
+    "CANALIZACION",
+    "SEGUIMIENTO",
+    "CIERRE",
+];
+
+// roles que emiten y cierran pases de salida del salon
+pub const HALL_PASS_ROLES: [&str; 2] = ["Docente", "Prefecto"];
+
+pub const HALL_PASS_DESTINATIONS: [&str; 6] = [
+    "ENFERMERIA",
+    "DIRECCION",
+    "ORIENTACION",
+    "SANITARIO",
+    "SALIDA_ANTICIPADA",
+    "OTRO",
+];
+
+// vigencia de un pase en minutos: por omision y maxima
+pub const HALL_PASS_DEFAULT_MINUTES: i64 = 15;
+pub const HALL_PASS_MAX_MINUTES: i64 = 240;
+
+// roles que dan de alta, editan y archivan grupos
+pub const GROUP_ADMIN_ROLES: [&str; 2] = ["Director", "Operador"];
+
+// semestres del bachillerato; el grado se deriva del semestre
+pub const MAX_SEMESTER: i64 = 6;
+
+// estatus de inscripcion; solo ACTIVO cuenta en listas, simulacros y faltas
+pub const ENROLLMENT_STATUSES: [&str; 4] = ["ACTIVO", "BAJA", "EGRESADO", "SUSPENDIDO"];
+
+// roles que cambian el estatus de inscripcion y eliminan alumnos
+pub const ENROLLMENT_ADMIN_ROLES: [&str; 2] = ["Director", "Operador"];
@@ -2,6 +2,8 @@ use bcrypt::{hash, DEFAULT_COST};
 use sqlx::{Pool, Row, Sqlite};
 use tracing::info;
 
+use crate::utils::guardians::sync_guardian_phones;
+
 // inicializar la base de datos con migraciones
 pub async fn init_db(pool: &Pool<Sqlite>) -> Result<(), sqlx::Error> {
     // estan de 0001...0002...etc. para que se creen en orden
@@ -67,21 +69,24 @@ pub async fn init_groups(pool: &Pool<Sqlite>) -> Result<(), sqlx::Error> {
                 "5APM",
                 "Programacion",
                 "Grupo quinto de programación turno matuto",
+                "MATUTINO",
             ),
             (
                 "5AEM",
                 "Electricidad",
                 "Grupo quinto de electricidad turno matuto",
+                "MATUTINO",
             ),
         ];
         // for para iterar en cada grupo y crearlo
-        for (nomenclature, major, description) in groups {
+        for (nomenclature, major, description, shift) in groups {
             sqlx::query(
-                "INSERT INTO grupos (id_nomenclatura, especialidad, descripcion) VALUES (?, ?, ?)",
+                "INSERT INTO grupos (id_nomenclatura, especialidad, descripcion, turno, semestre, grado) VALUES (?, ?, ?, ?, 5, 3)",
             )
             .bind(nomenclature)
             .bind(major)
             .bind(description)
+            .bind(shift)
             .execute(pool)
             .await?;
             info!("Grupo '{}' creado", nomenclature);
@@ -256,6 +261,13 @@ pub async fn init_students(pool: &Pool<Sqlite>) -> Result<(), sqlx::Error> {
             .bind(card_uid)
             .execute(pool)
             .await?;
+            sync_guardian_phones(
+                pool,
+                id,
+                Some(primary_guardian_phone),
+                Some(secondary_guardian_phone),
+            )
+            .await;
             info!("Estudiante '{}' creado", names);
         }
         info!("{} estudiantes falsos inicializados.", student_count);
@@ -0,0 +1,255 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    constants,
+    models::assembly::{AssemblyPoint, AssemblyRequest, GroupAssemblyPointRequest, Zone},
+    utils::auth::StaffSession,
+};
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    if e.to_string().contains("UNIQUE constraint failed") {
+        return (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "Ya existe un registro con ese nombre"})),
+        );
+    }
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": format!("Error de base de datos: {}", e)})),
+    )
+}
+
+fn not_found(message: &str) -> ApiError {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({"error": message})),
+    )
+}
+
+fn require_admin(session: &StaffSession) -> Result<(), ApiError> {
+    if session.has_role(&constants::ASSEMBLY_ADMIN_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(
+                serde_json::json!({"error": "No tienes permiso para configurar los puntos de reunión"}),
+            ),
+        ))
+    }
+}
+
+fn validate_name(payload: &AssemblyRequest) -> Result<String, ApiError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "El nombre es obligatorio"})),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn blank_to_none(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+// the zone of a point must exist and be active
+async fn require_zone(pool: &Pool<Sqlite>, zone_id: Option<i64>) -> Result<i64, ApiError> {
+    let zone_id = zone_id.ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "La zona es obligatoria"})),
+        )
+    })?;
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM zonas WHERE id = ? AND activa)")
+            .bind(zone_id)
+            .fetch_one(pool)
+            .await
+            .map_err(db_error)?;
+    if !exists {
+        return Err(not_found("Zona no encontrada"));
+    }
+    Ok(zone_id)
+}
+
+const POINT_SELECT: &str =
+    "SELECT p.*, z.nombre AS zone_name FROM puntos_reunion p JOIN zonas z ON z.id = p.id_zona";
+
+pub async fn get_zones(State(pool): State<Pool<Sqlite>>) -> Result<Json<Vec<Zone>>, ApiError> {
+    let zones = sqlx::query_as::<_, Zone>("SELECT * FROM zonas ORDER BY nombre")
+        .fetch_all(&pool)
+        .await
+        .map_err(db_error)?;
+    Ok(Json(zones))
+}
+
+pub async fn create_zone(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<AssemblyRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin(&session)?;
+    let name = validate_name(&payload)?;
+
+    let result = sqlx::query("INSERT INTO zonas (nombre, descripcion, activa) VALUES (?, ?, ?)")
+        .bind(&name)
+        .bind(blank_to_none(&payload.description))
+        .bind(payload.active.unwrap_or(true))
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Zona creada correctamente",
+        "id": result.last_insert_rowid()
+    })))
+}
+
+pub async fn update_zone(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<AssemblyRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin(&session)?;
+    let name = validate_name(&payload)?;
+
+    let result = sqlx::query(
+        "UPDATE zonas SET nombre = ?, descripcion = ?, activa = COALESCE(?, activa) WHERE id = ?",
+    )
+    .bind(&name)
+    .bind(blank_to_none(&payload.description))
+    .bind(payload.active)
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(not_found("Zona no encontrada"));
+    }
+
+    Ok(Json(
+        serde_json::json!({"message": "Zona actualizada correctamente"}),
+    ))
+}
+
+pub async fn get_assembly_points(
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Vec<AssemblyPoint>>, ApiError> {
+    let points = sqlx::query_as::<_, AssemblyPoint>(&format!(
+        "{} ORDER BY z.nombre, p.nombre",
+        POINT_SELECT
+    ))
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+    Ok(Json(points))
+}
+
+pub async fn create_assembly_point(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<AssemblyRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin(&session)?;
+    let name = validate_name(&payload)?;
+    let zone_id = require_zone(&pool, payload.zone_id).await?;
+
+    let result = sqlx::query(
+        "INSERT INTO puntos_reunion (nombre, id_zona, descripcion, activo) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&name)
+    .bind(zone_id)
+    .bind(blank_to_none(&payload.description))
+    .bind(payload.active.unwrap_or(true))
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Punto de reunión creado correctamente",
+        "id": result.last_insert_rowid()
+    })))
+}
+
+pub async fn update_assembly_point(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<AssemblyRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin(&session)?;
+    let name = validate_name(&payload)?;
+    let zone_id = require_zone(&pool, payload.zone_id).await?;
+
+    let result = sqlx::query(
+        "UPDATE puntos_reunion SET nombre = ?, id_zona = ?, descripcion = ?, activo = COALESCE(?, activo) WHERE id = ?",
+    )
+    .bind(&name)
+    .bind(zone_id)
+    .bind(blank_to_none(&payload.description))
+    .bind(payload.active)
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(not_found("Punto de reunión no encontrado"));
+    }
+
+    Ok(Json(
+        serde_json::json!({"message": "Punto de reunión actualizado correctamente"}),
+    ))
+}
+
+// expected assembly point of a group
+pub async fn set_group_assembly_point(
+    session: StaffSession,
+    Path(group_id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<GroupAssemblyPointRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_admin(&session)?;
+
+    if let Some(point_id) = payload.assembly_point_id {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM puntos_reunion WHERE id = ? AND activo)",
+        )
+        .bind(point_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(db_error)?;
+        if !exists {
+            return Err(not_found("Punto de reunión no encontrado"));
+        }
+    }
+
+    let result = sqlx::query("UPDATE grupos SET punto_reunion = ? WHERE id_nomenclatura = ?")
+        .bind(payload.assembly_point_id)
+        .bind(&group_id)
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(not_found("Grupo no encontrado"));
+    }
+
+    Ok(Json(serde_json::json!({
+        "message": "Punto de reunión del grupo actualizado",
+        "group": group_id,
+        "assembly_point_id": payload.assembly_point_id,
+    })))
+}
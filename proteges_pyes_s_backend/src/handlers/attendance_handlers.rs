@@ -1,51 +1,97 @@
 use crate::{
-    models::attendance::{AttendanceHistoryResponse, AttendanceRecord, CreateAttendanceRequest},
+    constants,
+    models::attendance::{
+        AttendanceHistoryResponse, AttendanceReportQuery, CreateAttendanceRequest,
+        RunAbsencesRequest, SchoolDayRule, UpdateSchoolDayRuleRequest,
+    },
     state::AppState,
-    utils::notifications::send_push_notification,
+    utils::{
+        absences::close_school_day,
+        attendance_summary::{attendance_by_day, summarize},
+        auth::StaffSession,
+        calendar::{find_term, last_school_days, parse_date, SchoolCalendar, TERM_FOR_DATE},
+        enrollment::status_on,
+        hall_passes::close_open_pass,
+        justifications::approved_justification,
+        notifications::{send_push_notification, NotificationKind},
+        schedules::{current_schedule_for_group, parse_time},
+        school_day::{classify, rule_for_group},
+        students::resolve_student_code,
+    },
 };
-use axum::{extract::State, http::StatusCode, Json};
-use chrono::Local;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Local, NaiveDate};
 use sqlx::Row;
 
 pub async fn register_attendance(
     State(state): State<AppState>,
     Json(payload): Json<CreateAttendanceRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    let now = Local::now().to_rfc3339();
+    let now_local = Local::now();
+    let now = now_local.to_rfc3339();
 
     // Resolve the real Student ID (id_control_escolar) and get their grupo
-    // Check if the input matches id_control_escolar OR card_uid
-    let student_row: Option<(String, String)> = sqlx::query_as(
-        "SELECT id_control_escolar, grupo FROM estudiantes WHERE id_control_escolar = ? OR card_uid = ?"
+    // The input may be the id_control_escolar, the card_uid or the card QR code
+    let resolved_student_id = resolve_student_code(&state.db, &payload.student_id)
+        .await
+        .map_err(|e| (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        ))?
+        .ok_or_else(|| (
+            StatusCode::NOT_FOUND,
+            Json(
+                serde_json::json!({"error": format!("Student not found for ID/UID: {}", payload.student_id)}),
+            ),
+        ))?;
+
+    // Withdrawn, graduated or suspended students don't get gate records
+    let enrollment = status_on(
+        &state.db,
+        &resolved_student_id,
+        &now_local.format("%Y-%m-%d").to_string(),
     )
-    .bind(&payload.student_id)
-    .bind(&payload.student_id)
-    .fetch_optional(&state.db)
     .await
-    .map_err(|e| (
-        StatusCode::INTERNAL_SERVER_ERROR,
-        Json(serde_json::json!({"error": e.to_string()})),
-    ))?;
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+    })?;
+    if enrollment != "ACTIVO" {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "El estudiante no está inscrito",
+                "enrollment_status": enrollment,
+            })),
+        ));
+    }
 
-    let (resolved_student_id, student_grupo) = match student_row {
-        Some(row) => row,
-        None => {
-            return Err((
-                StatusCode::NOT_FOUND,
-                Json(
-                    serde_json::json!({"error": format!("Student not found for ID/UID: {}", payload.student_id)}),
-                ),
-            ));
-        }
-    };
+    let student_grupo: String =
+        sqlx::query_scalar("SELECT grupo FROM estudiantes WHERE id_control_escolar = ?")
+            .bind(&resolved_student_id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": e.to_string()})),
+                )
+            })?;
 
-    // Count records for this student today to determine Entry vs Exit
-    let today_start = Local::now().format("%Y-%m-%d").to_string();
+    // Count gate records for this student today to determine Entry vs Exit
+    // (class roll-call records have no tipo_registro and don't count here)
+    let today_start = now_local.format("%Y-%m-%d").to_string();
     let count: i64 = sqlx::query(
-        "SELECT count(*) FROM asistencias WHERE id_control_escolar = ? AND substr(fecha_asistencia, 1, 10) = ?"
+        "SELECT count(*) FROM asistencias WHERE id_control_escolar = ? AND substr(fecha_asistencia, 1, 10) = ? AND tipo_registro IN ('ENTRADA', 'SALIDA')"
     )
     .bind(&resolved_student_id)
-    .bind(today_start)
+    .bind(&today_start)
     .fetch_one(&state.db)
     .await
     .map_err(|e| (
@@ -56,17 +102,53 @@ pub async fn register_attendance(
 
     let status_type = if count % 2 == 0 { "ENTRADA" } else { "SALIDA" };
 
-    // Use the student's grupo as the classroom
-    let final_classroom = student_grupo.clone();
+    // Use the classroom sent by the reader; otherwise the classroom of the group's
+    // current class period, falling back to the student's grupo
+    let current_period = current_schedule_for_group(&state.db, &student_grupo, now_local)
+        .await
+        .unwrap_or(None);
 
-    let result = sqlx::query(
-        "INSERT INTO asistencias (id_control_escolar, registrado_por, fecha_asistencia, salon_clase, presente) VALUES (?, ?, ?, ?, ?)"
-    )
+    // Classify against the school-day rules of the group's turno
+    let (classification, minutes_off) = match rule_for_group(&state.db, &student_grupo)
+        .await
+        .unwrap_or(None)
+    {
+        Some(rule) => classify(status_type, now_local.time(), &rule),
+        None => ("A_TIEMPO", 0),
+    };
+    let final_classroom = if !payload.classroom.trim().is_empty() {
+        payload.classroom.trim().to_string()
+    } else if let Some(period) = &current_period {
+        period.classroom.clone()
+    } else {
+        student_grupo.clone()
+    };
+
+    // A late arrival on a day that already has an approved justificante is born justified
+    let justification = if classification == "RETARDO" {
+        approved_justification(&state.db, &resolved_student_id, &today_start)
+            .await
+            .unwrap_or(None)
+    } else {
+        None
+    };
+
+    // The group and term are stored so later promotions don't move this record
+    let result = sqlx::query(&format!(
+        "INSERT INTO asistencias (id_control_escolar, registrado_por, fecha_asistencia, salon_clase, presente, tipo_registro, clasificacion, justificacion, id_justificante, grupo, id_periodo) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, {})",
+        TERM_FOR_DATE
+    ))
     .bind(&resolved_student_id)
     .bind(payload.user_id)
     .bind(now)
     .bind(&final_classroom)
     .bind(payload.present)
+    .bind(status_type)
+    .bind(classification)
+    .bind(justification.as_ref().map(|(_, reason)| reason.clone()))
+    .bind(justification.as_ref().map(|(id, _)| *id))
+    .bind(&student_grupo)
+    .bind(&today_start)
     .execute(&state.db)
     .await
     .map_err(|e| {
@@ -78,6 +160,20 @@ pub async fn register_attendance(
 
     let id_asistencia = result.last_insert_rowid();
 
+    // Leaving through the gate ends any hall pass (early exits end here)
+    if status_type == "SALIDA" {
+        if let Err(e) = close_open_pass(
+            &state.db,
+            &resolved_student_id,
+            payload.user_id,
+            "SALIDA_PLANTEL",
+        )
+        .await
+        {
+            eprintln!("Error closing hall pass: {:?}", e);
+        }
+    }
+
     // 4. Send Push Notification (async, don't block response)
     let db_pool_clone = state.db.clone();
     let student_id_clone = resolved_student_id.clone();
@@ -92,18 +188,42 @@ pub async fn register_attendance(
     // Better capture the String variant.
     let type_string = type_str.to_string();
 
+    let classification_clone = classification.to_string();
+
     tokio::spawn(async move {
         // Need to pass the pool, but it must be thread safe (sqlite pool is).
-        let title = "Registro de Asistencia";
-        let body = format!(
-            "Tu hijo/a registró {} en el salón {} a las {}",
-            type_string,
-            classroom_clone,
-            chrono::Local::now().format("%H:%M")
-        );
+        let time = chrono::Local::now().format("%H:%M");
+        // late arrivals and early departures always reach the guardian; plain
+        // entries/exits only if they asked for them
+        let (kind, title, body) = match classification_clone.as_str() {
+            "RETARDO" => (
+                NotificationKind::Important,
+                "Retardo en la Entrada",
+                format!(
+                    "Tu hijo/a llegó con {} minutos de retraso, registró entrada en el salón {} a las {}",
+                    minutes_off, classroom_clone, time
+                ),
+            ),
+            "SALIDA_ANTICIPADA" => (
+                NotificationKind::Important,
+                "Salida Anticipada",
+                format!(
+                    "Tu hijo/a registró salida {} minutos antes del fin de la jornada en el salón {} a las {}",
+                    minutes_off, classroom_clone, time
+                ),
+            ),
+            _ => (
+                NotificationKind::GateRecord,
+                "Registro de Asistencia",
+                format!(
+                    "Tu hijo/a registró {} en el salón {} a las {}",
+                    type_string, classroom_clone, time
+                ),
+            ),
+        };
 
         if let Err(e) =
-            send_push_notification(&db_pool_clone, &student_id_clone, title, &body).await
+            send_push_notification(&db_pool_clone, &student_id_clone, kind, title, &body).await
         {
             println!("Error enviando notificación: {}", e);
         }
@@ -112,7 +232,10 @@ pub async fn register_attendance(
     Ok(Json(serde_json::json!({
         "message": "Asistencia registrada",
         "type": status_type,
+        "classification": classification,
+        "minutes_off": minutes_off,
         "classroom": final_classroom,
+        "subject": current_period.map(|period| period.subject),
         "count": count + 1,
         "id_asistencia": id_asistencia
     })))
@@ -129,7 +252,11 @@ pub async fn get_attendance_history(
             (e.nombres || ' ' || e.apellido_paterno) as nombre_completo,
             a.fecha_asistencia,
             a.salon_clase,
-            a.presente
+            a.presente,
+            a.tipo_registro,
+            a.clasificacion,
+            a.justificacion,
+            a.id_justificante
         FROM asistencias a
         JOIN estudiantes e ON a.id_control_escolar = e.id_control_escolar
         ORDER BY a.fecha_asistencia DESC
@@ -147,3 +274,283 @@ pub async fn get_attendance_history(
 
     Ok(Json(history))
 }
+
+pub async fn get_school_day_rules(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<SchoolDayRule>>, (StatusCode, Json<serde_json::Value>)> {
+    let rules = sqlx::query_as::<_, SchoolDayRule>("SELECT * FROM reglas_jornada ORDER BY turno")
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+        })?;
+
+    Ok(Json(rules))
+}
+
+// school-day rules and manual closings change who gets marked absent
+fn require_school_admin(
+    session: &StaffSession,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if !session.has_role(&constants::SCHOOL_ADMIN_ROLES) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "No tienes permiso para modificar la jornada"})),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn update_school_day_rule(
+    State(state): State<AppState>,
+    Path(shift): Path<String>,
+    session: StaffSession,
+    Json(payload): Json<UpdateSchoolDayRuleRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_school_admin(&session)?;
+    let shift = shift.to_uppercase();
+    if !constants::SHIFTS.contains(&shift.as_str()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Turno inválido"})),
+        ));
+    }
+
+    match (
+        parse_time(&payload.entry_time),
+        parse_time(&payload.dismissal_time),
+        parse_time(&payload.absence_cutoff),
+    ) {
+        (Some(entry), Some(dismissal), Some(cutoff)) if entry < dismissal && entry < cutoff => {}
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(
+                    serde_json::json!({"error": "Horas inválidas, use HH:MM con la entrada antes de la salida y del corte de faltas"}),
+                ),
+            ))
+        }
+    }
+    if !(0..=120).contains(&payload.grace_minutes) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "La tolerancia debe estar entre 0 y 120 minutos"})),
+        ));
+    }
+
+    sqlx::query(
+        "INSERT INTO reglas_jornada (turno, hora_entrada, minutos_tolerancia, hora_salida, hora_corte_faltas) VALUES (?, ?, ?, ?, ?) ON CONFLICT(turno) DO UPDATE SET hora_entrada = excluded.hora_entrada, minutos_tolerancia = excluded.minutos_tolerancia, hora_salida = excluded.hora_salida, hora_corte_faltas = excluded.hora_corte_faltas",
+    )
+    .bind(&shift)
+    .bind(&payload.entry_time)
+    .bind(payload.grace_minutes)
+    .bind(&payload.dismissal_time)
+    .bind(&payload.absence_cutoff)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Regla de jornada actualizada",
+        "shift": shift
+    })))
+}
+
+// cerrar manualmente la jornada de una fecha pasada, o de hoy para los turnos despues del corte
+pub async fn run_absences(
+    State(state): State<AppState>,
+    session: StaffSession,
+    Json(payload): Json<RunAbsencesRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_school_admin(&session)?;
+
+    let today = Local::now().date_naive();
+    let date = match payload.date.as_deref() {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "Fecha inválida, use YYYY-MM-DD"})),
+            )
+        })?,
+        None => today,
+    };
+    if date > today {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "No se puede cerrar una jornada futura"})),
+        ));
+    }
+
+    let closures = close_school_day(&state.db, date).await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+    })?;
+    // hoy solo se cierran los turnos que ya pasaron su hora de corte
+    if closures.is_empty() && date == today {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(
+                serde_json::json!({"error": "Ningún turno ha llegado a su hora de corte de faltas"}),
+            ),
+        ));
+    }
+
+    let total: i64 = closures.iter().map(|closure| closure.absences).sum();
+    Ok(Json(serde_json::json!({
+        "message": "Jornada cerrada",
+        "date": date.format("%Y-%m-%d").to_string(),
+        "absences": total,
+        "shifts": closures
+            .iter()
+            .map(|closure| serde_json::json!({"shift": closure.shift, "absences": closure.absences}))
+            .collect::<Vec<_>>()
+    })))
+}
+
+// asistencia del alumno por dia con clases; por defecto los ultimos 30 dias con clases
+pub async fn get_student_attendance_report(
+    State(state): State<AppState>,
+    Path(student_id): Path<String>,
+    Query(query): Query<AttendanceReportQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let group: String =
+        sqlx::query_scalar("SELECT grupo FROM estudiantes WHERE id_control_escolar = ?")
+            .bind(&student_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": e.to_string()})),
+                )
+            })?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+                )
+            })?;
+
+    // A past term is reported with the group recorded on its attendance, not the current one
+    let term = match query.term_id {
+        Some(term_id) => Some(
+            find_term(&state.db, term_id)
+                .await
+                .map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(serde_json::json!({"error": e.to_string()})),
+                    )
+                })?
+                .ok_or_else(|| {
+                    (
+                        StatusCode::NOT_FOUND,
+                        Json(serde_json::json!({"error": "Periodo no encontrado"})),
+                    )
+                })?,
+        ),
+        None => None,
+    };
+    let group = match &term {
+        Some(term) => sqlx::query_scalar(
+            "SELECT grupo FROM asistencias WHERE id_control_escolar = ? AND id_periodo = ? AND grupo IS NOT NULL ORDER BY fecha_asistencia DESC LIMIT 1",
+        )
+        .bind(&student_id)
+        .bind(term.id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+        })?
+        .unwrap_or(group),
+        None => group,
+    };
+
+    let days = match (&term, query.from.as_deref(), query.to.as_deref()) {
+        (Some(term), _, _) => {
+            let today = Local::now().date_naive();
+            let (Some(from), Some(to)) = (parse_date(&term.start_date), parse_date(&term.end_date))
+            else {
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": "Fechas del periodo inválidas"})),
+                ));
+            };
+            let to = to.min(today);
+            SchoolCalendar::load(&state.db, from, to)
+                .await
+                .map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(serde_json::json!({"error": e.to_string()})),
+                    )
+                })?
+                .school_days(from, to, Some(&group))
+        }
+        (None, None, None) => {
+            last_school_days(&state.db, Local::now().date_naive(), 30, Some(&group))
+                .await
+                .map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(serde_json::json!({"error": e.to_string()})),
+                    )
+                })?
+        }
+        (None, from, to) => {
+            let today = Local::now().date_naive();
+            let from = from.map(parse_date).unwrap_or(Some(today));
+            let to = to.map(parse_date).unwrap_or(Some(today));
+            let (from, to) = match (from, to) {
+                (Some(from), Some(to)) if from <= to && (to - from).num_days() <= 366 => (from, to),
+                _ => {
+                    return Err((
+                        StatusCode::BAD_REQUEST,
+                        Json(
+                            serde_json::json!({"error": "Rango inválido, use YYYY-MM-DD y máximo un año"}),
+                        ),
+                    ))
+                }
+            };
+            SchoolCalendar::load(&state.db, from, to)
+                .await
+                .map_err(|e| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(serde_json::json!({"error": e.to_string()})),
+                    )
+                })?
+                .school_days(from, to, Some(&group))
+        }
+    };
+
+    let by_day = attendance_by_day(&state.db, &student_id, &days)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "student_id": student_id,
+        "group": group,
+        "term": term,
+        "summary": summarize(&by_day),
+        "days": by_day
+    })))
+}
@@ -3,7 +3,10 @@ use bcrypt::verify;
 use serde::Deserialize;
 use sqlx::{Pool, Sqlite};
 
-use crate::models::user::User;
+use crate::models::user::{StaffLogin, User};
+use crate::utils::auth::{session_token_hash, StaffSession};
+
+const SESSION_TTL_DAYS: i64 = 30;
 
 #[derive(Deserialize)]
 pub struct LoginRequest {
@@ -14,7 +17,7 @@ pub struct LoginRequest {
 pub async fn login_request(
     State(pool): State<Pool<Sqlite>>,
     Json(payload): Json<LoginRequest>,
-) -> Result<Json<User>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<StaffLogin>, (StatusCode, Json<serde_json::Value>)> {
     println!(
         "DEBUG: Login request received for user: '{}'",
         payload.username
@@ -42,7 +45,7 @@ pub async fn login_request(
                 Ok(is_valid) => {
                     if is_valid {
                         println!("DEBUG: Password correct for user: {}", user.username);
-                        Ok(Json(user))
+                        start_session(&pool, user).await
                     } else {
                         println!("DEBUG: Password incorrect for user: {}", user.username);
                         Err((
@@ -78,3 +81,56 @@ pub async fn login_request(
         }
     }
 }
+
+async fn start_session(
+    pool: &Pool<Sqlite>,
+    user: User,
+) -> Result<Json<StaffLogin>, (StatusCode, Json<serde_json::Value>)> {
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let now = chrono::Utc::now().timestamp();
+    let expires = now + SESSION_TTL_DAYS * 24 * 60 * 60;
+    sqlx::query(
+        "INSERT INTO sesiones_usuarios (token_hash, id_usuario, creado_en, expira_en) VALUES (?, ?, ?, ?)",
+    )
+    .bind(session_token_hash(&token))
+    .bind(user.id)
+    .bind(now)
+    .bind(expires)
+    .execute(pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "No se pudo iniciar la sesión"})),
+        )
+    })?;
+
+    Ok(Json(StaffLogin {
+        user,
+        token,
+        expires_at: chrono::DateTime::from_timestamp(expires, 0)
+            .unwrap_or_default()
+            .to_rfc3339(),
+    }))
+}
+
+pub async fn logout_request(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    sqlx::query("UPDATE sesiones_usuarios SET revocada = TRUE WHERE token_hash = ?")
+        .bind(&session.token_hash)
+        .execute(&pool)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al cerrar la sesión"})),
+            )
+        })?;
+    Ok(Json(serde_json::json!({"message": "Sesión cerrada"})))
+}
@@ -0,0 +1,444 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Local};
+use sqlx::{Pool, Sqlite};
+
+use crate::constants::SCHOOL_ADMIN_ROLES;
+use crate::models::calendar::{
+    CalendarException, CalendarImportQuery, CalendarQuery, CreateCalendarExceptionRequest,
+    DateRangeRequest, SchoolYear, Term,
+};
+use crate::utils::auth::StaffSession;
+use crate::utils::calendar::{parse_date, parse_ical, SchoolCalendar, EXCEPTION_KINDS};
+
+fn require_calendar_admin(
+    session: &StaffSession,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if !session.has_role(&SCHOOL_ADMIN_ROLES) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "No tienes permiso para modificar el calendario"})),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_range(
+    start: &str,
+    end: &str,
+) -> Result<(chrono::NaiveDate, chrono::NaiveDate), (StatusCode, Json<serde_json::Value>)> {
+    match (parse_date(start), parse_date(end)) {
+        (Some(start), Some(end)) if start <= end => Ok((start, end)),
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            Json(
+                serde_json::json!({"error": "Fechas inválidas, use YYYY-MM-DD con inicio antes del fin"}),
+            ),
+        )),
+    }
+}
+
+pub async fn get_school_years(
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let years = sqlx::query_as::<_, SchoolYear>(
+        "SELECT * FROM ciclos_escolares ORDER BY fecha_inicio DESC",
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar el calendario"})),
+        )
+    })?;
+
+    let terms = sqlx::query_as::<_, Term>("SELECT * FROM periodos_escolares ORDER BY fecha_inicio")
+        .fetch_all(&pool)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al consultar el calendario"})),
+            )
+        })?;
+
+    let formatted: Vec<serde_json::Value> = years
+        .into_iter()
+        .map(|year| {
+            let year_terms: Vec<&Term> = terms
+                .iter()
+                .filter(|term| term.school_year_id == year.id)
+                .collect();
+            serde_json::json!({
+                "id": year.id,
+                "name": year.name,
+                "start_date": year.start_date,
+                "end_date": year.end_date,
+                "terms": year_terms
+            })
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!(formatted)))
+}
+
+pub async fn create_school_year(
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+    Json(payload): Json<DateRangeRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_calendar_admin(&session)?;
+    validate_range(&payload.start_date, &payload.end_date)?;
+
+    let result = sqlx::query(
+        "INSERT INTO ciclos_escolares (nombre, fecha_inicio, fecha_fin) VALUES (?, ?, ?)",
+    )
+    .bind(payload.name.trim())
+    .bind(&payload.start_date)
+    .bind(&payload.end_date)
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({"error": "El ciclo escolar ya existe"})),
+            )
+        } else {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "No se pudo crear el ciclo escolar"})),
+            )
+        }
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Ciclo escolar creado correctamente",
+        "id": result.last_insert_rowid()
+    })))
+}
+
+pub async fn create_term(
+    Path(year_id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+    Json(payload): Json<DateRangeRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_calendar_admin(&session)?;
+    validate_range(&payload.start_date, &payload.end_date)?;
+
+    let year = sqlx::query_as::<_, SchoolYear>("SELECT * FROM ciclos_escolares WHERE id = ?")
+        .bind(year_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al consultar el calendario"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Ciclo escolar no encontrado"})),
+            )
+        })?;
+
+    if payload.start_date < year.start_date || payload.end_date > year.end_date {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "El periodo debe estar dentro del ciclo escolar"})),
+        ));
+    }
+
+    let overlapping: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM periodos_escolares WHERE fecha_inicio <= ? AND fecha_fin >= ?)",
+    )
+    .bind(&payload.end_date)
+    .bind(&payload.start_date)
+    .fetch_one(&pool)
+    .await
+    .unwrap_or(false);
+    if overlapping {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "El periodo se empalma con otro existente"})),
+        ));
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO periodos_escolares (id_ciclo, nombre, fecha_inicio, fecha_fin) VALUES (?, ?, ?, ?)",
+    )
+    .bind(year_id)
+    .bind(payload.name.trim())
+    .bind(&payload.start_date)
+    .bind(&payload.end_date)
+    .execute(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "No se pudo crear el periodo"})),
+        )
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Periodo creado correctamente",
+        "id": result.last_insert_rowid()
+    })))
+}
+
+pub async fn get_calendar_exceptions(
+    Query(query): Query<CalendarQuery>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Vec<CalendarException>>, (StatusCode, Json<serde_json::Value>)> {
+    let exceptions = sqlx::query_as::<_, CalendarException>(
+        r#"
+        SELECT * FROM calendario_excepciones
+        WHERE (? IS NULL OR fecha_fin >= ?)
+        AND (? IS NULL OR fecha_inicio <= ?)
+        AND (? IS NULL OR grupo IS NULL OR grupo = ?)
+        ORDER BY fecha_inicio
+        "#,
+    )
+    .bind(&query.from)
+    .bind(&query.from)
+    .bind(&query.to)
+    .bind(&query.to)
+    .bind(&query.group)
+    .bind(&query.group)
+    .fetch_all(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar el calendario"})),
+        )
+    })?;
+
+    Ok(Json(exceptions))
+}
+
+async fn validate_exception_target(
+    pool: &Pool<Sqlite>,
+    kind: &str,
+    group: Option<&str>,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if !EXCEPTION_KINDS.contains(&kind) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(
+                serde_json::json!({"error": "Tipo inválido, use FESTIVO, SUSPENSION o DIA_HABIL"}),
+            ),
+        ));
+    }
+    if let Some(group) = group {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM grupos WHERE id_nomenclatura = ?)")
+                .bind(group)
+                .fetch_one(pool)
+                .await
+                .unwrap_or(false);
+        if !exists {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Grupo no encontrado"})),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub async fn create_calendar_exception(
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+    Json(payload): Json<CreateCalendarExceptionRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_calendar_admin(&session)?;
+    let end_date = payload
+        .end_date
+        .clone()
+        .unwrap_or_else(|| payload.start_date.clone());
+    validate_range(&payload.start_date, &end_date)?;
+    let kind = payload.kind.to_uppercase();
+    validate_exception_target(&pool, &kind, payload.group.as_deref()).await?;
+
+    let result = sqlx::query(
+        "INSERT INTO calendario_excepciones (fecha_inicio, fecha_fin, tipo, grupo, descripcion) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&payload.start_date)
+    .bind(&end_date)
+    .bind(&kind)
+    .bind(&payload.group)
+    .bind(&payload.description)
+    .execute(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "No se pudo registrar el día"})),
+        )
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Día registrado en el calendario",
+        "id": result.last_insert_rowid()
+    })))
+}
+
+pub async fn delete_calendar_exception(
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_calendar_admin(&session)?;
+    let result = sqlx::query("DELETE FROM calendario_excepciones WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al eliminar el día"})),
+            )
+        })?;
+
+    if result.rows_affected() == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Día no encontrado"})),
+        ));
+    }
+
+    Ok(Json(
+        serde_json::json!({"message": "Día eliminado del calendario"}),
+    ))
+}
+
+// importar eventos de un archivo .ics enviado como cuerpo de la peticion;
+// volver a importar el mismo evento (mismo UID) lo reemplaza
+pub async fn import_ical(
+    Query(query): Query<CalendarImportQuery>,
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+    body: String,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_calendar_admin(&session)?;
+    let default_kind = query.kind.as_deref().unwrap_or("FESTIVO").to_uppercase();
+    validate_exception_target(&pool, &default_kind, query.group.as_deref()).await?;
+
+    let events = parse_ical(&body);
+    if events.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "El archivo no contiene eventos VEVENT válidos"})),
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al iniciar la transacción"})),
+        )
+    })?;
+
+    for event in &events {
+        // las suspensiones suelen venir nombradas asi en los calendarios oficiales
+        let kind = if event.summary.to_lowercase().contains("suspensi") {
+            "SUSPENSION"
+        } else {
+            default_kind.as_str()
+        };
+
+        if let Some(uid) = &event.uid {
+            sqlx::query("DELETE FROM calendario_excepciones WHERE uid_ical = ?")
+                .bind(uid)
+                .execute(&mut *tx)
+                .await
+                .map_err(|_| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(serde_json::json!({"error": "Error al importar el calendario"})),
+                    )
+                })?;
+        }
+
+        sqlx::query(
+            "INSERT INTO calendario_excepciones (fecha_inicio, fecha_fin, tipo, grupo, descripcion, uid_ical) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(event.start.format("%Y-%m-%d").to_string())
+        .bind(event.end.format("%Y-%m-%d").to_string())
+        .bind(kind)
+        .bind(&query.group)
+        .bind(&event.summary)
+        .bind(&event.uid)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al importar el calendario"})),
+            )
+        })?;
+    }
+
+    tx.commit().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al guardar el calendario"})),
+        )
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Calendario importado",
+        "imported": events.len()
+    })))
+}
+
+// dias con clases en el rango (por defecto los proximos 30 dias)
+pub async fn get_school_days(
+    Query(query): Query<CalendarQuery>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let today = Local::now().date_naive();
+    let from = query.from.as_deref().map(parse_date).unwrap_or(Some(today));
+    let to = query
+        .to
+        .as_deref()
+        .map(parse_date)
+        .unwrap_or(Some(today + Duration::days(30)));
+    let (from, to) = match (from, to) {
+        (Some(from), Some(to)) if from <= to && (to - from).num_days() <= 366 => (from, to),
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(
+                    serde_json::json!({"error": "Rango inválido, use YYYY-MM-DD y máximo un año"}),
+                ),
+            ))
+        }
+    };
+
+    let calendar = SchoolCalendar::load(&pool, from, to).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar el calendario"})),
+        )
+    })?;
+    let days: Vec<String> = calendar
+        .school_days(from, to, query.group.as_deref())
+        .into_iter()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "from": from.format("%Y-%m-%d").to_string(),
+        "to": to.format("%Y-%m-%d").to_string(),
+        "count": days.len(),
+        "days": days
+    })))
+}
@@ -0,0 +1,170 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    constants::CARD_ROLES,
+    models::{card::CardQuery, student::Student},
+    utils::{
+        auth::StaffSession,
+        cards::{cards_png, draw_card, page_slot, CARDS_PER_PAGE},
+        enrollment::{active_on_date, today},
+        pdf::PdfDocument,
+        tokens::card_token,
+    },
+};
+
+// cards carry a token that passes for the student at the scanner, so only admins print them
+fn require_card_role(session: &StaffSession) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if !session.has_role(&CARD_ROLES) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "No tienes permiso para imprimir credenciales"})),
+        ));
+    }
+    Ok(())
+}
+
+fn render_cards_pdf(
+    students: &[Student],
+) -> Result<Vec<u8>, (StatusCode, Json<serde_json::Value>)> {
+    let mut document = PdfDocument::new();
+    for chunk in students.chunks(CARDS_PER_PAGE) {
+        let page = document.add_page();
+        for (slot, student) in chunk.iter().enumerate() {
+            let (x, y) = page_slot(slot);
+            draw_card(page, x, y, student, &card_token(&student.id)).map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": format!("Error al generar QR: {}", e)})),
+                )
+            })?;
+        }
+    }
+    Ok(document.to_bytes())
+}
+
+fn render_cards_png(
+    students: &[Student],
+) -> Result<Vec<u8>, (StatusCode, Json<serde_json::Value>)> {
+    let cards: Vec<(&Student, String)> = students
+        .iter()
+        .map(|student| (student, card_token(&student.id)))
+        .collect();
+    cards_png(&cards).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Error al generar la imagen: {}", e)})),
+        )
+    })
+}
+
+// `name` is the file name without extension
+fn cards_response(
+    students: &[Student],
+    format: Option<&str>,
+    name: &str,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    match format.unwrap_or("pdf") {
+        "pdf" => Ok(file_response(
+            "application/pdf",
+            &format!("{}.pdf", name),
+            render_cards_pdf(students)?,
+        )),
+        "png" => Ok(file_response(
+            "image/png",
+            &format!("{}.png", name),
+            render_cards_png(students)?,
+        )),
+        _ => Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Formato inválido, use pdf o png"})),
+        )),
+    }
+}
+
+fn file_response(content_type: &str, filename: &str, bytes: Vec<u8>) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}\"", filename),
+            ),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+pub async fn get_student_card(
+    Path(id): Path<String>,
+    Query(query): Query<CardQuery>,
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    require_card_role(&session)?;
+    let student =
+        sqlx::query_as::<_, Student>("SELECT * FROM estudiantes WHERE id_control_escolar = ?")
+            .bind(&id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|_| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": "Error al consultar la base de datos"})),
+                )
+            })?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+                )
+            })?;
+
+    cards_response(
+        std::slice::from_ref(&student),
+        query.format.as_deref(),
+        &format!("credencial_{}", student.id),
+    )
+}
+
+pub async fn get_group_cards(
+    Path(group): Path<String>,
+    Query(query): Query<CardQuery>,
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    require_card_role(&session)?;
+    let students = sqlx::query_as::<_, Student>(&format!(
+        "SELECT e.* FROM estudiantes e WHERE e.grupo = ? AND {} ORDER BY e.apellido_paterno, e.apellido_materno, e.nombres",
+        active_on_date()
+    ))
+    .bind(&group)
+    .bind(today())
+    .fetch_all(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar la base de datos"})),
+        )
+    })?;
+
+    if students.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "El grupo no tiene estudiantes"})),
+        ));
+    }
+
+    cards_response(
+        &students,
+        query.format.as_deref(),
+        &format!("credenciales_{}", group),
+    )
+}
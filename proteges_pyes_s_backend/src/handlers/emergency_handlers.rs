@@ -1,35 +1,191 @@
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Local;
 use sqlx::Row;
+use std::net::SocketAddr;
 
 use crate::{
+    constants,
     models::{
+        emergency::{
+            EmergencyCounts, EmergencyPerson, EmergencySession, MedicalSummaryQuery,
+            PersonStatusRequest, ReportQuery, RoomReport, RoomReportRequest, SessionMetrics,
+            SessionQuery, SessionUpdateRequest, StudentStatusRequest,
+        },
         scan::{EmergencyStatus, EmergencyStudent, EmergencyTriggerRequest},
         student::Student,
     },
     state::AppState,
-    utils::notifications::broadcast_emergency_notification,
+    utils::{
+        assembly::{student_placements, zone_headcounts},
+        audit::log_access,
+        auth::StaffSession,
+        emergency::{
+            current_session, latest_session, notify_reunification, protocol_notice, session_counts,
+            set_student_status, student_status, student_statuses, EMERGENCY_CLOSED_NOTICE,
+            ON_CAMPUS_STUDENT,
+        },
+        emergency_medical::{medical_summary, medical_summary_pdf},
+        emergency_metrics::{drill_comparison, drill_comparison_csv, session_metrics},
+        emergency_report::{after_action_csv, after_action_pdf, after_action_report},
+        enrollment::{active_on_date, today},
+        hall_passes::passes_during_session,
+        notifications::broadcast_emergency_notification,
+        pickups::emergency_releases,
+        presence::{
+            add_people_to_session, people_counts, person_status, session_people, set_person_status,
+        },
+    },
 };
 
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": format!("Error de base de datos: {}", e)})),
+    )
+}
+
+fn conflict(message: String) -> ApiError {
+    (
+        StatusCode::CONFLICT,
+        Json(serde_json::json!({"error": message})),
+    )
+}
+
+fn require_roll_call_role(session: &StaffSession) -> Result<(), ApiError> {
+    if session.has_role(&constants::EMERGENCY_ROLL_CALL_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(
+                serde_json::json!({"error": "No tienes permiso para registrar el estado de los alumnos"}),
+            ),
+        ))
+    }
+}
+
+fn require_emergency_admin(session: &StaffSession) -> Result<(), ApiError> {
+    if session.has_role(&constants::EMERGENCY_ADMIN_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "No tienes permiso para administrar emergencias"})),
+        ))
+    }
+}
+
+// `scanned` se mantiene para la app: alumnos con paradero conocido
+fn stats_json(counts: &EmergencyCounts) -> serde_json::Value {
+    serde_json::json!({
+        "total": counts.total,
+        "scanned": counts.total - counts.missing,
+        "missing": counts.missing,
+        "located": counts.located,
+        "injured": counts.injured,
+        "released": counts.released,
+    })
+}
+
+async fn save_history(
+    state: &AppState,
+    action: &str,
+    user_id: i64,
+    counts: &EmergencyCounts,
+    notes: String,
+) {
+    sqlx::query(
+        "INSERT INTO emergency_history (action, triggered_by, total_students, scanned_students, missing_students, notes) VALUES (?, ?, ?, ?, ?, ?)"
+    )
+    .bind(action)
+    .bind(user_id)
+    .bind(counts.total)
+    .bind(counts.total - counts.missing)
+    .bind(counts.missing)
+    .bind(notes)
+    .execute(&state.db)
+    .await
+    .map_err(|e| {
+        eprintln!("Error saving emergency history: {}", e);
+        // Don't fail the request if history save fails
+    })
+    .ok();
+}
+
+async fn set_system_emergency(
+    state: &AppState,
+    conn: &mut sqlx::SqliteConnection,
+    active: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE system_state SET emergency_active = ?, last_updated = CURRENT_TIMESTAMP WHERE id = 1")
+        .bind(active)
+        .execute(conn)
+        .await?;
+    *state.emergency_active.write().await = active;
+    Ok(())
+}
+
+fn broadcast(state: &AppState, session_id: i64, (title, body): (&'static str, &'static str)) {
+    let db_pool = state.db.clone();
+    tokio::spawn(async move {
+        if let Err(e) = broadcast_emergency_notification(&db_pool, session_id, title, body).await {
+            println!("Error broadcasting emergency: {}", e);
+        }
+    });
+}
+
 pub async fn get_emergency_students(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<EmergencyStudent>>, (StatusCode, Json<serde_json::Value>)> {
-    // Get all students
-    let students = sqlx::query_as::<_, Student>("SELECT * FROM estudiantes")
-        .fetch_all(&state.db)
-        .await
-        .map_err(|_| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Error al consultar estudiantes"})),
-            )
-        })?;
+    // Status of every student in the open session (or the last one that was closed)
+    let session = latest_session(&state.db).await.map_err(db_error)?;
 
-    // Get scanned student IDs
-    let scanned_ids: Vec<String> =
-        sqlx::query_scalar("SELECT DISTINCT estudiante_consultado FROM historial_consulta")
-            .fetch_all(&state.db)
-            .await
-            .unwrap_or_default();
+    // The students of that session (those on campus and any scanned later); with no session
+    // yet, every enrolled student
+    let session_id = session.as_ref().map(|session| session.id);
+    let students = sqlx::query_as::<_, Student>(&format!(
+        "SELECT e.* FROM estudiantes e WHERE (? IS NULL AND {}) OR e.id_control_escolar IN (SELECT id_control_escolar FROM estado_alumnos_emergencia WHERE sesion_id IS ?)",
+        active_on_date()
+    ))
+    .bind(session_id)
+    .bind(today())
+    .bind(session_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar estudiantes"})),
+        )
+    })?;
+    let (statuses, releases, placements) = match &session {
+        Some(session) => (
+            student_statuses(&state.db, session.id)
+                .await
+                .map_err(db_error)?,
+            emergency_releases(&state.db, session.id)
+                .await
+                .unwrap_or_else(|e| {
+                    eprintln!("Error fetching emergency releases: {:?}", e);
+                    Vec::new()
+                }),
+            student_placements(&state.db, session.id)
+                .await
+                .map_err(db_error)?,
+        ),
+        None => (Vec::new(), Vec::new(), Vec::new()),
+    };
+
+    let passes = passes_during_session(&state.db, session.as_ref())
+        .await
+        .map_err(db_error)?;
 
     // ✅ OPTIMIZACIÓN: Obtener color del docente una sola vez (era N+1 antes)
     let teacher_color: Option<String> = sqlx::query_scalar(
@@ -42,6 +198,23 @@ pub async fn get_emergency_students(
     // Crear lista de estudiantes para emergencia
     let mut emergency_students = Vec::new();
     for student in students {
+        let (status, location) = statuses
+            .iter()
+            .find(|(student_id, _, _)| *student_id == student.id)
+            .map(|(_, status, location)| (status.clone(), location.clone()))
+            .unwrap_or_else(|| ("FALTANTE".to_string(), None));
+        let released_to = releases
+            .iter()
+            .find(|(student_id, _)| *student_id == student.id)
+            .map(|(_, received_by)| received_by.clone());
+        let placement = placements
+            .iter()
+            .find(|placement| placement.student_id == student.id);
+        // the latest pass if the student had several
+        let pass = passes
+            .iter()
+            .rev()
+            .find(|pass| pass.student_id == student.id);
         emergency_students.push(EmergencyStudent {
             id: student.id.clone(),
             names: student.names,
@@ -49,7 +222,18 @@ pub async fn get_emergency_students(
             maternal_last_name: student.maternal_last_name.unwrap_or_default(),
             group: student.group,
             major: student.major,
-            scanned: scanned_ids.contains(&student.id),
+            scanned: status != "FALTANTE",
+            released: status == "ENTREGADO",
+            status,
+            released_to,
+            location,
+            zone: placement.and_then(|placement| placement.zone_name.clone()),
+            assembly_point: placement.and_then(|placement| placement.point_name.clone()),
+            expected_assembly_point: placement
+                .and_then(|placement| placement.expected_point_name.clone()),
+            wrong_point: placement.is_some_and(|placement| placement.wrong_point()),
+            pass_destination: pass.map(|pass| pass.destination.clone()),
+            pass_issued_at: pass.map(|pass| pass.issued_at.clone()),
             teacher_color: teacher_color.clone(), // Usar el mismo color para todos
         });
     }
@@ -103,121 +287,864 @@ pub async fn get_emergency_history(
     Ok(Json(formatted_history))
 }
 
+// `active: true` starts an evacuation; `active: false` ends the evacuation and starts the
+// reunification phase, and a second `active: false` closes the session (see close_emergency)
 pub async fn trigger_emergency(
+    staff: StaffSession,
     State(state): State<AppState>,
     Json(payload): Json<EmergencyTriggerRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    // Update emergency state in memory
-    let mut emergency_active = state.emergency_active.write().await;
-    *emergency_active = payload.active;
+    require_emergency_admin(&staff)?;
+    let session = current_session(&state.db).await.map_err(db_error)?;
+    match (payload.active, session) {
+        (true, None) => start_emergency(&state, &payload, staff.user_id).await,
+        (true, Some(session)) => Err(conflict(format!(
+            "Ya hay una emergencia en curso (fase {})",
+            session.phase
+        ))),
+        (false, Some(session)) if session.phase == "EVACUACION" => {
+            start_reunification(&state, session, staff.user_id, payload.reunification_point).await
+        }
+        (false, Some(session)) => close_session(&state, session, staff.user_id).await,
+        (false, None) => Err(conflict("No hay una emergencia en curso".to_string())),
+    }
+}
 
-    // Update emergency state in DB
-    sqlx::query("UPDATE system_state SET emergency_active = ?, last_updated = CURRENT_TIMESTAMP WHERE id = 1")
-        .bind(payload.active)
-        .execute(&state.db)
-        .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": format!("Error updating system state: {}", e)})),
-            )
-        })?;
+fn validate_kind(kind: Option<&str>) -> Result<Option<String>, ApiError> {
+    let kind = kind
+        .map(|kind| kind.trim().to_uppercase())
+        .filter(|kind| !kind.is_empty());
+    if let Some(kind) = &kind {
+        if !constants::EMERGENCY_KINDS.contains(&kind.as_str()) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("Tipo inválido. Valores permitidos: {}", constants::EMERGENCY_KINDS.join(", "))
+                })),
+            ));
+        }
+    }
+    Ok(kind)
+}
+
+fn validate_protocol(protocol: Option<&str>) -> Result<Option<String>, ApiError> {
+    let protocol = protocol
+        .map(|protocol| protocol.trim().to_uppercase())
+        .filter(|protocol| !protocol.is_empty());
+    if let Some(protocol) = &protocol {
+        if !constants::EMERGENCY_PROTOCOLS.contains(&protocol.as_str()) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("Protocolo inválido. Valores permitidos: {}", constants::EMERGENCY_PROTOCOLS.join(", "))
+                })),
+            ));
+        }
+    }
+    Ok(protocol)
+}
 
-    // Calculate current stats for history
-    let mut total_students = 0;
-    let mut scanned_students = 0;
+fn blank_to_none(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
 
-    if payload.active {
-        // Clear previous scan history when activating emergency
-        sqlx::query("DELETE FROM historial_consulta")
-            .execute(&state.db)
-            .await
-            .map_err(|_| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({"error": "Error al limpiar historial"})),
-                )
-            })?;
-
-        // Count total students
-        total_students = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM estudiantes")
-            .fetch_one(&state.db)
-            .await
-            .unwrap_or(0);
-    } else {
-        // When ending emergency, get final counts
-        total_students = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM estudiantes")
-            .fetch_one(&state.db)
-            .await
-            .unwrap_or(0);
+async fn start_emergency(
+    state: &AppState,
+    payload: &EmergencyTriggerRequest,
+    user_id: i64,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let kind = validate_kind(payload.kind.as_deref())?.unwrap_or_else(|| "EVENTO_REAL".to_string());
+    let protocol = validate_protocol(payload.protocol.as_deref())?.unwrap_or_else(|| {
+        if kind == "CONFINAMIENTO" {
+            "CONFINAMIENTO"
+        } else {
+            "EVACUACION"
+        }
+        .to_string()
+    });
 
-        scanned_students = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(DISTINCT estudiante_consultado) FROM historial_consulta",
+    // New session with every enrolled student on campus starting as missing; previous scans stay
+    // in their session and students who arrive later are added when they are scanned.
+    // The first phase is stored as EVACUACION for every protocol
+    let start = async {
+        let mut tx = state.db.begin().await?;
+        let session_id = sqlx::query(
+            "INSERT INTO sesiones_emergencia (fase, iniciada_por, iniciada_en, tipo, notas, protocolo) VALUES ('EVACUACION', ?, ?, ?, ?, ?)",
         )
-        .fetch_one(&state.db)
+        .bind(user_id)
+        .bind(Local::now().to_rfc3339())
+        .bind(&kind)
+        .bind(blank_to_none(payload.notes.as_deref()))
+        .bind(&protocol)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        sqlx::query(
+            &format!(
+                "INSERT INTO estado_alumnos_emergencia (sesion_id, id_control_escolar, grupo, estado, actualizado_en) SELECT ?, e.id_control_escolar, e.grupo, 'FALTANTE', ? FROM estudiantes e WHERE {} AND {}",
+                active_on_date(),
+                ON_CAMPUS_STUDENT
+            ),
+        )
+        .bind(session_id)
+        .bind(Local::now().to_rfc3339())
+        .bind(today())
+        .bind(today())
+        .execute(&mut *tx)
+        .await?;
+        // staff and visitors on campus are counted too
+        add_people_to_session(&mut tx, session_id).await?;
+        set_system_emergency(state, &mut tx, true).await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(session_id)
+    };
+    let session_id = start.await.map_err(db_error)?;
+
+    let counts = session_counts(&state.db, session_id)
         .await
-        .unwrap_or(0);
-    }
+        .map_err(db_error)?;
+    let people = people_counts(&state.db, session_id)
+        .await
+        .map_err(db_error)?;
+    save_history(
+        state,
+        "ACTIVATED",
+        user_id,
+        &counts,
+        format!(
+            "Protocolo de {} activado ({})",
+            protocol.to_lowercase(),
+            kind
+        ),
+    )
+    .await;
+    broadcast(state, session_id, protocol_notice(&protocol));
 
-    let missing_students = total_students - scanned_students;
+    Ok(Json(serde_json::json!({
+        "message": "Emergencia activada",
+        "active": true,
+        "phase": "EVACUACION",
+        "protocol": protocol,
+        "kind": kind,
+        "session_id": session_id,
+        "stats": stats_json(&counts),
+        "people": people,
+    })))
+}
 
-    // Save to emergency history
-    let action = if payload.active {
-        "ACTIVATED"
-    } else {
-        "DEACTIVATED"
-    };
-    let notes = if payload.active {
-        "Protocolo de emergencia activado".to_string()
-    } else {
-        format!(
-            "Emergencia finalizada - {} de {} estudiantes localizados",
-            scanned_students, total_students
-        )
-    };
+async fn start_reunification(
+    state: &AppState,
+    session: EmergencySession,
+    user_id: i64,
+    reunification_point: Option<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let point = blank_to_none(reunification_point.as_deref());
 
     sqlx::query(
-        "INSERT INTO emergency_history (action, triggered_by, total_students, scanned_students, missing_students, notes) VALUES (?, ?, ?, ?, ?, ?)"
+        "UPDATE sesiones_emergencia SET fase = 'REUNIFICACION', reunificacion_en = ?, punto_reunificacion = ? WHERE id = ?",
     )
-    .bind(action)
-    .bind(payload.user_id)
-    .bind(total_students)
-    .bind(scanned_students)
-    .bind(missing_students)
-    .bind(notes)
+    .bind(Local::now().to_rfc3339())
+    .bind(&point)
+    .bind(session.id)
     .execute(&state.db)
     .await
-    .map_err(|e| {
-        eprintln!("Error saving emergency history: {}", e);
-        // Don't fail the request if history save fails
-    })
-    .ok();
+    .map_err(db_error)?;
 
-    // Broadcast Notification (async)
-    let db_pool = state.db.clone();
-    let active = payload.active;
+    let counts = session_counts(&state.db, session.id)
+        .await
+        .map_err(db_error)?;
+    save_history(
+        state,
+        "REUNIFICATION",
+        user_id,
+        &counts,
+        format!(
+            "Fase de respuesta concluida - {} de {} estudiantes localizados; inicia la reunificación",
+            counts.total - counts.missing,
+            counts.total
+        ),
+    )
+    .await;
+
+    // Targeted notice to the guardians of every student not yet released
+    let session_id = session.id;
+    let session = EmergencySession {
+        phase: "REUNIFICACION".to_string(),
+        reunification_point: point.clone(),
+        ..session
+    };
+    let pool = state.db.clone();
     tokio::spawn(async move {
-        if let Err(e) = broadcast_emergency_notification(&db_pool, active).await {
-            println!("Error broadcasting emergency: {}", e);
+        let pending: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+            SELECT s.id_control_escolar, e.nombres || ' ' || e.apellido_paterno, s.estado
+            FROM estado_alumnos_emergencia s
+            JOIN estudiantes e ON e.id_control_escolar = s.id_control_escolar
+            WHERE s.sesion_id = ? AND s.estado <> 'ENTREGADO'
+            "#,
+        )
+        .bind(session.id)
+        .fetch_all(&pool)
+        .await
+        .unwrap_or_default();
+        for (student_id, name, status) in pending {
+            notify_reunification(&pool, &session, &student_id, &name, &status).await;
         }
     });
 
     Ok(Json(serde_json::json!({
-        "message": if payload.active { "Emergencia activada" } else { "Emergencia desactivada" },
-        "active": payload.active,
-        "stats": {
-            "total": total_students,
-            "scanned": scanned_students,
-            "missing": missing_students
-        }
+        "message": "Evacuación concluida. Inicia la reunificación",
+        "active": true,
+        "phase": "REUNIFICACION",
+        "session_id": session_id,
+        "reunification_point": point,
+        "stats": stats_json(&counts),
+    })))
+}
+
+// the session closes only from reunification and once no student is still missing
+async fn close_session(
+    state: &AppState,
+    session: EmergencySession,
+    user_id: i64,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if session.phase != "REUNIFICACION" {
+        return Err(conflict(
+            "Primero concluye la fase de respuesta para iniciar la reunificación".to_string(),
+        ));
+    }
+
+    let counts = session_counts(&state.db, session.id)
+        .await
+        .map_err(db_error)?;
+    let people = people_counts(&state.db, session.id)
+        .await
+        .map_err(db_error)?;
+    if counts.missing > 0 || people.missing > 0 {
+        let missing: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT s.id_control_escolar, e.nombres || ' ' || e.apellido_paterno
+            FROM estado_alumnos_emergencia s
+            JOIN estudiantes e ON e.id_control_escolar = s.id_control_escolar
+            WHERE s.sesion_id = ? AND s.estado = 'FALTANTE'
+            ORDER BY e.grupo, e.apellido_paterno
+            "#,
+        )
+        .bind(session.id)
+        .fetch_all(&state.db)
+        .await
+        .map_err(db_error)?;
+        let missing_people: Vec<EmergencyPerson> = session_people(&state.db, session.id)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .filter(|person| person.status == "FALTANTE")
+            .collect();
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": format!(
+                    "No se puede cerrar la emergencia: {} estudiantes y {} personas del personal o visitantes siguen sin localizar",
+                    counts.missing, people.missing
+                ),
+                "missing_students": missing
+                    .into_iter()
+                    .map(|(id, name)| serde_json::json!({"id": id, "name": name}))
+                    .collect::<Vec<_>>(),
+                "missing_people": missing_people,
+            })),
+        ));
+    }
+
+    let close = async {
+        let mut tx = state.db.begin().await?;
+        sqlx::query(
+            "UPDATE sesiones_emergencia SET fase = 'CERRADA', cerrada_por = ?, cerrada_en = ? WHERE id = ?",
+        )
+        .bind(user_id)
+        .bind(Local::now().to_rfc3339())
+        .bind(session.id)
+        .execute(&mut *tx)
+        .await?;
+        set_system_emergency(state, &mut tx, false).await?;
+        tx.commit().await
+    };
+    close.await.map_err(db_error)?;
+
+    save_history(
+        state,
+        "DEACTIVATED",
+        user_id,
+        &counts,
+        format!(
+            "Emergencia finalizada - {} de {} estudiantes localizados, {} entregados; {} de {} del personal y visitantes localizados",
+            counts.total - counts.missing,
+            counts.total,
+            counts.released,
+            people.total - people.missing,
+            people.total
+        ),
+    )
+    .await;
+    broadcast(state, session.id, EMERGENCY_CLOSED_NOTICE);
+
+    Ok(Json(serde_json::json!({
+        "message": "Emergencia desactivada",
+        "active": false,
+        "phase": "CERRADA",
+        "session_id": session.id,
+        "stats": stats_json(&counts),
+        "people": people,
+    })))
+}
+
+pub async fn close_emergency(
+    staff: StaffSession,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_emergency_admin(&staff)?;
+    let session = current_session(&state.db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| conflict("No hay una emergencia en curso".to_string()))?;
+    close_session(&state, session, staff.user_id).await
+}
+
+// located / injured / missing; releases are recorded through /pickups/release
+pub async fn update_student_status(
+    staff: StaffSession,
+    Path(student_id): Path<String>,
+    State(state): State<AppState>,
+    Json(payload): Json<StudentStatusRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_roll_call_role(&staff)?;
+    let status = payload.status.trim().to_uppercase();
+    if status == "ENTREGADO" {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Las entregas se registran en /pickups/release"})),
+        ));
+    }
+    if !constants::EMERGENCY_STUDENT_STATUSES.contains(&status.as_str()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Estado inválido. Valores permitidos: LOCALIZADO, LESIONADO, FALTANTE"
+            })),
+        ));
+    }
+
+    let session = current_session(&state.db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| conflict("No hay una emergencia en curso".to_string()))?;
+
+    let previous = student_status(&state.db, session.id, &student_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(
+                    serde_json::json!({"error": "El estudiante no forma parte de esta emergencia"}),
+                ),
+            )
+        })?;
+    if previous == "ENTREGADO" {
+        return Err(conflict("El estudiante ya fue entregado".to_string()));
+    }
+
+    let notes = blank_to_none(payload.notes.as_deref());
+    set_student_status(
+        &state.db,
+        session.id,
+        &student_id,
+        &status,
+        staff.user_id,
+        notes.as_deref(),
+    )
+    .await
+    .map_err(db_error)?;
+
+    // During reunification the guardians learn right away where to go
+    if session.phase == "REUNIFICACION" && previous != status {
+        let pool = state.db.clone();
+        let student_id = student_id.clone();
+        let status = status.clone();
+        tokio::spawn(async move {
+            let name: String = sqlx::query_scalar(
+                "SELECT nombres || ' ' || apellido_paterno FROM estudiantes WHERE id_control_escolar = ?",
+            )
+            .bind(&student_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap_or_default();
+            notify_reunification(&pool, &session, &student_id, &name, &status).await;
+        });
+    }
+
+    Ok(Json(serde_json::json!({
+        "message": "Estado actualizado",
+        "student_id": student_id,
+        "status": status,
     })))
 }
 
 pub async fn get_emergency_status(
     State(state): State<AppState>,
 ) -> Result<Json<EmergencyStatus>, StatusCode> {
-    let emergency_active = state.emergency_active.read().await;
+    let emergency_active = *state.emergency_active.read().await;
+    let session = current_session(&state.db)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     Ok(Json(EmergencyStatus {
-        active: *emergency_active,
+        active: emergency_active,
+        phase: session.as_ref().map(|session| session.phase.clone()),
+        protocol: session.as_ref().map(|session| session.protocol.clone()),
+        session_id: session.as_ref().map(|session| session.id),
+        reunification_point: session.and_then(|session| session.reunification_point),
     }))
 }
+
+async fn fetch_session(state: &AppState, id: i64) -> Result<EmergencySession, ApiError> {
+    sqlx::query_as::<_, EmergencySession>("SELECT * FROM sesiones_emergencia WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Sesión de emergencia no encontrada"})),
+            )
+        })
+}
+
+pub async fn get_sessions(
+    State(state): State<AppState>,
+    Query(query): Query<SessionQuery>,
+) -> Result<Json<Vec<EmergencySession>>, ApiError> {
+    let kind = validate_kind(query.kind.as_deref())?;
+    let sessions = sqlx::query_as::<_, EmergencySession>(
+        r#"
+        SELECT * FROM sesiones_emergencia
+        WHERE (?1 IS NULL OR tipo = ?1)
+        AND (?2 IS NULL OR substr(iniciada_en, 1, 10) >= ?2)
+        AND (?3 IS NULL OR substr(iniciada_en, 1, 10) <= ?3)
+        ORDER BY id DESC
+        "#,
+    )
+    .bind(kind)
+    .bind(blank_to_none(query.from.as_deref()))
+    .bind(blank_to_none(query.to.as_deref()))
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(sessions))
+}
+
+// tag a session afterwards (e.g. an activation that turned out to be a drill)
+pub async fn update_session(
+    staff: StaffSession,
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    Json(payload): Json<SessionUpdateRequest>,
+) -> Result<Json<EmergencySession>, ApiError> {
+    require_emergency_admin(&staff)?;
+    let kind = validate_kind(payload.kind.as_deref())?;
+    let session = fetch_session(&state, id).await?;
+
+    sqlx::query("UPDATE sesiones_emergencia SET tipo = ?, notas = ? WHERE id = ?")
+        .bind(kind.unwrap_or(session.kind))
+        .bind(blank_to_none(payload.notes.as_deref()).or(session.notes))
+        .bind(id)
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(fetch_session(&state, id).await?))
+}
+
+pub async fn get_session_metrics(
+    staff: StaffSession,
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<SessionMetrics>, ApiError> {
+    require_emergency_admin(&staff)?;
+    let session = fetch_session(&state, id).await?;
+    Ok(Json(
+        session_metrics(&state.db, session)
+            .await
+            .map_err(db_error)?,
+    ))
+}
+
+// after-action report of a session: JSON, ?format=pdf or ?format=csv
+pub async fn get_session_report(
+    staff: StaffSession,
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Response, ApiError> {
+    require_emergency_admin(&staff)?;
+    let session = fetch_session(&state, id).await?;
+    let report = after_action_report(&state.db, session)
+        .await
+        .map_err(db_error)?;
+
+    match query.format.as_deref() {
+        Some("pdf") => Ok((
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("inline; filename=\"reporte_emergencia_{}.pdf\"", id),
+                ),
+            ],
+            after_action_pdf(&report),
+        )
+            .into_response()),
+        Some("csv") => Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"reporte_emergencia_{}.csv\"", id),
+                ),
+            ],
+            after_action_csv(&report),
+        )
+            .into_response()),
+        _ => Ok(Json(report).into_response()),
+    }
+}
+
+// comparativo de simulacros para las auditorias de proteccion civil (JSON o ?format=csv)
+pub async fn get_drill_report(
+    State(state): State<AppState>,
+    Query(query): Query<SessionQuery>,
+) -> Result<Response, ApiError> {
+    let kind = validate_kind(query.kind.as_deref())?;
+    let comparison = drill_comparison(
+        &state.db,
+        kind.as_deref(),
+        blank_to_none(query.from.as_deref()).as_deref(),
+        blank_to_none(query.to.as_deref()).as_deref(),
+    )
+    .await
+    .map_err(db_error)?;
+
+    if query.format.as_deref() == Some("csv") {
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"comparativo_simulacros.csv\"",
+                ),
+            ],
+            drill_comparison_csv(&comparison),
+        )
+            .into_response());
+    }
+
+    Ok(Json(comparison).into_response())
+}
+
+// lockdown / shelter-in-place roll-call: the teacher reports the students with them in a room
+pub async fn report_room(
+    staff: StaffSession,
+    State(state): State<AppState>,
+    Json(payload): Json<RoomReportRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_roll_call_role(&staff)?;
+    let room = payload.room.trim().to_string();
+    if room.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "El salón es obligatorio"})),
+        ));
+    }
+    let session = current_session(&state.db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| conflict("No hay una emergencia en curso".to_string()))?;
+    if session.protocol == "EVACUACION" {
+        return Err(conflict(
+            "En una evacuación los alumnos se registran en el punto de reunión".to_string(),
+        ));
+    }
+
+    let mut located = Vec::new();
+    let mut skipped = Vec::new();
+    let report = async {
+        let mut tx = state.db.begin().await?;
+        for student_id in &payload.student_ids {
+            match student_status(&mut *tx, session.id, student_id)
+                .await?
+                .as_deref()
+            {
+                None => skipped.push(serde_json::json!({
+                    "student_id": student_id, "reason": "No forma parte de esta emergencia"
+                })),
+                Some("ENTREGADO") => skipped.push(serde_json::json!({
+                    "student_id": student_id, "reason": "Ya fue entregado"
+                })),
+                Some(status) => {
+                    // an injured student keeps that status; only the room changes
+                    let status = if status == "LESIONADO" {
+                        "LESIONADO"
+                    } else {
+                        "LOCALIZADO"
+                    };
+                    set_student_status(
+                        &mut *tx,
+                        session.id,
+                        student_id,
+                        status,
+                        staff.user_id,
+                        None,
+                    )
+                    .await?;
+                    sqlx::query(
+                        "UPDATE estado_alumnos_emergencia SET ubicacion = ? WHERE sesion_id = ? AND id_control_escolar = ?",
+                    )
+                    .bind(&room)
+                    .bind(session.id)
+                    .bind(student_id)
+                    .execute(&mut *tx)
+                    .await?;
+                    located.push(student_id.clone());
+                }
+            }
+        }
+        sqlx::query(
+            "INSERT INTO reportes_aula (sesion_id, salon, reportado_por, alumnos, notas, reportado_en) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(session.id)
+        .bind(&room)
+        .bind(staff.user_id)
+        .bind(located.len() as i64)
+        .bind(blank_to_none(payload.notes.as_deref()))
+        .bind(Local::now().to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await
+    };
+    report.await.map_err(db_error)?;
+
+    Ok(Json(serde_json::json!({
+        "message": format!("Reporte del salón {} registrado", room),
+        "room": room,
+        "located": located,
+        "skipped": skipped,
+    })))
+}
+
+// latest report of every room in the session in progress
+pub async fn get_room_reports(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<RoomReport>>, ApiError> {
+    let Some(session) = current_session(&state.db).await.map_err(db_error)? else {
+        return Ok(Json(Vec::new()));
+    };
+
+    let reports = sqlx::query_as::<_, RoomReport>(
+        r#"
+        SELECT
+            r.salon AS room,
+            r.reportado_por AS reported_by,
+            u.nombre_mostrado AS reported_by_name,
+            (
+                SELECT COUNT(*) FROM estado_alumnos_emergencia s
+                WHERE s.sesion_id = r.sesion_id AND s.ubicacion = r.salon AND s.estado <> 'ENTREGADO'
+            ) AS students,
+            r.notas AS notes,
+            r.reportado_en AS reported_at
+        FROM reportes_aula r
+        LEFT JOIN usuarios u ON u.id_usuario = r.reportado_por
+        WHERE r.sesion_id = ?
+        AND r.id = (SELECT MAX(id) FROM reportes_aula WHERE sesion_id = r.sesion_id AND salon = r.salon)
+        ORDER BY r.salon
+        "#,
+    )
+    .bind(session.id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(reports))
+}
+
+// per-zone headcount of the open session (or the last one) for first responders
+pub async fn get_zone_headcounts(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let Some(session) = latest_session(&state.db).await.map_err(db_error)? else {
+        return Ok(Json(serde_json::json!({ "session_id": null, "zones": [] })));
+    };
+    let zones = zone_headcounts(&state.db, session.id)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(serde_json::json!({
+        "session_id": session.id,
+        "phase": session.phase,
+        "zones": zones,
+    })))
+}
+
+// staff and visitors counted in the open session (or the last one)
+pub async fn get_emergency_people(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let Some(session) = latest_session(&state.db).await.map_err(db_error)? else {
+        return Ok(Json(
+            serde_json::json!({ "session_id": null, "people": [] }),
+        ));
+    };
+    let people = session_people(&state.db, session.id)
+        .await
+        .map_err(db_error)?;
+    let counts = people_counts(&state.db, session.id)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(serde_json::json!({
+        "session_id": session.id,
+        "counts": counts,
+        "people": people,
+    })))
+}
+
+// located / injured / missing / left for a staff member (PERSONAL) or visitor (VISITANTE)
+pub async fn update_person_status(
+    staff: StaffSession,
+    Path((kind, person_id)): Path<(String, i64)>,
+    State(state): State<AppState>,
+    Json(payload): Json<PersonStatusRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_roll_call_role(&staff)?;
+    let kind = kind.trim().to_uppercase();
+    let status = payload.status.trim().to_uppercase();
+    if !constants::EMERGENCY_PERSON_STATUSES.contains(&status.as_str()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Estado inválido. Valores permitidos: {}", constants::EMERGENCY_PERSON_STATUSES.join(", "))
+            })),
+        ));
+    }
+
+    let session = current_session(&state.db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| conflict("No hay una emergencia en curso".to_string()))?;
+    let person = person_status(&state.db, session.id, &kind, person_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "La persona no forma parte de esta emergencia"})),
+            )
+        })?;
+
+    set_person_status(
+        &state.db,
+        session.id,
+        &kind,
+        person_id,
+        &person.name,
+        &status,
+        staff.user_id,
+        blank_to_none(payload.notes.as_deref()).as_deref(),
+    )
+    .await
+    .map_err(db_error)?;
+
+    let counts = people_counts(&state.db, session.id)
+        .await
+        .map_err(db_error)?;
+    Ok(Json(serde_json::json!({
+        "message": format!("{} marcado como {}", person.name, status),
+        "status": status,
+        "counts": counts,
+    })))
+}
+
+// blood type, allergies, chronic conditions and contacts of every injured or missing student,
+// for paramedics; only while an emergency is open, and every access is logged
+pub async fn get_medical_summary(
+    staff: StaffSession,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(state): State<AppState>,
+    Query(query): Query<MedicalSummaryQuery>,
+) -> Result<Response, ApiError> {
+    if !staff.has_role(&constants::MEDICAL_SUMMARY_ROLES) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(
+                serde_json::json!({"error": "No tienes permiso para consultar el resumen médico"}),
+            ),
+        ));
+    }
+
+    let session = current_session(&state.db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            conflict("El resumen médico solo está disponible durante una emergencia".to_string())
+        })?;
+    let entries = medical_summary(&state.db, session.id)
+        .await
+        .map_err(db_error)?;
+
+    let pdf = query.format.as_deref() == Some("pdf");
+    let actor = staff.actor();
+    let ip = addr.ip().to_string();
+    for entry in &entries {
+        log_access(
+            &state.db,
+            "DATOS_MEDICOS",
+            &entry.student_id,
+            if pdf {
+                "RESUMEN_EMERGENCIA_PDF"
+            } else {
+                "RESUMEN_EMERGENCIA"
+            },
+            &actor,
+            Some(&ip),
+        )
+        .await;
+    }
+
+    if pdf {
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("inline; filename=\"resumen_medico_{}.pdf\"", session.id),
+                ),
+                (header::CACHE_CONTROL, "private, no-store".to_string()),
+            ],
+            medical_summary_pdf(&session, &entries),
+        )
+            .into_response());
+    }
+
+    Ok((
+        [(header::CACHE_CONTROL, "private, no-store")],
+        Json(serde_json::json!({
+            "session_id": session.id,
+            "generated_at": Local::now().to_rfc3339(),
+            "students": entries,
+        })),
+    )
+        .into_response())
+}
@@ -0,0 +1,82 @@
+use axum::{
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use sqlx::{Pool, Sqlite};
+use std::net::SocketAddr;
+
+use crate::{
+    models::evidence::{EvidenceFile, SignedFileQuery},
+    utils::{
+        audit::log_access,
+        uploads::{evidence_path, verify_evidence_signature},
+    },
+};
+
+// descarga de evidencias solo con enlace firmado vigente; cada descarga queda en la bitacora
+pub async fn download_evidence(
+    Path(id): Path<i64>,
+    Query(query): Query<SignedFileQuery>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    if !verify_evidence_signature(id, &query.viewer, query.expires, &query.signature) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "Enlace inválido"})),
+        ));
+    }
+    if chrono::Utc::now().timestamp() > query.expires {
+        return Err((
+            StatusCode::GONE,
+            Json(serde_json::json!({"error": "El enlace expiró, solicita uno nuevo"})),
+        ));
+    }
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Evidencia no encontrada"})),
+        )
+    };
+
+    let evidence = sqlx::query_as::<_, EvidenceFile>(
+        "SELECT id, nombre_archivo, tipo_mime FROM evidencias WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+    })?
+    .ok_or_else(not_found)?;
+
+    let path = evidence_path(&evidence.nombre_archivo).ok_or_else(not_found)?;
+    // las evidencias tienen un tope de 5 MB, se pueden leer completas
+    let bytes = tokio::fs::read(&path).await.map_err(|_| not_found())?;
+
+    log_access(
+        &pool,
+        "EVIDENCIA",
+        &evidence.id.to_string(),
+        "DESCARGA",
+        &query.viewer,
+        Some(&addr.ip().to_string()),
+    )
+    .await;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, evidence.tipo_mime),
+            (header::CONTENT_DISPOSITION, "inline".to_string()),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        bytes,
+    )
+        .into_response())
+}
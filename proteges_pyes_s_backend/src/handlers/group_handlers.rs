@@ -1,20 +1,444 @@
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Local;
 use sqlx::{Pool, Sqlite};
 
-use crate::models::group::Group;
+use crate::{
+    constants,
+    models::group::{Group, GroupQuery, GroupRequest, RolloverRequest},
+    utils::{
+        auth::{user_role, StaffSession},
+        calendar::find_term,
+        groups::{find_group, group_select},
+        rollover::{apply_rollover, plan_rollover, rollover_done},
+    },
+};
 
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": format!("Error de base de datos: {}", e)})),
+    )
+}
+
+fn bad_request(message: String) -> ApiError {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({"error": message})),
+    )
+}
+
+fn group_not_found() -> ApiError {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({"error": "Grupo no encontrado"})),
+    )
+}
+
+fn blank_to_none(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn valid_group_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn require_group_admin(session: &StaffSession) -> Result<(), ApiError> {
+    if session.has_role(&constants::GROUP_ADMIN_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "No tienes permiso para administrar grupos"})),
+        ))
+    }
+}
+
+// Checked fields of a create/update request: (major, shift, semester, grade)
+async fn validate_group(
+    pool: &Pool<Sqlite>,
+    payload: &GroupRequest,
+) -> Result<(String, Option<String>, Option<i64>, Option<i64>), ApiError> {
+    let major = payload.major.trim();
+    if major.is_empty() {
+        return Err(bad_request("La especialidad es obligatoria".to_string()));
+    }
+
+    // GENERAL is the absence of a shift, not a shift of its own
+    let shift = blank_to_none(&payload.shift)
+        .map(|shift| shift.to_uppercase())
+        .filter(|shift| shift != "GENERAL");
+    if let Some(shift) = &shift {
+        if !constants::SHIFTS.contains(&shift.as_str()) {
+            return Err(bad_request(format!(
+                "Turno inválido. Valores permitidos: {}",
+                constants::SHIFTS[1..].join(", ")
+            )));
+        }
+    }
+
+    if let Some(semester) = payload.semester {
+        if !(1..=constants::MAX_SEMESTER).contains(&semester) {
+            return Err(bad_request(format!(
+                "El semestre debe estar entre 1 y {}",
+                constants::MAX_SEMESTER
+            )));
+        }
+    }
+    let grade = payload
+        .grade
+        .or(payload.semester.map(|semester| (semester + 1) / 2));
+    if let Some(grade) = grade {
+        if !(1..=(constants::MAX_SEMESTER + 1) / 2).contains(&grade) {
+            return Err(bad_request(format!(
+                "El grado debe estar entre 1 y {}",
+                (constants::MAX_SEMESTER + 1) / 2
+            )));
+        }
+    }
+
+    if let Some(tutor_id) = payload.tutor_id {
+        if user_role(pool, tutor_id)
+            .await
+            .map_err(db_error)?
+            .as_deref()
+            != Some("Docente")
+        {
+            return Err(bad_request(
+                "El tutor del grupo debe ser un docente".to_string(),
+            ));
+        }
+    }
+
+    Ok((major.to_string(), shift, payload.semester, grade))
+}
+
+async fn student_count(pool: &Pool<Sqlite>, id: &str) -> Result<i64, ApiError> {
+    sqlx::query_scalar("SELECT count(*) FROM estudiantes WHERE grupo = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(db_error)
+}
+
+fn has_students_error(action: &str, count: i64) -> ApiError {
+    (
+        StatusCode::CONFLICT,
+        Json(serde_json::json!({
+            "error": format!("No se puede {} un grupo con estudiantes; cámbialos de grupo primero", action),
+            "students": count,
+        })),
+    )
+}
+
+// Active groups; archived ones only with `include_archived=true`
 pub async fn get_all_groups(
     State(pool): State<Pool<Sqlite>>,
-) -> Result<Json<Vec<Group>>, (StatusCode, Json<serde_json::Value>)> {
-    let groups = sqlx::query_as::<_, Group>("SELECT * FROM grupos")
-        .fetch_all(&pool)
+    Query(query): Query<GroupQuery>,
+) -> Result<Json<Vec<Group>>, ApiError> {
+    let groups = sqlx::query_as::<_, Group>(&format!(
+        "{} WHERE ? OR NOT g.archivado ORDER BY g.archivado, g.id_nomenclatura",
+        group_select()
+    ))
+    .bind(query.include_archived.unwrap_or(false))
+    .fetch_all(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar grupos"})),
+        )
+    })?;
+
+    Ok(Json(groups))
+}
+
+pub async fn get_group(
+    Path(id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Group>, ApiError> {
+    let group = find_group(&pool, &id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(group_not_found)?;
+    Ok(Json(group))
+}
+
+pub async fn create_group(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<GroupRequest>,
+) -> Result<Json<Group>, ApiError> {
+    require_group_admin(&session)?;
+
+    let id = blank_to_none(&payload.id)
+        .map(|id| id.to_uppercase())
+        .ok_or_else(|| bad_request("La nomenclatura del grupo es obligatoria".to_string()))?;
+    if !valid_group_id(&id) {
+        return Err(bad_request(
+            "La nomenclatura solo admite letras, números y guiones".to_string(),
+        ));
+    }
+    let (major, shift, semester, grade) = validate_group(&pool, &payload).await?;
+
+    sqlx::query(
+        "INSERT INTO grupos (id_nomenclatura, especialidad, descripcion, turno, semestre, grado, id_tutor) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(&major)
+    .bind(blank_to_none(&payload.description))
+    .bind(&shift)
+    .bind(semester)
+    .bind(grade)
+    .bind(payload.tutor_id)
+    .execute(&pool)
+    .await
+    .map_err(|e| match e.as_database_error() {
+        Some(db) if db.is_unique_violation() => (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "El grupo ya existe"})),
+        ),
+        _ => db_error(e),
+    })?;
+
+    let group = find_group(&pool, &id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(group_not_found)?;
+    Ok(Json(group))
+}
+
+// Full replace: every field of the request is written, so an omitted description, shift,
+// semester or tutor is cleared (the grade is derived again from the semester)
+pub async fn update_group(
+    session: StaffSession,
+    Path(id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<GroupRequest>,
+) -> Result<Json<Group>, ApiError> {
+    require_group_admin(&session)?;
+    let (major, shift, semester, grade) = validate_group(&pool, &payload).await?;
+
+    let result = sqlx::query(
+        "UPDATE grupos SET especialidad = ?, descripcion = ?, turno = ?, semestre = ?, grado = ?, id_tutor = ? WHERE id_nomenclatura = ?",
+    )
+    .bind(&major)
+    .bind(blank_to_none(&payload.description))
+    .bind(&shift)
+    .bind(semester)
+    .bind(grade)
+    .bind(payload.tutor_id)
+    .bind(&id)
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(group_not_found());
+    }
+
+    let group = find_group(&pool, &id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(group_not_found)?;
+    Ok(Json(group))
+}
+
+// Archived groups keep their history but take no new students
+pub async fn archive_group(
+    session: StaffSession,
+    Path(id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Group>, ApiError> {
+    require_group_admin(&session)?;
+    let group = find_group(&pool, &id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(group_not_found)?;
+    if group.archived {
+        return Ok(Json(group));
+    }
+    if group.student_count > 0 {
+        return Err(has_students_error("archivar", group.student_count));
+    }
+
+    sqlx::query("UPDATE grupos SET archivado = TRUE, archivado_en = ? WHERE id_nomenclatura = ?")
+        .bind(Local::now().to_rfc3339())
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+    let group = find_group(&pool, &id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(group_not_found)?;
+    Ok(Json(group))
+}
+
+pub async fn restore_group(
+    session: StaffSession,
+    Path(id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Group>, ApiError> {
+    require_group_admin(&session)?;
+    let result = sqlx::query(
+        "UPDATE grupos SET archivado = FALSE, archivado_en = NULL WHERE id_nomenclatura = ?",
+    )
+    .bind(&id)
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(group_not_found());
+    }
+
+    let group = find_group(&pool, &id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(group_not_found)?;
+    Ok(Json(group))
+}
+
+// Only for groups created by mistake: anything with students or history must be archived
+pub async fn delete_group(
+    session: StaffSession,
+    Path(id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_group_admin(&session)?;
+    let count = student_count(&pool, &id).await?;
+    if count > 0 {
+        return Err(has_students_error("eliminar", count));
+    }
+
+    let result = sqlx::query("DELETE FROM grupos WHERE id_nomenclatura = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            // schedules, calendar exceptions... still point at it
+            Some(db) if db.is_foreign_key_violation() => (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({"error": "El grupo tiene registros asociados; archívalo en su lugar"})),
+            ),
+            _ => db_error(e),
+        })?;
+    if result.rows_affected() == 0 {
+        return Err(group_not_found());
+    }
+
+    Ok(Json(serde_json::json!({"message": "Grupo eliminado"})))
+}
+
+// Moves every active group up one semester for the start of a term. Unless `dry_run` is
+// sent as false nothing is written and the response is the preview of the same plan
+pub async fn rollover_groups(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Json(mut payload): Json<RolloverRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_group_admin(&session)?;
+
+    let term = find_term(&pool, payload.term_id)
         .await
-        .map_err(|_| {
+        .map_err(db_error)?
+        .ok_or_else(|| {
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Error al consultar grupos"})),
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Periodo no encontrado"})),
             )
         })?;
+    let already_done = || {
+        (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "Ya se hizo la promoción de este periodo"})),
+        )
+    };
+    if rollover_done(&pool, term.id).await.map_err(db_error)? {
+        return Err(already_done());
+    }
 
-    Ok(Json(groups))
+    if let Some(student_id) = payload
+        .repeaters
+        .iter()
+        .find(|id| payload.graduates.contains(id))
+    {
+        return Err(bad_request(format!(
+            "El estudiante {} no puede repetir y egresar a la vez",
+            student_id
+        )));
+    }
+    let mut unknown = Vec::new();
+    for student_id in payload.repeaters.iter().chain(&payload.graduates) {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM estudiantes WHERE id_control_escolar = ?)",
+        )
+        .bind(student_id)
+        .fetch_one(&pool)
+        .await
+        .map_err(db_error)?;
+        if !exists {
+            unknown.push(student_id.clone());
+        }
+    }
+    if !unknown.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Estudiantes no encontrados", "students": unknown})),
+        ));
+    }
+
+    payload.targets = payload
+        .targets
+        .drain()
+        .map(|(from, to)| (from.trim().to_uppercase(), to.trim().to_uppercase()))
+        .collect();
+    for (from, to) in &payload.targets {
+        let active = find_group(&pool, from)
+            .await
+            .map_err(db_error)?
+            .is_some_and(|group| !group.archived);
+        if !active {
+            return Err(bad_request(format!(
+                "El grupo {} no existe o está archivado",
+                from
+            )));
+        }
+        if !valid_group_id(to) {
+            return Err(bad_request(format!(
+                "Nomenclatura inválida para el grupo destino de {}",
+                from
+            )));
+        }
+    }
+
+    let plan = plan_rollover(&pool, &payload, term)
+        .await
+        .map_err(db_error)?;
+    if plan.dry_run {
+        return Ok(Json(serde_json::json!(plan)));
+    }
+
+    let rollover_id = apply_rollover(&pool, &plan, session.user_id)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            // two rollovers for the same term submitted at once
+            Some(db) if db.is_unique_violation() => already_done(),
+            _ => db_error(e),
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Promoción aplicada",
+        "id": rollover_id,
+        "plan": plan,
+    })))
 }
@@ -0,0 +1,298 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    constants,
+    models::guardian::{
+        Guardian, GuardianDetail, GuardianLink, GuardianLinkRequest, GuardianQuery, GuardianRequest,
+    },
+    utils::{
+        auth::StaffSession,
+        guardians::{links_for_guardian, links_for_student},
+    },
+};
+
+fn db_error(e: sqlx::Error) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": e.to_string()})),
+    )
+}
+
+// los datos de contacto y las restricciones de custodia solo los ve el personal de control escolar
+fn require_guardian_admin(
+    session: &StaffSession,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if session.has_role(&constants::GUARDIAN_ADMIN_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "No tienes permiso para consultar tutores"})),
+        ))
+    }
+}
+
+fn blank_to_none(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+async fn fetch_guardian(
+    pool: &Pool<Sqlite>,
+    id: i64,
+) -> Result<Guardian, (StatusCode, Json<serde_json::Value>)> {
+    sqlx::query_as::<_, Guardian>("SELECT * FROM tutores WHERE id_tutor = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Tutor no encontrado"})),
+            )
+        })
+}
+
+fn validate_guardian(
+    payload: &GuardianRequest,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if payload.name.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "El nombre del tutor es obligatorio"})),
+        ));
+    }
+    if blank_to_none(&payload.phone).is_none() && blank_to_none(&payload.email).is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Se requiere al menos un teléfono o correo"})),
+        ));
+    }
+    Ok(())
+}
+
+fn conflict_or_db_error(e: sqlx::Error) -> (StatusCode, Json<serde_json::Value>) {
+    if e.to_string().contains("UNIQUE constraint failed") {
+        (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "Ya existe un tutor con ese teléfono"})),
+        )
+    } else {
+        db_error(e)
+    }
+}
+
+// tutores, opcionalmente buscando por telefono
+pub async fn get_guardians(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Query(query): Query<GuardianQuery>,
+) -> Result<Json<Vec<Guardian>>, (StatusCode, Json<serde_json::Value>)> {
+    require_guardian_admin(&session)?;
+    let guardians = sqlx::query_as::<_, Guardian>(
+        "SELECT * FROM tutores WHERE (?1 IS NULL OR telefono = ?1 OR telefono_alterno = ?1) ORDER BY nombre",
+    )
+    .bind(blank_to_none(&query.phone))
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(guardians))
+}
+
+pub async fn get_guardian(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<GuardianDetail>, (StatusCode, Json<serde_json::Value>)> {
+    require_guardian_admin(&session)?;
+    let guardian = fetch_guardian(&pool, id).await?;
+    let students = links_for_guardian(&pool, id).await.map_err(db_error)?;
+    Ok(Json(GuardianDetail { guardian, students }))
+}
+
+pub async fn create_guardian(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<GuardianRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_guardian_admin(&session)?;
+    validate_guardian(&payload)?;
+
+    let result = sqlx::query(
+        "INSERT INTO tutores (nombre, telefono, telefono_alterno, correo, notificar_push, notificar_sms, avisos_entrada_salida) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(payload.name.trim())
+    .bind(blank_to_none(&payload.phone))
+    .bind(blank_to_none(&payload.alternate_phone))
+    .bind(blank_to_none(&payload.email))
+    .bind(payload.notify_push.unwrap_or(true))
+    .bind(payload.notify_sms.unwrap_or(false))
+    .bind(payload.gate_notices.unwrap_or(true))
+    .execute(&pool)
+    .await
+    .map_err(conflict_or_db_error)?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Tutor registrado",
+        "id": result.last_insert_rowid()
+    })))
+}
+
+// datos de contacto y preferencias; las que no se envian se conservan
+pub async fn update_guardian(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<GuardianRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_guardian_admin(&session)?;
+    validate_guardian(&payload)?;
+    let current = fetch_guardian(&pool, id).await?;
+
+    sqlx::query(
+        "UPDATE tutores SET nombre = ?, telefono = ?, telefono_alterno = ?, correo = ?, notificar_push = ?, notificar_sms = ?, avisos_entrada_salida = ? WHERE id_tutor = ?",
+    )
+    .bind(payload.name.trim())
+    .bind(blank_to_none(&payload.phone))
+    .bind(blank_to_none(&payload.alternate_phone))
+    .bind(blank_to_none(&payload.email))
+    .bind(payload.notify_push.unwrap_or(current.notify_push))
+    .bind(payload.notify_sms.unwrap_or(current.notify_sms))
+    .bind(payload.gate_notices.unwrap_or(current.gate_notices))
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(conflict_or_db_error)?;
+
+    Ok(Json(serde_json::json!({"message": "Tutor actualizado"})))
+}
+
+// tutores de un estudiante con parentesco y restricciones de custodia
+pub async fn get_student_guardians(
+    session: StaffSession,
+    Path(student_id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Vec<GuardianLink>>, (StatusCode, Json<serde_json::Value>)> {
+    require_guardian_admin(&session)?;
+    Ok(Json(
+        links_for_student(&pool, &student_id)
+            .await
+            .map_err(db_error)?,
+    ))
+}
+
+// crear o actualizar el vinculo con un estudiante
+pub async fn link_student(
+    session: StaffSession,
+    Path((id, student_id)): Path<(i64, String)>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<GuardianLinkRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_guardian_admin(&session)?;
+    fetch_guardian(&pool, id).await?;
+
+    let student_exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM estudiantes WHERE id_control_escolar = ?)")
+            .bind(&student_id)
+            .fetch_one(&pool)
+            .await
+            .map_err(db_error)?;
+    if !student_exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+        ));
+    }
+
+    let relationship = payload
+        .relationship
+        .as_deref()
+        .unwrap_or("NO_ESPECIFICADO")
+        .to_uppercase();
+    if !constants::RELATIONSHIPS.contains(&relationship.as_str()) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Parentesco inválido, usa uno de {:?}", constants::RELATIONSHIPS)
+            })),
+        ));
+    }
+
+    let existing: Option<(bool, bool, bool, bool)> = sqlx::query_as(
+        "SELECT es_principal, puede_recoger, recibe_notificaciones, entrega_restringida FROM tutores_estudiantes WHERE id_tutor = ? AND id_control_escolar = ?",
+    )
+    .bind(id)
+    .bind(&student_id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(db_error)?;
+    let (primary, can_pick_up, receives_notifications, release_restricted) =
+        existing.unwrap_or((false, true, true, false));
+
+    sqlx::query(
+        r#"
+        INSERT INTO tutores_estudiantes
+            (id_tutor, id_control_escolar, parentesco, es_principal, puede_recoger, recibe_notificaciones, entrega_restringida, restriccion_custodia)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(id_tutor, id_control_escolar) DO UPDATE SET
+            parentesco = excluded.parentesco,
+            es_principal = excluded.es_principal,
+            puede_recoger = excluded.puede_recoger,
+            recibe_notificaciones = excluded.recibe_notificaciones,
+            entrega_restringida = excluded.entrega_restringida,
+            restriccion_custodia = excluded.restriccion_custodia
+        "#,
+    )
+    .bind(id)
+    .bind(&student_id)
+    .bind(&relationship)
+    .bind(payload.primary.unwrap_or(primary))
+    .bind(payload.can_pick_up.unwrap_or(can_pick_up))
+    .bind(payload.receives_notifications.unwrap_or(receives_notifications))
+    .bind(payload.release_restricted.unwrap_or(release_restricted))
+    .bind(blank_to_none(&payload.custody_notes))
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(
+        serde_json::json!({"message": "Tutor vinculado al estudiante"}),
+    ))
+}
+
+pub async fn unlink_student(
+    session: StaffSession,
+    Path((id, student_id)): Path<(i64, String)>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_guardian_admin(&session)?;
+    let result = sqlx::query(
+        "DELETE FROM tutores_estudiantes WHERE id_tutor = ? AND id_control_escolar = ?",
+    )
+    .bind(id)
+    .bind(&student_id)
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+
+    if result.rows_affected() == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "El tutor no está vinculado al estudiante"})),
+        ));
+    }
+
+    Ok(Json(
+        serde_json::json!({"message": "Tutor desvinculado del estudiante"}),
+    ))
+}
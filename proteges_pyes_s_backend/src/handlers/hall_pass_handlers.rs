@@ -0,0 +1,235 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Duration, Local};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    constants,
+    models::hall_pass::{HallPass, HallPassQuery, HallPassRequest},
+    utils::{
+        auth::StaffSession,
+        calendar::parse_date,
+        hall_passes::{mark_overdue, open_pass, HALL_PASS_SELECT},
+        notifications::{send_push_notification, NotificationKind},
+        students::resolve_student_code,
+    },
+};
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": format!("Error de base de datos: {}", e)})),
+    )
+}
+
+fn bad_request(message: String) -> ApiError {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({"error": message})),
+    )
+}
+
+fn blank_to_none(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn require_pass_role(session: &StaffSession) -> Result<(), ApiError> {
+    if session.has_role(&constants::HALL_PASS_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "Solo docentes y prefectos pueden manejar pases"})),
+        ))
+    }
+}
+
+async fn fetch_pass(pool: &Pool<Sqlite>, id: i64) -> Result<HallPass, ApiError> {
+    let mut pass = sqlx::query_as::<_, HallPass>(&format!("{} WHERE p.id = ?", HALL_PASS_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Pase no encontrado"})),
+            )
+        })?;
+    mark_overdue(&mut pass, Local::now());
+    Ok(pass)
+}
+
+// A student holds at most one open pass
+pub async fn issue_pass(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<HallPassRequest>,
+) -> Result<Json<HallPass>, ApiError> {
+    require_pass_role(&session)?;
+
+    let student_id = resolve_student_code(&pool, &payload.student_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+            )
+        })?;
+
+    let destination = payload.destination.trim().to_uppercase();
+    if !constants::HALL_PASS_DESTINATIONS.contains(&destination.as_str()) {
+        return Err(bad_request(format!(
+            "Destino inválido. Valores permitidos: {}",
+            constants::HALL_PASS_DESTINATIONS.join(", ")
+        )));
+    }
+    let minutes = payload
+        .minutes
+        .unwrap_or(constants::HALL_PASS_DEFAULT_MINUTES);
+    if !(1..=constants::HALL_PASS_MAX_MINUTES).contains(&minutes) {
+        return Err(bad_request(format!(
+            "La vigencia del pase debe ser de 1 a {} minutos",
+            constants::HALL_PASS_MAX_MINUTES
+        )));
+    }
+
+    if let Some(open) = open_pass(&pool, &student_id).await.map_err(db_error)? {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "El estudiante ya tiene un pase abierto",
+                "pass": open,
+            })),
+        ));
+    }
+
+    let now = Local::now();
+    let id = sqlx::query(
+        "INSERT INTO pases (id_control_escolar, destino, motivo, salon_origen, emitido_por, emitido_en, vence_en) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&student_id)
+    .bind(&destination)
+    .bind(blank_to_none(&payload.reason))
+    .bind(blank_to_none(&payload.classroom))
+    .bind(session.user_id)
+    .bind(now.to_rfc3339())
+    .bind((now + Duration::minutes(minutes)).to_rfc3339())
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        // idx_pases_abiertos: another pass was opened between the check and the insert
+        if e.to_string().contains("UNIQUE constraint failed") {
+            (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({"error": "El estudiante ya tiene un pase abierto"})),
+            )
+        } else {
+            db_error(e)
+        }
+    })?
+    .last_insert_rowid();
+
+    // An early exit always reaches the guardians; the gate scan closes the pass
+    if destination == "SALIDA_ANTICIPADA" {
+        let notify_pool = pool.clone();
+        let notify_student = student_id.clone();
+        let body = format!(
+            "Se autorizó la salida anticipada de su hijo/a a las {}.",
+            now.format("%H:%M")
+        );
+        tokio::spawn(async move {
+            if let Err(e) = send_push_notification(
+                &notify_pool,
+                &notify_student,
+                NotificationKind::Important,
+                "Salida anticipada autorizada",
+                &body,
+            )
+            .await
+            {
+                println!("Error enviando notificación de pase: {}", e);
+            }
+        });
+    }
+
+    Ok(Json(fetch_pass(&pool, id).await?))
+}
+
+pub async fn return_pass(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<HallPass>, ApiError> {
+    require_pass_role(&session)?;
+
+    let result = sqlx::query(
+        "UPDATE pases SET cerrado_en = ?, cerrado_por = ?, cierre = 'REGRESO' WHERE id = ? AND cerrado_en IS NULL",
+    )
+    .bind(Local::now().to_rfc3339())
+    .bind(session.user_id)
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+
+    let pass = fetch_pass(&pool, id).await?;
+    if result.rows_affected() == 0 {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "El pase ya estaba cerrado", "pass": pass})),
+        ));
+    }
+    Ok(Json(pass))
+}
+
+// Open passes (or every pass of `date`), optionally only a student's or only overdue ones
+pub async fn list_passes(
+    State(pool): State<Pool<Sqlite>>,
+    Query(query): Query<HallPassQuery>,
+) -> Result<Json<Vec<HallPass>>, ApiError> {
+    let date = match blank_to_none(&query.date) {
+        Some(date) => Some(
+            parse_date(&date)
+                .ok_or_else(|| {
+                    bad_request("La fecha debe tener el formato YYYY-MM-DD".to_string())
+                })?
+                .format("%Y-%m-%d")
+                .to_string(),
+        ),
+        None => None,
+    };
+
+    let mut passes = sqlx::query_as::<_, HallPass>(&format!(
+        r#"{}
+        WHERE (?1 IS NULL OR p.id_control_escolar = ?1)
+        AND (CASE WHEN ?2 IS NULL THEN p.cerrado_en IS NULL ELSE substr(p.emitido_en, 1, 10) = ?2 END)
+        ORDER BY p.vence_en
+        "#,
+        HALL_PASS_SELECT
+    ))
+    .bind(blank_to_none(&query.student_id))
+    .bind(&date)
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+
+    let now = Local::now();
+    for pass in &mut passes {
+        mark_overdue(pass, now);
+    }
+    if query.overdue.unwrap_or(false) {
+        passes.retain(|pass| pass.overdue);
+    }
+    Ok(Json(passes))
+}
@@ -0,0 +1,483 @@
+use axum::{
+    extract::{ConnectInfo, Multipart, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Local};
+use sqlx::{Pool, Sqlite};
+use std::net::SocketAddr;
+
+use crate::{
+    constants,
+    models::{
+        evidence::EvidenceLink,
+        incident::{Incident, IncidentActionRequest, IncidentDetail, IncidentListQuery},
+    },
+    utils::{
+        audit::log_access,
+        auth::StaffSession,
+        incidents::{find_incident, incident_detail, incident_students, INCIDENT_SELECT},
+        notifications::{send_push_notification, NotificationKind},
+        uploads::{evidence_link, store_upload, StoredUpload},
+    },
+};
+
+// files accepted with a single report
+pub const MAX_INCIDENT_EVIDENCE: usize = 5;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": format!("Error de base de datos: {}", e)})),
+    )
+}
+
+fn bad_request(message: String) -> ApiError {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({"error": message})),
+    )
+}
+
+fn not_found() -> ApiError {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({"error": "Incidente no encontrado"})),
+    )
+}
+
+fn blank_to_none(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn require_role(session: &StaffSession, roles: &[&str], message: &str) -> Result<(), ApiError> {
+    if session.has_role(roles) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": message})),
+        ))
+    }
+}
+
+fn require_incident_staff(session: &StaffSession) -> Result<(), ApiError> {
+    require_role(
+        session,
+        &constants::INCIDENT_STAFF_ROLES,
+        "No tienes permiso para consultar los incidentes",
+    )
+}
+
+fn validate_choice(value: &str, allowed: &[&str], field: &str) -> Result<String, ApiError> {
+    let value = value.trim().to_uppercase();
+    if allowed.contains(&value.as_str()) {
+        Ok(value)
+    } else {
+        Err(bad_request(format!(
+            "Valor de {} inválido. Valores permitidos: {}",
+            field,
+            allowed.join(", ")
+        )))
+    }
+}
+
+fn notify_guardians(pool: &Pool<Sqlite>, student_ids: Vec<String>, title: String, body: String) {
+    let pool = pool.clone();
+    tokio::spawn(async move {
+        for student_id in student_ids {
+            if let Err(e) = send_push_notification(
+                &pool,
+                &student_id,
+                NotificationKind::Important,
+                &title,
+                &body,
+            )
+            .await
+            {
+                println!("Error enviando notificación de incidente: {}", e);
+            }
+        }
+    });
+}
+
+#[derive(Default)]
+struct IncidentForm {
+    student_ids: Vec<String>,
+    category: String,
+    severity: String,
+    description: String,
+    location: Option<String>,
+    occurred_at: Option<String>,
+}
+
+// Validates the report and stores it with its students and evidence in one transaction
+async fn insert_incident(
+    pool: &Pool<Sqlite>,
+    session: &StaffSession,
+    form: &IncidentForm,
+    evidence: &[StoredUpload],
+) -> Result<(i64, Vec<String>), ApiError> {
+    require_role(
+        session,
+        &constants::INCIDENT_REPORTER_ROLES,
+        "No tienes permiso para reportar incidentes",
+    )?;
+    let user_id = session.user_id;
+
+    let category = validate_choice(&form.category, &constants::INCIDENT_CATEGORIES, "categoría")?;
+    let severity = validate_choice(&form.severity, &constants::INCIDENT_SEVERITIES, "severidad")?;
+    let description = form.description.trim();
+    if description.is_empty() {
+        return Err(bad_request("La descripción es obligatoria".to_string()));
+    }
+
+    let mut student_ids: Vec<String> = Vec::new();
+    for student_id in &form.student_ids {
+        if !student_ids.contains(student_id) {
+            student_ids.push(student_id.clone());
+        }
+    }
+    if student_ids.is_empty() {
+        return Err(bad_request(
+            "Indica al menos un estudiante involucrado".to_string(),
+        ));
+    }
+    for student_id in &student_ids {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM estudiantes WHERE id_control_escolar = ?)",
+        )
+        .bind(student_id)
+        .fetch_one(pool)
+        .await
+        .map_err(db_error)?;
+        if !exists {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(
+                    serde_json::json!({"error": format!("Estudiante {} no encontrado", student_id)}),
+                ),
+            ));
+        }
+    }
+
+    let now = Local::now();
+    let occurred_at = match blank_to_none(&form.occurred_at) {
+        Some(value) => DateTime::parse_from_rfc3339(&value)
+            .map_err(|_| bad_request("Fecha del incidente inválida, use RFC 3339".to_string()))?
+            .with_timezone(&Local),
+        None => now,
+    };
+    if occurred_at > now {
+        return Err(bad_request(
+            "La fecha del incidente no puede ser futura".to_string(),
+        ));
+    }
+
+    let insert = async {
+        let mut tx = pool.begin().await?;
+        let id = sqlx::query(
+            "INSERT INTO incidentes (categoria, severidad, descripcion, lugar, ocurrido_en, reportado_por, creado_en) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&category)
+        .bind(&severity)
+        .bind(description)
+        .bind(blank_to_none(&form.location))
+        .bind(occurred_at.to_rfc3339())
+        .bind(user_id)
+        .bind(now.to_rfc3339())
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        for student_id in &student_ids {
+            sqlx::query(
+                "INSERT INTO incidentes_estudiantes (id_incidente, id_control_escolar) VALUES (?, ?)",
+            )
+            .bind(id)
+            .bind(student_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        for upload in evidence {
+            let evidence_id = upload.save(&mut *tx).await?;
+            sqlx::query("INSERT INTO incidentes_evidencias (id_incidente, evidencia_id) VALUES (?, ?)")
+                .bind(id)
+                .bind(evidence_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(id)
+    }
+    .await
+    .map_err(db_error)?;
+
+    Ok((insert, student_ids))
+}
+
+// Multipart: student_id (repeatable or comma separated), category, severity,
+// description, location, occurred_at and up to MAX_INCIDENT_EVIDENCE `evidence` files
+pub async fn create_incident(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    mut multipart: Multipart,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let mut form = IncidentForm::default();
+    let mut evidence: Vec<StoredUpload> = Vec::new();
+
+    let read = async {
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|e| bad_request(e.body_text()))?
+        {
+            let name = field.name().unwrap_or("").to_string();
+            if name == "evidence" {
+                if evidence.len() == MAX_INCIDENT_EVIDENCE {
+                    return Err(bad_request(format!(
+                        "Se aceptan como máximo {} archivos de evidencia",
+                        MAX_INCIDENT_EVIDENCE
+                    )));
+                }
+                evidence.push(store_upload(field).await?);
+                continue;
+            }
+
+            let data = field.text().await.map_err(|e| bad_request(e.body_text()))?;
+            match name.as_str() {
+                "student_id" | "student_ids" => form.student_ids.extend(
+                    data.split(',')
+                        .map(str::trim)
+                        .filter(|id| !id.is_empty())
+                        .map(str::to_string),
+                ),
+                "category" => form.category = data,
+                "severity" => form.severity = data,
+                "description" => form.description = data,
+                "location" => form.location = Some(data),
+                "occurred_at" => form.occurred_at = Some(data),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+    .await;
+
+    let result = match read {
+        Ok(()) => insert_incident(&pool, &session, &form, &evidence).await,
+        Err(e) => Err(e),
+    };
+    let (id, student_ids) = match result {
+        Ok(created) => created,
+        Err(e) => {
+            for upload in &evidence {
+                upload.discard().await;
+            }
+            return Err(e);
+        }
+    };
+
+    notify_guardians(
+        &pool,
+        student_ids,
+        "Reporte de incidente".to_string(),
+        format!(
+            "Se registró un incidente ({}) que involucra a su hijo/a. Consulte el reporte en el portal y confirme de enterado.",
+            form.category.trim().to_uppercase()
+        ),
+    );
+
+    Ok(Json(serde_json::json!({
+        "message": "Incidente reportado",
+        "id": id,
+        "evidence_files": evidence.len(),
+    })))
+}
+
+pub async fn list_incidents(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Query(query): Query<IncidentListQuery>,
+) -> Result<Json<Vec<Incident>>, ApiError> {
+    require_incident_staff(&session)?;
+
+    let incidents = sqlx::query_as::<_, Incident>(&format!(
+        r#"{}
+        WHERE (?1 IS NULL OR i.id IN (SELECT id_incidente FROM incidentes_estudiantes WHERE id_control_escolar = ?1))
+        AND (?2 IS NULL OR i.estado = ?2)
+        AND (?3 IS NULL OR i.categoria = ?3)
+        AND (?4 IS NULL OR substr(i.ocurrido_en, 1, 10) >= ?4)
+        AND (?5 IS NULL OR substr(i.ocurrido_en, 1, 10) <= ?5)
+        ORDER BY i.ocurrido_en DESC
+        LIMIT 200
+        "#,
+        INCIDENT_SELECT
+    ))
+    .bind(blank_to_none(&query.student_id))
+    .bind(blank_to_none(&query.status).map(|status| status.to_uppercase()))
+    .bind(blank_to_none(&query.category).map(|category| category.to_uppercase()))
+    .bind(blank_to_none(&query.from))
+    .bind(blank_to_none(&query.to))
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(incidents))
+}
+
+pub async fn get_incident(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<IncidentDetail>, ApiError> {
+    require_incident_staff(&session)?;
+    let incident = find_incident(&pool, id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(not_found)?;
+    Ok(Json(
+        incident_detail(&pool, incident).await.map_err(db_error)?,
+    ))
+}
+
+// Disciplinary history of a student; every read is logged
+pub async fn get_student_incidents(
+    session: StaffSession,
+    Path(student_id): Path<String>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Vec<IncidentDetail>>, ApiError> {
+    require_incident_staff(&session)?;
+
+    let incidents = sqlx::query_as::<_, Incident>(&format!(
+        "{} JOIN incidentes_estudiantes ie ON ie.id_incidente = i.id WHERE ie.id_control_escolar = ? ORDER BY i.ocurrido_en DESC",
+        INCIDENT_SELECT
+    ))
+    .bind(&student_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+
+    let mut history = Vec::new();
+    for incident in incidents {
+        history.push(incident_detail(&pool, incident).await.map_err(db_error)?);
+    }
+
+    log_access(
+        &pool,
+        "INCIDENTES",
+        &student_id,
+        "HISTORIAL",
+        &session.actor(),
+        Some(&addr.ip().to_string()),
+    )
+    .await;
+
+    Ok(Json(history))
+}
+
+// Follow-up on an incident; CIERRE closes it and nothing can be added afterwards
+pub async fn add_incident_action(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<IncidentActionRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_incident_staff(&session)?;
+    let action = validate_choice(&payload.action, &constants::INCIDENT_ACTIONS, "acción")?;
+    let notes = blank_to_none(&payload.notes);
+
+    let incident = find_incident(&pool, id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(not_found)?;
+    if incident.status == "CERRADO" {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "El incidente ya está cerrado"})),
+        ));
+    }
+
+    let mut tx = pool.begin().await.map_err(db_error)?;
+    let action_id = sqlx::query(
+        "INSERT INTO acciones_incidente (id_incidente, accion, notas, registrado_por, registrado_en) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(id)
+    .bind(&action)
+    .bind(&notes)
+    .bind(session.user_id)
+    .bind(Local::now().to_rfc3339())
+    .execute(&mut *tx)
+    .await
+    .map_err(db_error)?
+    .last_insert_rowid();
+    if action == "CIERRE" {
+        sqlx::query("UPDATE incidentes SET estado = 'CERRADO' WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+    }
+    tx.commit().await.map_err(db_error)?;
+
+    // Guardians have to act on a summons or a suspension
+    if action == "CITATORIO" || action == "SUSPENSION" {
+        let student_ids = incident_students(&pool, id)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .map(|student| student.student_id)
+            .collect();
+        let title = if action == "CITATORIO" {
+            "Citatorio"
+        } else {
+            "Suspensión"
+        };
+        notify_guardians(
+            &pool,
+            student_ids,
+            title.to_string(),
+            notes.unwrap_or_else(|| {
+                "Consulte el seguimiento del incidente en el portal.".to_string()
+            }),
+        );
+    }
+
+    Ok(Json(serde_json::json!({
+        "message": "Seguimiento registrado",
+        "id": action_id,
+        "status": if action == "CIERRE" { "CERRADO" } else { "ABIERTO" },
+    })))
+}
+
+// Short-lived signed link to one of the incident's evidence files
+pub async fn get_incident_evidence_link(
+    session: StaffSession,
+    Path((id, evidence_id)): Path<(i64, i64)>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<EvidenceLink>, ApiError> {
+    require_incident_staff(&session)?;
+
+    let attached: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM incidentes_evidencias WHERE id_incidente = ? AND evidencia_id = ?)",
+    )
+    .bind(id)
+    .bind(evidence_id)
+    .fetch_one(&pool)
+    .await
+    .map_err(db_error)?;
+    if !attached {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Evidencia no encontrada"})),
+        ));
+    }
+
+    Ok(Json(evidence_link(evidence_id, &session.actor())))
+}
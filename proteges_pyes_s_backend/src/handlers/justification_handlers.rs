@@ -1,75 +1,190 @@
-use crate::{models::justification::CreateJustificationResponse, state::AppState};
-use axum::extract::multipart::Field;
+use crate::{
+    constants,
+    models::{
+        evidence::EvidenceLink,
+        justification::{
+            CreateJustificationResponse, Justification, JustificationDetail,
+            JustificationListQuery, JustificationPage,
+        },
+    },
+    state::AppState,
+    utils::{
+        auth::{guardian_has_student, GuardianSession, StaffSession},
+        calendar::parse_date,
+        justifications::{apply_justification, revert_justification},
+        notifications::{send_push_notification, NotificationKind},
+        uploads::{evidence_link, store_upload, StoredUpload},
+    },
+};
 use axum::{
-    extract::{Multipart, State},
+    extract::{Multipart, Query, State},
     http::StatusCode,
     Json,
 };
-use std::path::Path;
-use tokio::fs;
-use tokio::io::AsyncWriteExt;
 
+// longest range a single justificante may cover
+const MAX_RANGE_DAYS: i64 = 31;
+
+// Justificante reasons are private: only staff allowed to review them can read them
+fn require_reviewer(session: &StaffSession) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if session.has_role(&constants::EVIDENCE_VIEWER_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({ "error": "No tienes permiso para revisar justificantes" })),
+        ))
+    }
+}
+
+// Review decisions: pending requests are approved or rejected and only an approval can be
+// revoked; a rejected request is corrected by resubmitting it
+fn allowed_transition(from: &str, to: &str) -> bool {
+    matches!(
+        (from, to),
+        ("PENDIENTE", "APROBADO") | ("PENDIENTE", "RECHAZADO") | ("APROBADO", "REVOCADO")
+    )
+}
+
+// Checks the date range and, for a resubmission, that it follows a rejected request of the
+// same student that hasn't been resubmitted yet. Returns the id being resubmitted.
+async fn validate_submission(
+    state: &AppState,
+    student_id: &str,
+    date: &str,
+    end_date: &str,
+    resubmission_of: Option<&str>,
+) -> Result<Option<i64>, (StatusCode, Json<serde_json::Value>)> {
+    let bad_request = |message: &str| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": message })),
+        )
+    };
+
+    let (Some(start), Some(end)) = (parse_date(date), parse_date(end_date)) else {
+        return Err(bad_request("Las fechas deben tener el formato YYYY-MM-DD"));
+    };
+    if end < start {
+        return Err(bad_request(
+            "La fecha final no puede ser anterior a la inicial",
+        ));
+    }
+    if (end - start).num_days() >= MAX_RANGE_DAYS {
+        return Err(bad_request(&format!(
+            "Un justificante cubre como máximo {} días",
+            MAX_RANGE_DAYS
+        )));
+    }
+
+    let Some(previous_id) = resubmission_of.filter(|value| !value.trim().is_empty()) else {
+        return Ok(None);
+    };
+    let previous_id: i64 = previous_id
+        .trim()
+        .parse()
+        .map_err(|_| bad_request("resubmission_of debe ser un id de justificante"))?;
+
+    let previous: Option<(String, String, bool)> = sqlx::query_as(
+        "SELECT estudiante_id, estado, EXISTS(SELECT 1 FROM justificantes r WHERE r.reenvio_de = j.id) FROM justificantes j WHERE id = ?",
+    )
+    .bind(previous_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+    })?;
+
+    match previous {
+        None => Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "Justificante original no encontrado" })),
+        )),
+        Some((previous_student, _, _)) if previous_student != student_id => Err(bad_request(
+            "El justificante original es de otro estudiante",
+        )),
+        Some((_, status, _)) if status != "RECHAZADO" => Err((
+            StatusCode::CONFLICT,
+            Json(
+                serde_json::json!({ "error": "Solo se puede reenviar un justificante rechazado" }),
+            ),
+        )),
+        Some((_, _, true)) => Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": "El justificante ya fue reenviado" })),
+        )),
+        Some(_) => Ok(Some(previous_id)),
+    }
+}
+
+// Guardians submit from the portal, only for their own linked students
 pub async fn submit_justification(
+    session: GuardianSession,
     State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> Result<Json<CreateJustificationResponse>, (StatusCode, Json<serde_json::Value>)> {
     let mut student_id = String::new();
     let mut date = String::new();
+    let mut end_date = String::new();
     let mut reason = String::new();
-    let mut evidence_path: Option<String> = None;
+    let mut resubmission_of: Option<String> = None;
+    let mut evidence: Option<StoredUpload> = None;
 
-    while let Ok(Some(field)) = multipart.next_field().await {
-        let name = field.name().unwrap_or("").to_string();
-
-        if name == "evidence" {
-            let filename = field.file_name().unwrap_or("evidence.jpg").to_string();
-            // We need to read the bytes. Axum 0.7/0.8 uses `bytes()` or stream.
-            let data = match field.bytes().await {
-                Ok(bytes) => bytes,
-                Err(e) => {
-                    return Err((
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(
-                            serde_json::json!({ "error": format!("Failed to read file bytes: {}", e) }),
-                        ),
-                    ))
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => {
+                if let Some(evidence) = &evidence {
+                    evidence.discard().await;
                 }
-            };
-
-            let timestamp = chrono::Local::now().timestamp();
-            // Sanitize filename a bit
-            let safe_filename = format!("{}_{}", timestamp, filename.replace(" ", "_"));
-            let filepath = format!("uploads/{}", safe_filename);
-
-            if let Err(e) = fs::write(&filepath, data).await {
                 return Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({ "error": format!("Failed to save file: {}", e) })),
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "error": e.to_string() })),
                 ));
             }
-            evidence_path = Some(filepath);
+        };
+        let name = field.name().unwrap_or("").to_string();
+
+        if name == "evidence" {
+            // Validated by content, size-capped and stored under a server-generated name
+            if let Some(previous) = evidence.take() {
+                previous.discard().await;
+            }
+            evidence = Some(store_upload(field).await?);
         } else {
             // For text fields
             let data = match field.text().await {
                 Ok(text) => text,
                 Err(e) => {
+                    if let Some(evidence) = &evidence {
+                        evidence.discard().await;
+                    }
                     return Err((
                         StatusCode::BAD_REQUEST,
                         Json(serde_json::json!({ "error": e.to_string() })),
-                    ))
+                    ));
                 }
             };
 
             match name.as_str() {
                 "student_id" => student_id = data,
                 "date" => date = data,
+                "end_date" => end_date = data,
                 "reason" => reason = data,
+                "resubmission_of" => resubmission_of = Some(data),
                 _ => {}
             }
         }
     }
 
     if student_id.is_empty() || date.is_empty() || reason.is_empty() {
+        if let Some(evidence) = &evidence {
+            evidence.discard().await;
+        }
         return Err((
             StatusCode::BAD_REQUEST,
             Json(
@@ -78,23 +193,80 @@ pub async fn submit_justification(
         ));
     }
 
-    let result = sqlx::query(
-        "INSERT INTO justificantes (estudiante_id, fecha_justificacion, motivo, evidencia_url) VALUES (?, ?, ?, ?)"
+    // someone else's child looks the same as a missing one
+    let linked = guardian_has_student(&state.db, session.guardian_id, &student_id).await;
+    if !matches!(linked, Ok(true)) {
+        if let Some(evidence) = &evidence {
+            evidence.discard().await;
+        }
+        return Err(match linked {
+            Err(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            ),
+            _ => (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({ "error": "Estudiante no encontrado" })),
+            ),
+        });
+    }
+
+    // A single day unless an end_date is given
+    if end_date.trim().is_empty() {
+        end_date = date.clone();
+    }
+    let resubmission_of = match validate_submission(
+        &state,
+        &student_id,
+        &date,
+        &end_date,
+        resubmission_of.as_deref(),
     )
-    .bind(&student_id)
-    .bind(&date)
-    .bind(&reason)
-    .bind(&evidence_path)
-    .execute(&state.db)
     .await
-    .map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-             Json(serde_json::json!({ "error": e.to_string() })),
+    {
+        Ok(resubmission_of) => resubmission_of,
+        Err(e) => {
+            if let Some(evidence) = &evidence {
+                evidence.discard().await;
+            }
+            return Err(e);
+        }
+    };
+
+    let insert = async {
+        let mut tx = state.db.begin().await?;
+        let evidence_id = match &evidence {
+            Some(evidence) => Some(evidence.save(&mut *tx).await?),
+            None => None,
+        };
+        let result = sqlx::query(
+            "INSERT INTO justificantes (estudiante_id, fecha_justificacion, fecha_fin, motivo, evidencia_id, reenvio_de) VALUES (?, ?, ?, ?, ?, ?)"
         )
-    })?;
+        .bind(&student_id)
+        .bind(&date)
+        .bind(&end_date)
+        .bind(&reason)
+        .bind(evidence_id)
+        .bind(resubmission_of)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(result.last_insert_rowid())
+    }
+    .await;
 
-    let id = result.last_insert_rowid();
+    let id = match insert {
+        Ok(id) => id,
+        Err(e) => {
+            if let Some(evidence) = &evidence {
+                evidence.discard().await;
+            }
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            ));
+        }
+    };
 
     Ok(Json(CreateJustificationResponse {
         message: "Justificación enviada correctamente".to_string(),
@@ -103,11 +275,13 @@ pub async fn submit_justification(
 }
 
 pub async fn get_pending_justifications(
+    session: StaffSession,
     State(state): State<AppState>,
 ) -> Result<
     Json<Vec<crate::models::justification::Justification>>,
     (StatusCode, Json<serde_json::Value>),
 > {
+    require_reviewer(&session)?;
     let justifications = sqlx::query_as::<_, crate::models::justification::Justification>(
         "SELECT * FROM justificantes WHERE estado = 'PENDIENTE' ORDER BY fecha_solicitud DESC",
     )
@@ -123,37 +297,275 @@ pub async fn get_pending_justifications(
     Ok(Json(justifications))
 }
 
+// Paginated listing, optionally filtered by student and status (newest first)
+pub async fn list_justifications(
+    session: StaffSession,
+    State(state): State<AppState>,
+    Query(query): Query<JustificationListQuery>,
+) -> Result<Json<JustificationPage>, (StatusCode, Json<serde_json::Value>)> {
+    require_reviewer(&session)?;
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+
+    let db_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+    };
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT count(*) FROM justificantes WHERE (?1 IS NULL OR estudiante_id = ?1) AND (?2 IS NULL OR estado = ?2)",
+    )
+    .bind(&query.student_id)
+    .bind(&query.status)
+    .fetch_one(&state.db)
+    .await
+    .map_err(db_error)?;
+
+    let items = sqlx::query_as::<_, Justification>(
+        "SELECT * FROM justificantes WHERE (?1 IS NULL OR estudiante_id = ?1) AND (?2 IS NULL OR estado = ?2) ORDER BY fecha_solicitud DESC, id DESC LIMIT ?3 OFFSET ?4",
+    )
+    .bind(&query.student_id)
+    .bind(&query.status)
+    .bind(per_page)
+    .bind((page - 1) * per_page)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(JustificationPage {
+        items,
+        page,
+        per_page,
+        total,
+    }))
+}
+
+// A justificante with the chain of rejected requests it resubmits
+pub async fn get_justification(
+    session: StaffSession,
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> Result<Json<JustificationDetail>, (StatusCode, Json<serde_json::Value>)> {
+    require_reviewer(&session)?;
+    let db_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+    };
+    let fetch = |id: i64| {
+        sqlx::query_as::<_, Justification>("SELECT * FROM justificantes WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&state.db)
+    };
+
+    let justification = fetch(id).await.map_err(db_error)?.ok_or((
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "error": "Justificante no encontrado" })),
+    ))?;
+
+    let mut previous_attempts: Vec<Justification> = Vec::new();
+    let mut next = justification.reenvio_de;
+    while let Some(previous_id) = next {
+        let Some(previous) = fetch(previous_id).await.map_err(db_error)? else {
+            break;
+        };
+        next = previous.reenvio_de;
+        previous_attempts.push(previous);
+    }
+
+    Ok(Json(JustificationDetail {
+        justification,
+        previous_attempts,
+    }))
+}
+
 pub async fn update_justification_status(
+    session: StaffSession,
     State(state): State<AppState>,
     axum::extract::Path(id): axum::extract::Path<i64>,
     Json(payload): Json<crate::models::justification::UpdateJustificationStatusRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    // Validate status
-    if payload.status != "APROBADO" && payload.status != "RECHAZADO" {
+    // Validate status (REVOCADO withdraws a previous approval)
+    if !["APROBADO", "RECHAZADO", "REVOCADO"].contains(&payload.status.as_str()) {
         return Err((
             StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({ "error": "Status must be APROBADO or RECHAZADO" })),
+            Json(serde_json::json!({ "error": "Status must be APROBADO, RECHAZADO or REVOCADO" })),
         ));
     }
 
-    sqlx::query("UPDATE justificantes SET estado = ?, comentario_admin = ? WHERE id = ?")
+    let db_error = |e: sqlx::Error| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+    };
+
+    // Reviewers are the staff allowed to see the evidence
+    require_reviewer(&session)?;
+
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+
+    let justification = sqlx::query_as::<_, crate::models::justification::Justification>(
+        "SELECT * FROM justificantes WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(db_error)?
+    .ok_or((
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({ "error": "Justificante no encontrado" })),
+    ))?;
+
+    let invalid_transition = || {
+        (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": format!(
+                    "Un justificante {} no puede pasar a {}",
+                    justification.estado, payload.status
+                ),
+                "status": justification.estado,
+            })),
+        )
+    };
+    if !allowed_transition(&justification.estado, &payload.status) {
+        return Err(invalid_transition());
+    }
+
+    // the state is checked again in the UPDATE so two reviewers can't both decide
+    let result = sqlx::query("UPDATE justificantes SET estado = ?, comentario_admin = ?, revisado_por = ?, fecha_revision = ? WHERE id = ? AND estado = ?")
         .bind(&payload.status)
         .bind(&payload.admin_comment)
+        .bind(session.user_id)
+        .bind(chrono::Local::now().to_rfc3339())
         .bind(id)
-        .execute(&state.db)
+        .bind(&justification.estado)
+        .execute(&mut *tx)
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": e.to_string() })),
-            )
-        })?;
+        .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err(invalid_transition());
+    }
+
+    // Keep the attendance records in sync with the decision
+    let updated_records = if payload.status == "APROBADO" {
+        apply_justification(&mut tx, &justification)
+            .await
+            .map_err(db_error)?
+    } else {
+        revert_justification(&mut tx, id).await.map_err(db_error)?
+    };
 
-    // Optional: Send notification to student (parent) about status change
-    // We would need to fetch student_id first.
-    // Simplifying for now.
+    tx.commit().await.map_err(db_error)?;
+
+    // Let the guardian know how the request was resolved
+    let days = match justification.fecha_fin.as_deref() {
+        Some(end) if end != justification.fecha_justificacion => {
+            format!("del {} al {}", justification.fecha_justificacion, end)
+        }
+        _ => format!("del {}", justification.fecha_justificacion),
+    };
+    let (title, mut body) = match payload.status.as_str() {
+        "APROBADO" => (
+            "Justificante Aprobado",
+            format!("El justificante {} fue aprobado.", days),
+        ),
+        "RECHAZADO" => (
+            "Justificante Rechazado",
+            format!(
+                "El justificante {} fue rechazado. Puedes corregirlo y volver a enviarlo desde la app.",
+                days
+            ),
+        ),
+        _ => (
+            "Justificante Revocado",
+            format!("La aprobación del justificante {} fue revocada.", days),
+        ),
+    };
+    if let Some(comment) = payload
+        .admin_comment
+        .as_deref()
+        .filter(|c| !c.trim().is_empty())
+    {
+        body.push_str(&format!(" Comentario: {}", comment.trim()));
+    }
+    let pool = state.db.clone();
+    let student_id = justification.estudiante_id.clone();
+    tokio::spawn(async move {
+        if let Err(e) = send_push_notification(
+            &pool,
+            &student_id,
+            NotificationKind::Important,
+            title,
+            &body,
+        )
+        .await
+        {
+            println!("Error enviando notificación de justificante: {}", e);
+        }
+    });
 
     Ok(Json(serde_json::json!({
-        "message": "Estado actualizado correctamente"
+        "message": "Estado actualizado correctamente",
+        "updated_records": updated_records
     })))
 }
+
+// Short-lived signed link to a justificante's evidence for staff with an allowed role
+// (guardians get theirs through the portal)
+pub async fn get_evidence_link(
+    session: StaffSession,
+    State(state): State<AppState>,
+    axum::extract::Path(id): axum::extract::Path<i64>,
+) -> Result<Json<EvidenceLink>, (StatusCode, Json<serde_json::Value>)> {
+    if !session.has_role(&constants::EVIDENCE_VIEWER_ROLES) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({ "error": "No tienes permiso para ver esta evidencia" })),
+        ));
+    }
+
+    let evidence_id = justification_evidence(&state, id, None).await?;
+    Ok(Json(evidence_link(evidence_id, &session.actor())))
+}
+
+// Evidence id of a justificante; with `student_ids` the justificante must belong to one of them
+pub async fn justification_evidence(
+    state: &AppState,
+    id: i64,
+    student_ids: Option<&[String]>,
+) -> Result<i64, (StatusCode, Json<serde_json::Value>)> {
+    let justification: Option<(String, Option<i64>)> =
+        sqlx::query_as("SELECT estudiante_id, evidencia_id FROM justificantes WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({ "error": e.to_string() })),
+                )
+            })?;
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "Justificante no encontrado" })),
+        )
+    };
+    let (student_id, evidence_id) = justification.ok_or_else(not_found)?;
+    if student_ids.is_some_and(|ids| !ids.contains(&student_id)) {
+        return Err(not_found());
+    }
+
+    evidence_id.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "El justificante no tiene evidencia" })),
+        )
+    })
+}
@@ -0,0 +1,351 @@
+use axum::{
+    extract::{ConnectInfo, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Local};
+use sqlx::{Pool, Sqlite};
+use std::net::SocketAddr;
+
+use crate::{
+    constants,
+    models::{
+        medical::{
+            InfirmaryVisit, InfirmaryVisitRequest, MedicalItem, MedicalItemRequest, MedicalQuery,
+            MedicalRecord,
+        },
+        student::Student,
+    },
+    utils::{
+        audit::log_access,
+        auth::StaffSession,
+        medical::{active_items, recent_visits, VISIT_SELECT},
+        notifications::{send_push_notification, NotificationKind},
+    },
+};
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": format!("Error de base de datos: {}", e)})),
+    )
+}
+
+fn bad_request(message: String) -> ApiError {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({"error": message})),
+    )
+}
+
+fn blank_to_none(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn require_medical_staff(session: &StaffSession) -> Result<(), ApiError> {
+    if session.has_role(&constants::MEDICAL_STAFF_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(
+                serde_json::json!({"error": "Solo el personal médico puede acceder al expediente"}),
+            ),
+        ))
+    }
+}
+
+async fn fetch_student(pool: &Pool<Sqlite>, student_id: &str) -> Result<Student, ApiError> {
+    sqlx::query_as::<_, Student>("SELECT * FROM estudiantes WHERE id_control_escolar = ?")
+        .bind(student_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+            )
+        })
+}
+
+fn validate_severity(severity: &Option<String>) -> Result<Option<String>, ApiError> {
+    let severity = blank_to_none(severity).map(|severity| severity.to_uppercase());
+    if let Some(severity) = &severity {
+        if !constants::ALLERGY_SEVERITIES.contains(&severity.as_str()) {
+            return Err(bad_request(format!(
+                "Severidad inválida. Valores permitidos: {}",
+                constants::ALLERGY_SEVERITIES.join(", ")
+            )));
+        }
+    }
+    Ok(severity)
+}
+
+fn validate_name(name: &str) -> Result<String, ApiError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(bad_request("El nombre es obligatorio".to_string()));
+    }
+    Ok(name.to_string())
+}
+
+// Full medical record of a student; every read is logged
+pub async fn get_medical_record(
+    session: StaffSession,
+    Path(student_id): Path<String>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<MedicalRecord>, ApiError> {
+    require_medical_staff(&session)?;
+    let student = fetch_student(&pool, &student_id).await?;
+
+    let items = active_items(&pool, &student_id).await.map_err(db_error)?;
+    let (mut allergies, mut conditions, mut medications) = (Vec::new(), Vec::new(), Vec::new());
+    for item in items {
+        match item.kind.as_str() {
+            "ALERGIA" => allergies.push(item),
+            "CONDICION" => conditions.push(item),
+            _ => medications.push(item),
+        }
+    }
+    let visits = recent_visits(&pool, &student_id).await.map_err(db_error)?;
+
+    log_access(
+        &pool,
+        "DATOS_MEDICOS",
+        &student_id,
+        "EXPEDIENTE",
+        &session.actor(),
+        Some(&addr.ip().to_string()),
+    )
+    .await;
+
+    Ok(Json(MedicalRecord {
+        name: format!("{} {}", student.names, student.paternal_last_name),
+        student_id: student.id,
+        group: student.group,
+        blood_type: student.blood_type,
+        allergies,
+        conditions,
+        medications,
+        visits,
+    }))
+}
+
+pub async fn create_medical_item(
+    session: StaffSession,
+    Path(student_id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<MedicalItemRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_medical_staff(&session)?;
+    fetch_student(&pool, &student_id).await?;
+
+    let kind = blank_to_none(&payload.kind)
+        .map(|kind| kind.to_uppercase())
+        .filter(|kind| constants::MEDICAL_ITEM_KINDS.contains(&kind.as_str()))
+        .ok_or_else(|| {
+            bad_request(format!(
+                "Tipo inválido. Valores permitidos: {}",
+                constants::MEDICAL_ITEM_KINDS.join(", ")
+            ))
+        })?;
+    let name = validate_name(&payload.name)?;
+    // Only allergies carry a severity
+    let severity = if kind == "ALERGIA" {
+        validate_severity(&payload.severity)?
+    } else {
+        None
+    };
+
+    let id = sqlx::query(
+        "INSERT INTO antecedentes_medicos (id_control_escolar, tipo, nombre, detalle, severidad, registrado_por, actualizado_en) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&student_id)
+    .bind(&kind)
+    .bind(&name)
+    .bind(blank_to_none(&payload.detail))
+    .bind(&severity)
+    .bind(session.user_id)
+    .bind(Local::now().to_rfc3339())
+    .execute(&pool)
+    .await
+    .map_err(db_error)?
+    .last_insert_rowid();
+
+    Ok(Json(serde_json::json!({
+        "message": "Registro médico agregado",
+        "id": id,
+    })))
+}
+
+pub async fn update_medical_item(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<MedicalItemRequest>,
+) -> Result<Json<MedicalItem>, ApiError> {
+    require_medical_staff(&session)?;
+    let name = validate_name(&payload.name)?;
+    let severity = validate_severity(&payload.severity)?;
+
+    let item = sqlx::query_as::<_, MedicalItem>(
+        "UPDATE antecedentes_medicos SET nombre = ?, detalle = ?, severidad = CASE WHEN tipo = 'ALERGIA' THEN ? END, activo = COALESCE(?, activo), registrado_por = ?, actualizado_en = ? WHERE id = ? RETURNING *",
+    )
+    .bind(&name)
+    .bind(blank_to_none(&payload.detail))
+    .bind(&severity)
+    .bind(payload.active)
+    .bind(session.user_id)
+    .bind(Local::now().to_rfc3339())
+    .bind(id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(db_error)?
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Registro médico no encontrado"})),
+        )
+    })?;
+
+    Ok(Json(item))
+}
+
+// Items are deactivated, never deleted, so the history stays
+pub async fn deactivate_medical_item(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_medical_staff(&session)?;
+
+    let result = sqlx::query(
+        "UPDATE antecedentes_medicos SET activo = FALSE, registrado_por = ?, actualizado_en = ? WHERE id = ?",
+    )
+    .bind(session.user_id)
+    .bind(Local::now().to_rfc3339())
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Registro médico no encontrado"})),
+        ));
+    }
+
+    Ok(Json(
+        serde_json::json!({"message": "Registro médico dado de baja"}),
+    ))
+}
+
+pub async fn record_infirmary_visit(
+    session: StaffSession,
+    Path(student_id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<InfirmaryVisitRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_medical_staff(&session)?;
+    let student = fetch_student(&pool, &student_id).await?;
+
+    let symptoms = payload.symptoms.trim();
+    if symptoms.is_empty() {
+        return Err(bad_request("Los síntomas son obligatorios".to_string()));
+    }
+    let now = Local::now();
+    let arrived_at = match blank_to_none(&payload.arrived_at) {
+        Some(value) => DateTime::parse_from_rfc3339(&value)
+            .map_err(|_| bad_request("Hora de llegada inválida, use RFC 3339".to_string()))?
+            .with_timezone(&Local),
+        None => now,
+    };
+    let sent_home = payload.sent_home.unwrap_or(false);
+
+    let id = sqlx::query(
+        "INSERT INTO visitas_enfermeria (id_control_escolar, atendido_por, llegada, sintomas, tratamiento, enviado_a_casa, notas, registrado_en) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&student_id)
+    .bind(session.user_id)
+    .bind(arrived_at.to_rfc3339())
+    .bind(symptoms)
+    .bind(blank_to_none(&payload.treatment))
+    .bind(sent_home)
+    .bind(blank_to_none(&payload.notes))
+    .bind(now.to_rfc3339())
+    .execute(&pool)
+    .await
+    .map_err(db_error)?
+    .last_insert_rowid();
+
+    // Guardians hear about every visit; a student sent home needs to be picked up
+    let body = if sent_home {
+        format!(
+            "{} fue atendido/a en la enfermería a las {} y debe retirarse a casa. Por favor acuda a recogerlo/a.",
+            student.names,
+            arrived_at.format("%H:%M")
+        )
+    } else {
+        format!(
+            "{} fue atendido/a en la enfermería a las {} y regresó a clases.",
+            student.names,
+            arrived_at.format("%H:%M")
+        )
+    };
+    let notify_pool = pool.clone();
+    let notify_student = student_id.clone();
+    tokio::spawn(async move {
+        if let Err(e) = send_push_notification(
+            &notify_pool,
+            &notify_student,
+            NotificationKind::Important,
+            "Visita a la enfermería",
+            &body,
+        )
+        .await
+        {
+            println!("Error enviando notificación de enfermería: {}", e);
+        }
+    });
+
+    Ok(Json(serde_json::json!({
+        "message": "Visita registrada",
+        "id": id,
+        "sent_home": sent_home,
+    })))
+}
+
+// Infirmary log, optionally for one student and a date range
+pub async fn get_infirmary_visits(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Query(query): Query<MedicalQuery>,
+) -> Result<Json<Vec<InfirmaryVisit>>, ApiError> {
+    require_medical_staff(&session)?;
+
+    let visits = sqlx::query_as::<_, InfirmaryVisit>(&format!(
+        r#"{}
+        WHERE (?1 IS NULL OR v.id_control_escolar = ?1)
+        AND (?2 IS NULL OR substr(v.llegada, 1, 10) >= ?2)
+        AND (?3 IS NULL OR substr(v.llegada, 1, 10) <= ?3)
+        ORDER BY v.llegada DESC
+        "#,
+        VISIT_SELECT
+    ))
+    .bind(blank_to_none(&query.student_id))
+    .bind(blank_to_none(&query.from))
+    .bind(blank_to_none(&query.to))
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(visits))
+}
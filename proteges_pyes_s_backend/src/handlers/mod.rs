@@ -1,11 +1,21 @@
+pub mod assembly_handlers;
 pub mod attendance_handlers;
 pub mod auth_handlers;
+pub mod calendar_handlers;
+pub mod card_handlers;
 pub mod emergency_handlers;
+pub mod file_handlers;
 pub mod group_handlers;
+pub mod guardian_handlers;
+pub mod hall_pass_handlers;
+pub mod incident_handlers;
 pub mod justification_handlers;
-pub mod notification_handlers;
+pub mod medical_handlers;
 pub mod parent_handlers;
+pub mod pickup_handlers;
+pub mod presence_handlers;
 pub mod scan_handlers;
+pub mod schedule_handlers;
 pub mod stats_handlers;
 pub mod student_handlers;
 pub mod user_handlers;
@@ -1,12 +1,56 @@
 use axum::{
-    extract::{Path, State},
+    extract::{ConnectInfo, Path, Query, State},
     http::StatusCode,
     Json,
 };
+use chrono::{Local, Utc};
 use serde::Serialize;
 use sqlx::FromRow;
+use std::net::SocketAddr;
+use tracing::warn;
 
+use crate::handlers::justification_handlers::justification_evidence;
+use crate::models::attendance::AttendanceSummary;
+use crate::models::evidence::EvidenceLink;
+use crate::models::guardian::Guardian;
+use crate::models::incident::{PortalIncident, PortalIncidentQuery};
+use crate::models::justification::Justification;
+use crate::models::portal::{
+    PortalDeviceRequest, PortalJustificationQuery, PortalProfile, PortalSession,
+    RequestCodeRequest, VerifyCodeRequest,
+};
 use crate::state::AppState;
+use crate::utils::{
+    attendance_summary::{attendance_by_day, summarize},
+    auth::{guardian_has_student, session_token_hash, GuardianSession},
+    calendar::last_school_days,
+    emergency::{current_session, protocol_notice, student_status},
+    guardians::links_for_guardian,
+    incidents::incident_actions,
+    pickups::release_today,
+    sms::{mask_phone, normalize_phone, send_sms},
+    tokens::{constant_time_eq, sign},
+    uploads::evidence_link,
+};
+
+// vigencia del codigo enviado por SMS
+const CODE_TTL_SECS: i64 = 10 * 60;
+// espera minima antes de mandar otro codigo al mismo tutor
+const CODE_RESEND_SECS: i64 = 60;
+const CODE_MAX_ATTEMPTS: i64 = 5;
+// codigos equivocados por IP (contra cualquier telefono) antes de bloquearla un rato
+const IP_MAX_FAILURES: i64 = 20;
+const IP_FAILURE_WINDOW_SECS: i64 = 15 * 60;
+const SESSION_TTL_DAYS: i64 = 90;
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": e.to_string()})),
+    )
+}
 
 #[derive(Debug, Serialize, FromRow)]
 pub struct StudentBasicInfo {
@@ -24,25 +68,59 @@ pub struct StudentAttendanceRecord {
     pub fecha_asistencia: String,
     pub presente: bool,
     pub salon_clase: String,
+    pub tipo_registro: Option<String>,
+    pub clasificacion: Option<String>,
+    pub justificacion: Option<String>,
+    pub id_justificante: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct EmergencyStatusInfo {
     pub scanned: bool,
+    pub released: bool,
+    pub status: String,   // LOCALIZADO, LESIONADO, ENTREGADO, FALTANTE
+    pub phase: String,    // EVACUACION, REUNIFICACION
+    pub protocol: String, // EVACUACION, CONFINAMIENTO, RESGUARDO
+    pub instructions: String,
+    pub reunification_point: Option<String>,
+}
+
+// who picked the student up today
+#[derive(Debug, Serialize)]
+pub struct PortalRelease {
+    pub received_by: String,
+    pub released_at: String,
+    pub during_emergency: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct StudentPortalInfo {
     pub student: StudentBasicInfo,
     pub attendance: Vec<StudentAttendanceRecord>,
+    pub attendance_summary: AttendanceSummary,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub emergency_status: Option<EmergencyStatusInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release: Option<PortalRelease>,
 }
 
-pub async fn get_student_portal_info(
-    Path(student_id): Path<String>,
-    State(state): State<AppState>,
-) -> Result<Json<StudentPortalInfo>, (StatusCode, Json<serde_json::Value>)> {
+async fn student_portal_info(
+    state: &AppState,
+    guardian_id: i64,
+    student_id: &str,
+) -> Result<StudentPortalInfo, ApiError> {
+    // a guardian the student may not be released to doesn't learn where the student is
+    // during an emergency
+    let release_restricted: bool = sqlx::query_scalar(
+        "SELECT entrega_restringida FROM tutores_estudiantes WHERE id_tutor = ? AND id_control_escolar = ?",
+    )
+    .bind(guardian_id)
+    .bind(student_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error)?
+    .unwrap_or(true);
+
     // Get student basic info
     let student = sqlx::query_as::<_, StudentBasicInfo>(
         r#"
@@ -57,7 +135,7 @@ pub async fn get_student_portal_info(
         WHERE id_control_escolar = ?
         "#,
     )
-    .bind(&student_id)
+    .bind(student_id)
     .fetch_optional(&state.db)
     .await
     .map_err(|e| {
@@ -74,22 +152,50 @@ pub async fn get_student_portal_info(
         )
     })?;
 
-    // Get attendance history (last 30 days)
+    // Last 30 school days according to the calendar (holidays and weekends are skipped)
+    let school_days = last_school_days(
+        &state.db,
+        Local::now().date_naive(),
+        30,
+        Some(&student.grupo),
+    )
+    .await
+    .unwrap_or_else(|e| {
+        eprintln!("Error loading school calendar (returning empty): {:?}", e);
+        Vec::new()
+    });
+    let since = school_days
+        .first()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
+
+    let attendance_summary = summarize(
+        &attendance_by_day(&state.db, student_id, &school_days)
+            .await
+            .unwrap_or_default(),
+    );
+
+    // Get attendance history within those school days
     let attendance = sqlx::query_as::<_, StudentAttendanceRecord>(
         r#"
         SELECT 
             id_asistencia,
             fecha_asistencia,
             presente,
-            salon_clase
+            salon_clase,
+            tipo_registro,
+            clasificacion,
+            justificacion,
+            id_justificante
         FROM asistencias
         WHERE id_control_escolar = ?
-        AND datetime(fecha_asistencia) >= datetime('now', '-30 days')
+        AND substr(fecha_asistencia, 1, 10) >= ?
         ORDER BY fecha_asistencia DESC
-        LIMIT 30
+        LIMIT 100
         "#,
     )
-    .bind(&student_id)
+    .bind(student_id)
+    .bind(&since)
     .fetch_all(&state.db)
     .await
     .unwrap_or_else(|e| {
@@ -97,32 +203,496 @@ pub async fn get_student_portal_info(
         Vec::new()
     });
 
-    // Check if emergency is active
-    let emergency_active = *state.emergency_active.read().await;
-    let emergency_status = if emergency_active {
-        // Check if student is scanned
-        let scanned = sqlx::query_scalar::<_, i64>(
-            r#"
-            SELECT COUNT(*)
-            FROM historial_consulta
-            WHERE estudiante_consultado = ?
-            "#,
-        )
-        .bind(&student_id)
-        .fetch_one(&state.db)
-        .await
-        .unwrap_or(0);
-
-        Some(EmergencyStatusInfo {
-            scanned: scanned > 0,
-        })
-    } else {
+    // Status of the student in the emergency in progress (evacuation or reunification)
+    let session = current_session(&state.db).await.unwrap_or_else(|e| {
+        eprintln!("Error fetching emergency session (ignored): {:?}", e);
         None
+    });
+    // (a student who wasn't on campus when it started isn't part of it)
+    let status = match &session {
+        Some(session) if !release_restricted => student_status(&state.db, session.id, student_id)
+            .await
+            .unwrap_or_default(),
+        _ => None,
+    };
+    let emergency_status = match (session, status) {
+        (Some(session), Some(status)) => Some(EmergencyStatusInfo {
+            scanned: status != "FALTANTE",
+            released: status == "ENTREGADO",
+            status,
+            instructions: protocol_notice(&session.protocol).1.to_string(),
+            phase: session.phase,
+            protocol: session.protocol,
+            reunification_point: session.reunification_point,
+        }),
+        _ => None,
     };
 
-    Ok(Json(StudentPortalInfo {
+    let release = release_today(&state.db, student_id)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Error fetching release (ignored): {:?}", e);
+            None
+        })
+        .filter(|release| !(release_restricted && release.during_emergency))
+        .map(|release| PortalRelease {
+            received_by: release.received_by,
+            released_at: release.released_at,
+            during_emergency: release.during_emergency,
+        });
+
+    Ok(StudentPortalInfo {
         student,
         attendance,
+        attendance_summary,
         emergency_status,
+        release,
+    })
+}
+
+async fn find_guardian_by_phone(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    phone: &str,
+) -> Result<Option<(i64, String)>, sqlx::Error> {
+    let wanted = normalize_phone(phone);
+    if wanted.len() < 7 {
+        return Ok(None);
+    }
+    // se compara sin espacios ni lada para aceptar el formato que capture el tutor
+    let guardians: Vec<(i64, Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT id_tutor, telefono, telefono_alterno FROM tutores")
+            .fetch_all(pool)
+            .await?;
+    Ok(guardians.into_iter().find_map(|(id, phone, alternate)| {
+        [phone, alternate]
+            .into_iter()
+            .flatten()
+            .find(|candidate| normalize_phone(candidate) == wanted)
+            .map(|candidate| (id, candidate))
+    }))
+}
+
+fn code_hash(guardian_id: i64, code: &str) -> String {
+    sign(&format!("codigo.{}.{}", guardian_id, code))
+}
+
+// Counts a failed code for the IP throttle; rows past the window are no longer needed
+async fn record_failed_code(state: &AppState, ip: &str, now: i64) -> Result<(), ApiError> {
+    sqlx::query("DELETE FROM intentos_portal WHERE creado_en <= ?")
+        .bind(now - IP_FAILURE_WINDOW_SECS)
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+    sqlx::query("INSERT INTO intentos_portal (ip, creado_en) VALUES (?, ?)")
+        .bind(ip)
+        .bind(now)
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+    Ok(())
+}
+
+// Step 1 of the login: send a one-time code to the guardian phone on record. The response
+// is the same whether or not the phone exists so it can't be used to look up guardians.
+pub async fn request_code(
+    State(state): State<AppState>,
+    Json(payload): Json<RequestCodeRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let response = Json(serde_json::json!({
+        "message": "Si el teléfono está registrado recibirás un código por SMS"
+    }));
+
+    let Some((guardian_id, phone)) = find_guardian_by_phone(&state.db, &payload.phone)
+        .await
+        .map_err(db_error)?
+    else {
+        return Ok(response);
+    };
+
+    // only guardians linked to at least one student can sign in
+    let has_students: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM tutores_estudiantes WHERE id_tutor = ?)")
+            .bind(guardian_id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(db_error)?;
+    if !has_students {
+        return Ok(response);
+    }
+
+    let now = Utc::now().timestamp();
+    let recently_sent: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM codigos_verificacion WHERE id_tutor = ? AND creado_en > ?)",
+    )
+    .bind(guardian_id)
+    .bind(now - CODE_RESEND_SECS)
+    .fetch_one(&state.db)
+    .await
+    .map_err(db_error)?;
+    if recently_sent {
+        return Ok(response);
+    }
+
+    let code = format!("{:06}", uuid::Uuid::new_v4().as_u128() % 1_000_000);
+
+    // a new code replaces any pending one
+    sqlx::query("UPDATE codigos_verificacion SET usado = TRUE WHERE id_tutor = ? AND NOT usado")
+        .bind(guardian_id)
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+    sqlx::query(
+        "INSERT INTO codigos_verificacion (id_tutor, codigo_hash, expira_en, creado_en) VALUES (?, ?, ?, ?)",
+    )
+    .bind(guardian_id)
+    .bind(code_hash(guardian_id, &code))
+    .bind(now + CODE_TTL_SECS)
+    .bind(now)
+    .execute(&state.db)
+    .await
+    .map_err(db_error)?;
+
+    let message = format!(
+        "Tu código de acceso a Proteges-PyEs es {}. Vence en {} minutos.",
+        code,
+        CODE_TTL_SECS / 60
+    );
+    if let Err(e) = send_sms(&phone, &message).await {
+        warn!("Error enviando código a {}: {}", mask_phone(&phone), e);
+    }
+
+    Ok(response)
+}
+
+// Step 2: exchange the code for a session token
+pub async fn verify_code(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(payload): Json<VerifyCodeRequest>,
+) -> Result<Json<PortalSession>, ApiError> {
+    let invalid = || {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Código inválido o vencido"})),
+        )
+    };
+
+    // the per-code attempt limit doesn't stop trying one code against many phones
+    let ip = addr.ip().to_string();
+    let now = Utc::now().timestamp();
+    let recent_failures: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM intentos_portal WHERE ip = ? AND creado_en > ?")
+            .bind(&ip)
+            .bind(now - IP_FAILURE_WINDOW_SECS)
+            .fetch_one(&state.db)
+            .await
+            .map_err(db_error)?;
+    if recent_failures >= IP_MAX_FAILURES {
+        return Err((
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(serde_json::json!({"error": "Demasiados intentos, espera unos minutos"})),
+        ));
+    }
+
+    let Some((guardian_id, _)) = find_guardian_by_phone(&state.db, &payload.phone)
+        .await
+        .map_err(db_error)?
+    else {
+        record_failed_code(&state, &ip, now).await?;
+        return Err(invalid());
+    };
+
+    let pending: Option<(i64, String)> = sqlx::query_as(
+        "SELECT id, codigo_hash FROM codigos_verificacion WHERE id_tutor = ? AND NOT usado AND expira_en > ? ORDER BY id DESC LIMIT 1",
+    )
+    .bind(guardian_id)
+    .bind(now)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error)?;
+    let Some((code_id, expected_hash)) = pending else {
+        record_failed_code(&state, &ip, now).await?;
+        return Err(invalid());
+    };
+
+    if !constant_time_eq(&code_hash(guardian_id, payload.code.trim()), &expected_hash) {
+        // too many wrong attempts burn the code
+        sqlx::query(
+            "UPDATE codigos_verificacion SET intentos = intentos + 1, usado = (intentos + 1 >= ?) WHERE id = ?",
+        )
+        .bind(CODE_MAX_ATTEMPTS)
+        .bind(code_id)
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+        record_failed_code(&state, &ip, now).await?;
+        return Err(invalid());
+    }
+
+    sqlx::query("UPDATE codigos_verificacion SET usado = TRUE WHERE id = ?")
+        .bind(code_id)
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let expires = now + SESSION_TTL_DAYS * 24 * 60 * 60;
+    sqlx::query(
+        "INSERT INTO sesiones_tutores (token_hash, id_tutor, nombre_dispositivo, creado_en, expira_en) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(session_token_hash(&token))
+    .bind(guardian_id)
+    .bind(&payload.device_name)
+    .bind(now)
+    .bind(expires)
+    .execute(&state.db)
+    .await
+    .map_err(db_error)?;
+
+    let guardian = sqlx::query_as::<_, Guardian>("SELECT * FROM tutores WHERE id_tutor = ?")
+        .bind(guardian_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(PortalSession {
+        token,
+        expires_at: chrono::DateTime::from_timestamp(expires, 0)
+            .unwrap_or_default()
+            .to_rfc3339(),
+        guardian,
     }))
 }
+
+pub async fn logout(
+    session: GuardianSession,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    sqlx::query("UPDATE sesiones_tutores SET revocada = TRUE WHERE token_hash = ?")
+        .bind(&session.token_hash)
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+    Ok(Json(serde_json::json!({"message": "Sesión cerrada"})))
+}
+
+pub async fn get_profile(
+    session: GuardianSession,
+    State(state): State<AppState>,
+) -> Result<Json<PortalProfile>, ApiError> {
+    let guardian = sqlx::query_as::<_, Guardian>("SELECT * FROM tutores WHERE id_tutor = ?")
+        .bind(session.guardian_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(db_error)?;
+    let students = links_for_guardian(&state.db, session.guardian_id)
+        .await
+        .map_err(db_error)?;
+    Ok(Json(PortalProfile { guardian, students }))
+}
+
+async fn guardian_student_ids(state: &AppState, guardian_id: i64) -> Result<Vec<String>, ApiError> {
+    sqlx::query_scalar(
+        "SELECT id_control_escolar FROM tutores_estudiantes WHERE id_tutor = ? ORDER BY id_control_escolar",
+    )
+    .bind(guardian_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error)
+}
+
+// attendance and emergency status of every child of the guardian
+pub async fn get_children(
+    session: GuardianSession,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<StudentPortalInfo>>, ApiError> {
+    let mut children = Vec::new();
+    for student_id in guardian_student_ids(&state, session.guardian_id).await? {
+        children.push(student_portal_info(&state, session.guardian_id, &student_id).await?);
+    }
+    Ok(Json(children))
+}
+
+pub async fn get_child(
+    session: GuardianSession,
+    Path(student_id): Path<String>,
+    State(state): State<AppState>,
+) -> Result<Json<StudentPortalInfo>, ApiError> {
+    // someone else's child looks the same as a missing one
+    if !guardian_has_student(&state.db, session.guardian_id, &student_id)
+        .await
+        .map_err(db_error)?
+    {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+        ));
+    }
+    Ok(Json(
+        student_portal_info(&state, session.guardian_id, &student_id).await?,
+    ))
+}
+
+// push notifications for this device, for all of the guardian's children
+pub async fn register_device(
+    session: GuardianSession,
+    State(state): State<AppState>,
+    Json(payload): Json<PortalDeviceRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if payload.token.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "El token del dispositivo es obligatorio"})),
+        ));
+    }
+
+    // a device belongs to one guardian at a time
+    sqlx::query("DELETE FROM push_tokens WHERE token = ?")
+        .bind(payload.token.trim())
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+    sqlx::query("INSERT INTO push_tokens (token, device_name, tutor_id) VALUES (?, ?, ?)")
+        .bind(payload.token.trim())
+        .bind(&payload.device_name)
+        .bind(session.guardian_id)
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(
+        serde_json::json!({"message": "Dispositivo registrado para notificaciones"}),
+    ))
+}
+
+// justificantes of the guardian's children (optionally one of them)
+pub async fn get_justifications(
+    session: GuardianSession,
+    Query(query): Query<PortalJustificationQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Justification>>, ApiError> {
+    let justifications = sqlx::query_as::<_, Justification>(
+        r#"
+        SELECT j.* FROM justificantes j
+        JOIN tutores_estudiantes te ON te.id_control_escolar = j.estudiante_id
+        WHERE te.id_tutor = ? AND (? IS NULL OR j.estudiante_id = ?)
+        ORDER BY j.fecha_solicitud DESC, j.id DESC
+        LIMIT 100
+        "#,
+    )
+    .bind(session.guardian_id)
+    .bind(&query.student_id)
+    .bind(&query.student_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error)?;
+    Ok(Json(justifications))
+}
+
+pub async fn get_justification_evidence(
+    session: GuardianSession,
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<EvidenceLink>, ApiError> {
+    let children = guardian_student_ids(&state, session.guardian_id).await?;
+    let evidence_id = justification_evidence(&state, id, Some(&children)).await?;
+    Ok(Json(evidence_link(
+        evidence_id,
+        &format!("tutor:{}", session.guardian_id),
+    )))
+}
+
+// incidents involving the guardian's children (optionally one of them)
+pub async fn get_incidents(
+    session: GuardianSession,
+    Query(query): Query<PortalIncidentQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<PortalIncident>>, ApiError> {
+    let mut incidents = sqlx::query_as::<_, PortalIncident>(
+        r#"
+        SELECT
+            i.id,
+            ie.id_control_escolar AS student_id,
+            i.categoria AS category,
+            i.severidad AS severity,
+            i.descripcion AS description,
+            i.lugar AS location,
+            i.ocurrido_en AS occurred_at,
+            i.estado AS status,
+            ie.acuse_en AS acknowledged_at
+        FROM incidentes i
+        JOIN incidentes_estudiantes ie ON ie.id_incidente = i.id
+        JOIN tutores_estudiantes te ON te.id_control_escolar = ie.id_control_escolar
+        WHERE te.id_tutor = ? AND (? IS NULL OR ie.id_control_escolar = ?)
+        ORDER BY i.ocurrido_en DESC
+        LIMIT 100
+        "#,
+    )
+    .bind(session.guardian_id)
+    .bind(&query.student_id)
+    .bind(&query.student_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error)?;
+
+    for incident in &mut incidents {
+        incident.actions = incident_actions(&state.db, incident.id)
+            .await
+            .map_err(db_error)?;
+    }
+    Ok(Json(incidents))
+}
+
+// the guardian confirms they read the report, for each of their children involved
+pub async fn acknowledge_incident(
+    session: GuardianSession,
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let children = guardian_student_ids(&state, session.guardian_id).await?;
+    let involved: Vec<(String, Option<String>)> = sqlx::query_as(
+        "SELECT id_control_escolar, acuse_en FROM incidentes_estudiantes WHERE id_incidente = ?",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(db_error)?
+    .into_iter()
+    .filter(|(student_id, _)| children.contains(student_id))
+    .collect();
+    if involved.is_empty() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Incidente no encontrado"})),
+        ));
+    }
+
+    let now = Local::now().to_rfc3339();
+    let mut acknowledged = Vec::new();
+    for (student_id, acknowledged_at) in involved {
+        // the first acknowledgment is the one that counts
+        if acknowledged_at.is_some() {
+            continue;
+        }
+        sqlx::query(
+            "UPDATE incidentes_estudiantes SET acuse_tutor = ?, acuse_en = ? WHERE id_incidente = ? AND id_control_escolar = ? AND acuse_en IS NULL",
+        )
+        .bind(session.guardian_id)
+        .bind(&now)
+        .bind(id)
+        .bind(&student_id)
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+        acknowledged.push(student_id);
+    }
+
+    Ok(Json(serde_json::json!({
+        "message": "Acuse de recibo registrado",
+        "students": acknowledged,
+    })))
+}
@@ -0,0 +1,471 @@
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Local;
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    constants,
+    models::{
+        evidence::EvidenceLink,
+        pickup::{
+            AuthorizedPerson, AuthorizedPersonRequest, PickupContact, Release, ReleaseQuery,
+            ReleaseRequest,
+        },
+    },
+    state::AppState,
+    utils::{
+        auth::StaffSession,
+        emergency::{current_session, set_student_status},
+        guardians::links_for_student,
+        hall_passes::close_open_pass,
+        notifications::{send_push_notification, NotificationKind},
+        pickups::{release_today, RELEASE_SELECT},
+        uploads::{evidence_link, store_upload, StoredUpload},
+    },
+};
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": e.to_string()})),
+    )
+}
+
+fn blank_to_none(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn forbidden(message: &str) -> ApiError {
+    (
+        StatusCode::FORBIDDEN,
+        Json(serde_json::json!({"error": message})),
+    )
+}
+
+fn require_release_role(session: &StaffSession) -> Result<(), ApiError> {
+    if session.has_role(&constants::RELEASE_ROLES) {
+        Ok(())
+    } else {
+        Err(forbidden("No tienes permiso para entregar alumnos"))
+    }
+}
+
+async fn fetch_person(pool: &Pool<Sqlite>, id: i64) -> Result<AuthorizedPerson, ApiError> {
+    sqlx::query_as::<_, AuthorizedPerson>("SELECT * FROM personas_autorizadas WHERE id_persona = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Persona autorizada no encontrada"})),
+            )
+        })
+}
+
+fn validate_person(payload: &AuthorizedPersonRequest) -> Result<Option<String>, ApiError> {
+    if payload.name.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "El nombre es obligatorio"})),
+        ));
+    }
+    let relationship = blank_to_none(&payload.relationship).map(|value| value.to_uppercase());
+    if let Some(relationship) = &relationship {
+        if !constants::RELATIONSHIPS.contains(&relationship.as_str()) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("Parentesco inválido. Valores permitidos: {}", constants::RELATIONSHIPS.join(", "))
+                })),
+            ));
+        }
+    }
+    Ok(relationship)
+}
+
+// tutores y personas autorizadas del alumno, indicando a quien se le puede entregar
+pub async fn get_pickup_contacts(
+    session: StaffSession,
+    Path(student_id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Vec<PickupContact>>, ApiError> {
+    require_release_role(&session)?;
+    let mut contacts: Vec<PickupContact> = links_for_student(&pool, &student_id)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|link| PickupContact {
+            kind: "TUTOR",
+            id: link.guardian_id,
+            name: link.guardian_name,
+            relationship: link.relationship,
+            phone: link.guardian_phone,
+            id_type: None,
+            id_number: None,
+            photo_id: None,
+            allowed: link.can_pick_up && !link.release_restricted,
+            custody_notes: link.custody_notes,
+        })
+        .collect();
+
+    let persons = sqlx::query_as::<_, AuthorizedPerson>(
+        "SELECT * FROM personas_autorizadas WHERE id_control_escolar = ? ORDER BY nombre",
+    )
+    .bind(&student_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+
+    contacts.extend(persons.into_iter().map(|person| PickupContact {
+        kind: "PERSONA",
+        id: person.id,
+        name: person.name,
+        relationship: person.relationship,
+        phone: person.phone,
+        id_type: person.id_type,
+        id_number: person.id_number,
+        photo_id: person.photo_id,
+        allowed: person.active,
+        custody_notes: None,
+    }));
+
+    Ok(Json(contacts))
+}
+
+pub async fn create_authorized_person(
+    session: StaffSession,
+    Path(student_id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<AuthorizedPersonRequest>,
+) -> Result<Json<AuthorizedPerson>, ApiError> {
+    require_release_role(&session)?;
+    let relationship = validate_person(&payload)?;
+
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM estudiantes WHERE id_control_escolar = ?)")
+            .bind(&student_id)
+            .fetch_one(&pool)
+            .await
+            .map_err(db_error)?;
+    if !exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+        ));
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO personas_autorizadas (id_control_escolar, nombre, parentesco, telefono, tipo_identificacion, numero_identificacion, activa) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&student_id)
+    .bind(payload.name.trim())
+    .bind(relationship.as_deref().unwrap_or("NO_ESPECIFICADO"))
+    .bind(blank_to_none(&payload.phone))
+    .bind(blank_to_none(&payload.id_type))
+    .bind(blank_to_none(&payload.id_number))
+    .bind(payload.active.unwrap_or(true))
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(fetch_person(&pool, result.last_insert_rowid()).await?))
+}
+
+pub async fn update_authorized_person(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<AuthorizedPersonRequest>,
+) -> Result<Json<AuthorizedPerson>, ApiError> {
+    require_release_role(&session)?;
+    let relationship = validate_person(&payload)?;
+    let current = fetch_person(&pool, id).await?;
+
+    sqlx::query(
+        "UPDATE personas_autorizadas SET nombre = ?, parentesco = ?, telefono = ?, tipo_identificacion = ?, numero_identificacion = ?, activa = ? WHERE id_persona = ?",
+    )
+    .bind(payload.name.trim())
+    .bind(relationship.unwrap_or(current.relationship))
+    .bind(blank_to_none(&payload.phone))
+    .bind(blank_to_none(&payload.id_type))
+    .bind(blank_to_none(&payload.id_number))
+    .bind(payload.active.unwrap_or(current.active))
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(fetch_person(&pool, id).await?))
+}
+
+// se desactiva en lugar de borrar: las entregas anteriores siguen apuntando a la persona
+pub async fn deactivate_authorized_person(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_release_role(&session)?;
+    fetch_person(&pool, id).await?;
+    sqlx::query("UPDATE personas_autorizadas SET activa = FALSE WHERE id_persona = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+    Ok(Json(
+        serde_json::json!({"message": "Persona autorizada desactivada"}),
+    ))
+}
+
+// foto de la persona (campo `photo`); pasa por la misma validacion que las evidencias
+pub async fn upload_person_photo(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    mut multipart: Multipart,
+) -> Result<Json<AuthorizedPerson>, ApiError> {
+    require_release_role(&session)?;
+    fetch_person(&pool, id).await?;
+
+    let mut photo: Option<StoredUpload> = None;
+    while let Ok(Some(field)) = multipart.next_field().await {
+        if field.name() == Some("photo") {
+            if let Some(previous) = photo.take() {
+                previous.discard().await;
+            }
+            photo = Some(store_upload(field).await?);
+        }
+    }
+
+    let Some(photo) = photo else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Falta el campo photo"})),
+        ));
+    };
+    if !photo.mime.starts_with("image/") {
+        photo.discard().await;
+        return Err((
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Json(serde_json::json!({"error": "La foto debe ser una imagen JPEG o PNG"})),
+        ));
+    }
+
+    let saved = async {
+        let mut tx = pool.begin().await?;
+        let photo_id = photo.save(&mut *tx).await?;
+        sqlx::query("UPDATE personas_autorizadas SET foto_id = ? WHERE id_persona = ?")
+            .bind(photo_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+    .await;
+    if let Err(e) = saved {
+        photo.discard().await;
+        return Err(db_error(e));
+    }
+
+    Ok(Json(fetch_person(&pool, id).await?))
+}
+
+// enlace firmado a la foto para quien va a entregar al alumno
+pub async fn get_person_photo(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<EvidenceLink>, ApiError> {
+    require_release_role(&session)?;
+
+    let photo_id = fetch_person(&pool, id).await?.photo_id.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "La persona no tiene foto registrada"})),
+        )
+    })?;
+
+    Ok(Json(evidence_link(photo_id, &session.actor())))
+}
+
+// entregar al alumno: solo a un tutor que puede recogerlo y sin entrega restringida,
+// o a una persona autorizada activa del mismo alumno; queda registrado quien inicio sesion
+pub async fn release_student(
+    session: StaffSession,
+    State(state): State<AppState>,
+    Json(payload): Json<ReleaseRequest>,
+) -> Result<Json<Release>, ApiError> {
+    require_release_role(&session)?;
+    let user_id = session.user_id;
+
+    let student_name: String = sqlx::query_scalar(
+        "SELECT nombres || ' ' || apellido_paterno FROM estudiantes WHERE id_control_escolar = ?",
+    )
+    .bind(&payload.student_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(db_error)?
+    .ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+        )
+    })?;
+
+    let received_by = match (payload.guardian_id, payload.authorized_person_id) {
+        (Some(guardian_id), None) => {
+            let link = links_for_student(&state.db, &payload.student_id)
+                .await
+                .map_err(db_error)?
+                .into_iter()
+                .find(|link| link.guardian_id == guardian_id)
+                .ok_or_else(|| forbidden("El tutor no está vinculado a este alumno"))?;
+            if link.release_restricted {
+                return Err((
+                    StatusCode::FORBIDDEN,
+                    Json(serde_json::json!({
+                        "error": "El tutor tiene una restricción de custodia; no se puede entregar al alumno",
+                        "custody_notes": link.custody_notes,
+                    })),
+                ));
+            }
+            if !link.can_pick_up {
+                return Err(forbidden(
+                    "El tutor no está autorizado para recoger al alumno",
+                ));
+            }
+            link.guardian_name
+        }
+        (None, Some(person_id)) => {
+            let person = fetch_person(&state.db, person_id).await?;
+            if person.student_id != payload.student_id || !person.active {
+                return Err(forbidden(
+                    "La persona no está autorizada para recoger a este alumno",
+                ));
+            }
+            person.name
+        }
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": "Indica guardian_id o authorized_person_id (solo uno)"
+                })),
+            ))
+        }
+    };
+
+    if let Some(previous) = release_today(&state.db, &payload.student_id)
+        .await
+        .map_err(db_error)?
+    {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": format!("El alumno ya fue entregado hoy a {}", previous.received_by),
+                "release": previous,
+            })),
+        ));
+    }
+
+    // during an emergency the release also resolves the student in the session
+    let now = Local::now();
+    let insert = async {
+        let mut tx = state.db.begin().await?;
+        let session = current_session(&mut *tx).await?;
+        let result = sqlx::query(
+            "INSERT INTO entregas_estudiantes (id_control_escolar, id_tutor, id_persona, recibido_por, entregado_por, identificacion_verificada, durante_emergencia, notas, fecha_entrega, sesion_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&payload.student_id)
+        .bind(payload.guardian_id)
+        .bind(payload.authorized_person_id)
+        .bind(&received_by)
+        .bind(user_id)
+        .bind(payload.id_verified.unwrap_or(false))
+        .bind(session.is_some())
+        .bind(blank_to_none(&payload.notes))
+        .bind(now.to_rfc3339())
+        .bind(session.as_ref().map(|session| session.id))
+        .execute(&mut *tx)
+        .await?;
+        if let Some(session) = &session {
+            set_student_status(
+                &mut *tx,
+                session.id,
+                &payload.student_id,
+                "ENTREGADO",
+                user_id,
+                Some(&format!("Entregado a {}", received_by)),
+            )
+            .await?;
+        }
+        close_open_pass(&mut *tx, &payload.student_id, user_id, "SALIDA_PLANTEL").await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(result.last_insert_rowid())
+    };
+    let release_id = insert.await.map_err(db_error)?;
+
+    let release =
+        sqlx::query_as::<_, Release>(&format!("{} WHERE r.id_entrega = ?", RELEASE_SELECT))
+            .bind(release_id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(db_error)?;
+
+    let pool = state.db.clone();
+    let student_id = payload.student_id.clone();
+    let body = format!(
+        "{} fue entregado/a a {} a las {}.",
+        student_name,
+        received_by,
+        now.format("%H:%M")
+    );
+    tokio::spawn(async move {
+        if let Err(e) = send_push_notification(
+            &pool,
+            &student_id,
+            NotificationKind::Important,
+            "Estudiante Entregado",
+            &body,
+        )
+        .await
+        {
+            println!("Error enviando notificación de entrega: {}", e);
+        }
+    });
+
+    Ok(Json(release))
+}
+
+pub async fn get_releases(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Query(query): Query<ReleaseQuery>,
+) -> Result<Json<Vec<Release>>, ApiError> {
+    require_release_role(&session)?;
+    let date =
+        blank_to_none(&query.date).unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string());
+
+    let releases = sqlx::query_as::<_, Release>(&format!(
+        "{} WHERE substr(r.fecha_entrega, 1, 10) = ?1 AND (?2 IS NULL OR r.id_control_escolar = ?2) ORDER BY r.fecha_entrega DESC",
+        RELEASE_SELECT
+    ))
+    .bind(&date)
+    .bind(blank_to_none(&query.student_id))
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(releases))
+}
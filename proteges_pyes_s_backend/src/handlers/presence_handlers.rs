@@ -0,0 +1,295 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Local;
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    constants,
+    models::presence::{StaffPresence, Visitor, VisitorQuery, VisitorRequest},
+    utils::{
+        auth::StaffSession,
+        emergency::current_session,
+        presence::{set_person_status, today},
+    },
+};
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({"error": format!("Error de base de datos: {}", e)})),
+    )
+}
+
+fn blank_to_none(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn require_roll_call_role(session: &StaffSession) -> Result<(), ApiError> {
+    if session.has_role(&constants::EMERGENCY_ROLL_CALL_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(
+                serde_json::json!({"error": "No tienes permiso para registrar entradas y salidas"}),
+            ),
+        ))
+    }
+}
+
+// Arrivals and departures during an emergency update the session headcount
+async fn update_emergency_headcount(
+    pool: &Pool<Sqlite>,
+    kind: &str,
+    person_id: i64,
+    name: &str,
+    status: &str,
+    user_id: i64,
+) -> Result<(), ApiError> {
+    if let Some(session) = current_session(pool).await.map_err(db_error)? {
+        set_person_status(
+            pool, session.id, kind, person_id, name, status, user_id, None,
+        )
+        .await
+        .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+// Staff currently on campus
+pub async fn get_staff_on_campus(
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Vec<StaffPresence>>, ApiError> {
+    let staff = sqlx::query_as::<_, StaffPresence>(
+        r#"
+        SELECT p.*, u.nombre_mostrado AS name, u.rol AS role
+        FROM presencia_personal p
+        JOIN usuarios u ON u.id_usuario = p.id_usuario
+        WHERE p.salida IS NULL AND substr(p.entrada, 1, 10) = ?
+        ORDER BY p.entrada
+        "#,
+    )
+    .bind(today())
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(staff))
+}
+
+pub async fn staff_check_in(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_roll_call_role(&session)?;
+
+    let open: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM presencia_personal WHERE id_usuario = ? AND salida IS NULL AND substr(entrada, 1, 10) = ?)",
+    )
+    .bind(session.user_id)
+    .bind(today())
+    .fetch_one(&pool)
+    .await
+    .map_err(db_error)?;
+    if open {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "Ya registraste tu entrada"})),
+        ));
+    }
+
+    let now = Local::now().to_rfc3339();
+    sqlx::query("INSERT INTO presencia_personal (id_usuario, entrada) VALUES (?, ?)")
+        .bind(session.user_id)
+        .bind(&now)
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+    let name: String =
+        sqlx::query_scalar("SELECT nombre_mostrado FROM usuarios WHERE id_usuario = ?")
+            .bind(session.user_id)
+            .fetch_one(&pool)
+            .await
+            .map_err(db_error)?;
+    update_emergency_headcount(
+        &pool,
+        "PERSONAL",
+        session.user_id,
+        &name,
+        "LOCALIZADO",
+        session.user_id,
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Entrada registrada",
+        "checked_in_at": now,
+    })))
+}
+
+pub async fn staff_check_out(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_roll_call_role(&session)?;
+
+    let now = Local::now().to_rfc3339();
+    let result = sqlx::query(
+        "UPDATE presencia_personal SET salida = ? WHERE id_usuario = ? AND salida IS NULL",
+    )
+    .bind(&now)
+    .bind(session.user_id)
+    .execute(&pool)
+    .await
+    .map_err(db_error)?;
+    if result.rows_affected() == 0 {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "No tienes una entrada registrada"})),
+        ));
+    }
+
+    let name: String =
+        sqlx::query_scalar("SELECT nombre_mostrado FROM usuarios WHERE id_usuario = ?")
+            .bind(session.user_id)
+            .fetch_one(&pool)
+            .await
+            .map_err(db_error)?;
+    update_emergency_headcount(
+        &pool,
+        "PERSONAL",
+        session.user_id,
+        &name,
+        "RETIRADO",
+        session.user_id,
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Salida registrada",
+        "checked_out_at": now,
+    })))
+}
+
+// Visitor log of a day (today by default)
+pub async fn get_visitors(
+    State(pool): State<Pool<Sqlite>>,
+    Query(query): Query<VisitorQuery>,
+) -> Result<Json<Vec<Visitor>>, ApiError> {
+    let visitors = sqlx::query_as::<_, Visitor>(
+        r#"
+        SELECT * FROM visitantes
+        WHERE substr(entrada, 1, 10) = ?
+        AND (? IS NULL OR (salida IS NULL) = ?)
+        ORDER BY entrada DESC
+        "#,
+    )
+    .bind(query.date.unwrap_or_else(today))
+    .bind(query.on_campus)
+    .bind(query.on_campus)
+    .fetch_all(&pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(Json(visitors))
+}
+
+pub async fn sign_in_visitor(
+    session: StaffSession,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<VisitorRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_roll_call_role(&session)?;
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "El nombre del visitante es obligatorio"})),
+        ));
+    }
+
+    let now = Local::now().to_rfc3339();
+    let id = sqlx::query(
+        "INSERT INTO visitantes (nombre, motivo, visita_a, telefono, tipo_identificacion, numero_identificacion, registrado_por, entrada) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(name)
+    .bind(blank_to_none(&payload.reason))
+    .bind(blank_to_none(&payload.host))
+    .bind(blank_to_none(&payload.phone))
+    .bind(blank_to_none(&payload.id_type))
+    .bind(blank_to_none(&payload.id_number))
+    .bind(session.user_id)
+    .bind(&now)
+    .execute(&pool)
+    .await
+    .map_err(db_error)?
+    .last_insert_rowid();
+
+    update_emergency_headcount(&pool, "VISITANTE", id, name, "LOCALIZADO", session.user_id).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Visitante registrado",
+        "id": id,
+        "signed_in_at": now,
+    })))
+}
+
+pub async fn sign_out_visitor(
+    session: StaffSession,
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    require_roll_call_role(&session)?;
+
+    let visitor = sqlx::query_as::<_, Visitor>("SELECT * FROM visitantes WHERE id = ?")
+        .bind(id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Visitante no encontrado"})),
+            )
+        })?;
+    if visitor.signed_out_at.is_some() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "El visitante ya registró su salida"})),
+        ));
+    }
+
+    let now = Local::now().to_rfc3339();
+    sqlx::query("UPDATE visitantes SET salida = ?, salida_registrada_por = ? WHERE id = ?")
+        .bind(&now)
+        .bind(session.user_id)
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(db_error)?;
+
+    update_emergency_headcount(
+        &pool,
+        "VISITANTE",
+        id,
+        &visitor.name,
+        "RETIRADO",
+        session.user_id,
+    )
+    .await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Salida del visitante registrada",
+        "signed_out_at": now,
+    })))
+}
@@ -2,34 +2,69 @@ use axum::{extract::State, http::StatusCode, Json};
 use chrono::Utc;
 use sqlx::{Pool, Sqlite};
 
-use crate::models::scan::{RegisterScanRequest, ScanHistoryItem, ToggleScanRequest};
+use crate::{
+    models::{
+        assembly::StudentPlacement,
+        scan::{RegisterScanRequest, ScanHistoryItem, ToggleScanRequest},
+    },
+    utils::{
+        assembly::{expected_point, resolve_scan_location},
+        emergency::{current_session, set_student_status, student_status},
+        presence::mark_staff_located,
+        students::resolve_student_code,
+    },
+};
+
+// Zone and assembly point sent by the scanner, validated
+async fn scan_location(
+    pool: &Pool<Sqlite>,
+    zone_id: Option<i64>,
+    point_id: Option<i64>,
+) -> Result<(Option<i64>, Option<i64>), (StatusCode, Json<serde_json::Value>)> {
+    resolve_scan_location(pool, zone_id, point_id)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al registrar el escaneo"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "Zona o punto de reunión no válido"})),
+            )
+        })
+}
 
 pub async fn register_scan(
     State(pool): State<Pool<Sqlite>>,
     Json(payload): Json<RegisterScanRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    // Verify student exists
-    let student_exists: bool =
-        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM estudiantes WHERE id_control_escolar = ?)")
-            .bind(&payload.student_id)
-            .fetch_one(&pool)
-            .await
-            .unwrap_or(false);
-
-    if !student_exists {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "Estudiante no encontrado"})),
-        ));
-    }
+    // Verify student exists (accepts id_control_escolar, RFID UID or the card QR code)
+    let student_id = resolve_student_code(&pool, &payload.student_id)
+        .await
+        .unwrap_or(None)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+            )
+        })?;
+
+    let (zone_id, point_id) =
+        scan_location(&pool, payload.zone_id, payload.assembly_point_id).await?;
 
-    // Check if duplicate scan (already scanned today/session)
-    // We check if this student has been scanned by ANYONE in the scanned history
-    // For a cleaner 'session' based scan, we might want to date-limit this, but for now global check as per 'toggle' logic
+    // Scans belong to the emergency session in progress (none outside an emergency)
+    let session = current_session(&pool).await.unwrap_or(None);
+    let session_id = session.as_ref().map(|session| session.id);
+
+    // Check if duplicate scan within the same session
     let already_scanned: bool = sqlx::query_scalar(
-        "SELECT EXISTS(SELECT 1 FROM historial_consulta WHERE estudiante_consultado = ?)",
+        "SELECT EXISTS(SELECT 1 FROM historial_consulta WHERE estudiante_consultado = ? AND sesion_id IS ?)",
     )
-    .bind(&payload.student_id)
+    .bind(&student_id)
+    .bind(session_id)
     .fetch_one(&pool)
     .await
     .unwrap_or(false);
@@ -41,85 +76,187 @@ pub async fn register_scan(
         ));
     }
 
-    // Insert scan record
+    // Insert scan record; a missing student becomes located (injured/released stay as they are)
     let now = Utc::now().to_rfc3339();
-    sqlx::query(
-        "INSERT INTO historial_consulta (estudiante_consultado, consultado_por, fecha_consulta) VALUES (?, ?, ?)"
-    )
-    .bind(&payload.student_id)
-    .bind(payload.user_id)
-    .bind(&now)
-    .execute(&pool)
-    .await
-    .map_err(|_| {
+    let insert = async {
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO historial_consulta (estudiante_consultado, consultado_por, fecha_consulta, sesion_id, id_zona, id_punto) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(&student_id)
+        .bind(payload.user_id)
+        .bind(&now)
+        .bind(session_id)
+        .bind(zone_id)
+        .bind(point_id)
+        .execute(&mut *tx)
+        .await?;
+        if let Some(session_id) = session_id {
+            let status = student_status(&mut *tx, session_id, &student_id).await?;
+            if status.as_deref().unwrap_or("FALTANTE") == "FALTANTE" {
+                set_student_status(
+                    &mut *tx,
+                    session_id,
+                    &student_id,
+                    "LOCALIZADO",
+                    payload.user_id,
+                    None,
+                )
+                .await?;
+            }
+        }
+        tx.commit().await
+    };
+    insert.await.map_err(|_| {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({"error": "Error al registrar el escaneo"})),
         )
     })?;
 
+    // Whoever is scanning is on campus
+    if let Some(session_id) = session_id {
+        if let Err(e) = mark_staff_located(&pool, session_id, payload.user_id).await {
+            eprintln!("Error updating staff headcount: {:?}", e);
+        }
+    }
+
+    // Warn the scanner right away when the student is at the wrong assembly point
+    let expected = expected_point(&pool, &student_id).await.unwrap_or(None);
+    let placement = StudentPlacement {
+        student_id: student_id.clone(),
+        zone_id,
+        zone_name: None,
+        point_id,
+        point_name: None,
+        expected_point_id: expected.as_ref().map(|(id, _, _)| *id),
+        expected_point_name: expected.as_ref().map(|(_, name, _)| name.clone()),
+        expected_zone_id: expected.as_ref().map(|(_, _, zone)| *zone),
+        status: "LOCALIZADO".to_string(),
+    };
+
     Ok(Json(serde_json::json!({
         "message": "Escaneo registrado correctamente",
-        "student_id": payload.student_id,
+        "student_id": student_id,
         "scan_type": payload.scan_type,
+        "expected_assembly_point": placement.expected_point_name,
+        "wrong_point": session_id.is_some() && placement.wrong_point(),
     })))
 }
 
+// located <-> missing in the emergency in progress
 pub async fn toggle_scan_status(
     State(pool): State<Pool<Sqlite>>,
     Json(payload): Json<ToggleScanRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    // Check if student has been scanned recently
-    let now = Utc::now().to_rfc3339();
-    let scanned: bool = sqlx::query_scalar(
-        "SELECT EXISTS(SELECT 1 FROM historial_consulta WHERE estudiante_consultado = ?)",
-    )
-    .bind(&payload.student_id)
-    .fetch_one(&pool)
-    .await
-    .unwrap_or(false);
-
-    if scanned {
-        // Remove scan record (mark as missing)
-        sqlx::query("DELETE FROM historial_consulta WHERE estudiante_consultado = ?")
-            .bind(&payload.student_id)
-            .execute(&pool)
-            .await
-            .map_err(|_| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({"error": "Error al actualizar estado"})),
-                )
-            })?;
-
-        Ok(Json(serde_json::json!({
-            "message": "Estudiante marcado como faltante",
-            "scanned": false
-        })))
-    } else {
-        // Add scan record (mark as safe)
-        sqlx::query(
-            "INSERT INTO historial_consulta (estudiante_consultado, consultado_por, fecha_consulta) VALUES (?, ?, ?)"
+    let update_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al actualizar estado"})),
         )
-        .bind(&payload.student_id)
-        .bind(payload.user_id)
-        .bind(&now)
-        .execute(&pool)
+    };
+
+    let session = current_session(&pool)
         .await
-        .map_err(|_| {
+        .map_err(update_error)?
+        .ok_or_else(|| {
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": "Error al actualizar estado"})),
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({"error": "No hay una emergencia en curso"})),
             )
         })?;
 
-        Ok(Json(serde_json::json!({
-            "message": "Estudiante marcado como a salvo",
-            "scanned": true
-        })))
+    let status = student_status(&pool, session.id, &payload.student_id)
+        .await
+        .map_err(update_error)?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(
+                    serde_json::json!({"error": "El estudiante no forma parte de esta emergencia"}),
+                ),
+            )
+        })?;
+
+    let now = Utc::now().to_rfc3339();
+    match status.as_str() {
+        "LOCALIZADO" => {
+            // Remove this session's scan records (mark as missing)
+            let update = async {
+                let mut tx = pool.begin().await?;
+                sqlx::query(
+                    "DELETE FROM historial_consulta WHERE estudiante_consultado = ? AND sesion_id = ?",
+                )
+                .bind(&payload.student_id)
+                .bind(session.id)
+                .execute(&mut *tx)
+                .await?;
+                set_student_status(
+                    &mut *tx,
+                    session.id,
+                    &payload.student_id,
+                    "FALTANTE",
+                    payload.user_id,
+                    None,
+                )
+                .await?;
+                tx.commit().await
+            };
+            update.await.map_err(update_error)?;
+
+            Ok(Json(serde_json::json!({
+                "message": "Estudiante marcado como faltante",
+                "scanned": false
+            })))
+        }
+        "FALTANTE" => {
+            let (zone_id, point_id) =
+                scan_location(&pool, payload.zone_id, payload.assembly_point_id).await?;
+
+            // Add scan record (mark as safe)
+            let update = async {
+                let mut tx = pool.begin().await?;
+                sqlx::query(
+                    "INSERT INTO historial_consulta (estudiante_consultado, consultado_por, fecha_consulta, sesion_id, id_zona, id_punto) VALUES (?, ?, ?, ?, ?, ?)"
+                )
+                .bind(&payload.student_id)
+                .bind(payload.user_id)
+                .bind(&now)
+                .bind(session.id)
+                .bind(zone_id)
+                .bind(point_id)
+                .execute(&mut *tx)
+                .await?;
+                set_student_status(
+                    &mut *tx,
+                    session.id,
+                    &payload.student_id,
+                    "LOCALIZADO",
+                    payload.user_id,
+                    None,
+                )
+                .await?;
+                tx.commit().await
+            };
+            update.await.map_err(update_error)?;
+
+            Ok(Json(serde_json::json!({
+                "message": "Estudiante marcado como a salvo",
+                "scanned": true
+            })))
+        }
+        // injured or released students change through their own endpoints
+        other => Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": format!("El estudiante está marcado como {}", other)
+            })),
+        )),
     }
 }
 
+// sin ruta: la app no consulta el historial de escaneos
+#[allow(dead_code)]
 pub async fn get_scan_history(
     State(pool): State<Pool<Sqlite>>,
 ) -> Result<Json<Vec<ScanHistoryItem>>, (StatusCode, Json<serde_json::Value>)> {
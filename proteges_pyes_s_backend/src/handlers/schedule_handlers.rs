@@ -0,0 +1,527 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{Datelike, Local, NaiveDate};
+use sqlx::{Pool, Sqlite};
+
+use crate::constants::SCHOOL_ADMIN_ROLES;
+use crate::models::schedule::{
+    RollCallRequest, RosterEntry, RosterQuery, Schedule, ScheduleQuery, ScheduleRequest,
+};
+use crate::utils::auth::{user_role, StaffSession};
+use crate::utils::calendar::TERM_FOR_DATE;
+use crate::utils::enrollment::active_on_date;
+use crate::utils::schedules::parse_time;
+
+const SCHEDULE_SELECT: &str = r#"
+    SELECT h.*, u.nombre_mostrado AS teacher_name
+    FROM horarios h
+    LEFT JOIN usuarios u ON h.docente = u.id_usuario
+"#;
+
+fn require_schedule_admin(
+    session: &StaffSession,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if !session.has_role(&SCHOOL_ADMIN_ROLES) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "No tienes permiso para modificar horarios"})),
+        ));
+    }
+    Ok(())
+}
+
+async fn fetch_schedule(
+    pool: &Pool<Sqlite>,
+    id: i64,
+) -> Result<Schedule, (StatusCode, Json<serde_json::Value>)> {
+    sqlx::query_as::<_, Schedule>(&format!("{} WHERE h.id_horario = ?", SCHEDULE_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al consultar horarios"})),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Horario no encontrado"})),
+            )
+        })
+}
+
+// validaciones comunes para crear y actualizar; `exclude_id` evita chocar consigo mismo.
+// Regresa las horas como HH:MM con cero a la izquierda: se comparan como texto
+async fn validate_schedule(
+    pool: &Pool<Sqlite>,
+    payload: &ScheduleRequest,
+    exclude_id: Option<i64>,
+) -> Result<(String, String), (StatusCode, Json<serde_json::Value>)> {
+    if payload.subject.trim().is_empty() || payload.classroom.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Materia y salón son obligatorios"})),
+        ));
+    }
+    if !(1..=7).contains(&payload.weekday) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Día inválido, use 1 (lunes) a 7 (domingo)"})),
+        ));
+    }
+    let (start_time, end_time) = match (
+        parse_time(&payload.start_time),
+        parse_time(&payload.end_time),
+    ) {
+        (Some(start), Some(end)) if start < end => (
+            start.format("%H:%M").to_string(),
+            end.format("%H:%M").to_string(),
+        ),
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(
+                    serde_json::json!({"error": "Horas inválidas, use HH:MM con inicio antes del fin"}),
+                ),
+            ))
+        }
+    };
+
+    let group_exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM grupos WHERE id_nomenclatura = ?)")
+            .bind(&payload.group)
+            .fetch_one(pool)
+            .await
+            .unwrap_or(false);
+    if !group_exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Grupo no encontrado"})),
+        ));
+    }
+
+    let teacher_role = user_role(pool, payload.teacher_id).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar horarios"})),
+        )
+    })?;
+    match teacher_role.as_deref() {
+        Some("Docente") => {}
+        Some(_) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "El usuario asignado no es docente"})),
+            ))
+        }
+        None => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Docente no encontrado"})),
+            ))
+        }
+    }
+
+    // ni el grupo ni el docente pueden estar en dos clases al mismo tiempo
+    let overlapping: Option<(i64, String)> = sqlx::query_as(
+        r#"
+        SELECT id_horario, materia FROM horarios
+        WHERE dia_semana = ?
+        AND (grupo = ? OR docente = ?)
+        AND hora_inicio < ? AND hora_fin > ?
+        AND id_horario != ?
+        LIMIT 1
+        "#,
+    )
+    .bind(payload.weekday)
+    .bind(&payload.group)
+    .bind(payload.teacher_id)
+    .bind(&end_time)
+    .bind(&start_time)
+    .bind(exclude_id.unwrap_or(-1))
+    .fetch_optional(pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar horarios"})),
+        )
+    })?;
+
+    if let Some((id, subject)) = overlapping {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": format!("El horario se empalma con {} (id {})", subject, id)
+            })),
+        ));
+    }
+
+    Ok((start_time, end_time))
+}
+
+pub async fn get_schedules(
+    Query(query): Query<ScheduleQuery>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Vec<Schedule>>, (StatusCode, Json<serde_json::Value>)> {
+    let schedules = sqlx::query_as::<_, Schedule>(&format!(
+        r#"{}
+        WHERE (? IS NULL OR h.grupo = ?)
+        AND (? IS NULL OR h.docente = ?)
+        AND (? IS NULL OR h.dia_semana = ?)
+        ORDER BY h.dia_semana, h.hora_inicio"#,
+        SCHEDULE_SELECT
+    ))
+    .bind(&query.group)
+    .bind(&query.group)
+    .bind(query.teacher_id)
+    .bind(query.teacher_id)
+    .bind(query.weekday)
+    .bind(query.weekday)
+    .fetch_all(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar horarios"})),
+        )
+    })?;
+
+    Ok(Json(schedules))
+}
+
+pub async fn get_schedule(
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Schedule>, (StatusCode, Json<serde_json::Value>)> {
+    Ok(Json(fetch_schedule(&pool, id).await?))
+}
+
+// periodo que el docente tiene en este momento, para pasar lista sin buscarlo
+pub async fn get_current_schedule(
+    Query(query): Query<ScheduleQuery>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<Option<Schedule>>, (StatusCode, Json<serde_json::Value>)> {
+    let now = Local::now();
+    let time = now.format("%H:%M").to_string();
+    let schedule = sqlx::query_as::<_, Schedule>(&format!(
+        r#"{}
+        WHERE h.dia_semana = ?
+        AND h.hora_inicio <= ? AND h.hora_fin > ?
+        AND (? IS NULL OR h.docente = ?)
+        AND (? IS NULL OR h.grupo = ?)
+        LIMIT 1"#,
+        SCHEDULE_SELECT
+    ))
+    .bind(now.weekday().number_from_monday() as i64)
+    .bind(&time)
+    .bind(&time)
+    .bind(query.teacher_id)
+    .bind(query.teacher_id)
+    .bind(&query.group)
+    .bind(&query.group)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar horarios"})),
+        )
+    })?;
+
+    Ok(Json(schedule))
+}
+
+pub async fn create_schedule(
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+    Json(payload): Json<ScheduleRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_schedule_admin(&session)?;
+    let (start_time, end_time) = validate_schedule(&pool, &payload, None).await?;
+
+    let result = sqlx::query(
+        "INSERT INTO horarios (grupo, materia, docente, salon_clase, dia_semana, hora_inicio, hora_fin) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&payload.group)
+    .bind(payload.subject.trim())
+    .bind(payload.teacher_id)
+    .bind(payload.classroom.trim())
+    .bind(payload.weekday)
+    .bind(&start_time)
+    .bind(&end_time)
+    .execute(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "No se pudo crear el horario"})),
+        )
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Horario creado correctamente",
+        "id": result.last_insert_rowid()
+    })))
+}
+
+pub async fn update_schedule(
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+    Json(payload): Json<ScheduleRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_schedule_admin(&session)?;
+    fetch_schedule(&pool, id).await?;
+    let (start_time, end_time) = validate_schedule(&pool, &payload, Some(id)).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE horarios SET
+            grupo=?, materia=?, docente=?, salon_clase=?,
+            dia_semana=?, hora_inicio=?, hora_fin=?
+        WHERE id_horario=?
+        "#,
+    )
+    .bind(&payload.group)
+    .bind(payload.subject.trim())
+    .bind(payload.teacher_id)
+    .bind(payload.classroom.trim())
+    .bind(payload.weekday)
+    .bind(&start_time)
+    .bind(&end_time)
+    .bind(id)
+    .execute(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al actualizar el horario"})),
+        )
+    })?;
+
+    Ok(Json(
+        serde_json::json!({"message": "Horario actualizado correctamente"}),
+    ))
+}
+
+pub async fn delete_schedule(
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_schedule_admin(&session)?;
+    // si ya hay listas tomadas en este periodo se conserva para no perder el historial
+    let has_attendance: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM asistencias WHERE id_horario = ?)")
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .unwrap_or(false);
+    if has_attendance {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "El horario ya tiene asistencias registradas"})),
+        ));
+    }
+
+    let result = sqlx::query("DELETE FROM horarios WHERE id_horario = ?")
+        .bind(id)
+        .execute(&pool)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al eliminar el horario"})),
+            )
+        })?;
+
+    if result.rows_affected() == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Horario no encontrado"})),
+        ));
+    }
+
+    Ok(Json(
+        serde_json::json!({"message": "Horario eliminado correctamente"}),
+    ))
+}
+
+fn parse_date(date: Option<&str>) -> Result<NaiveDate, (StatusCode, Json<serde_json::Value>)> {
+    match date {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "Fecha inválida, use YYYY-MM-DD"})),
+            )
+        }),
+        None => Ok(Local::now().date_naive()),
+    }
+}
+
+// alumnos esperados en el periodo y si ya se les paso lista ese dia
+pub async fn get_schedule_roster(
+    Path(id): Path<i64>,
+    Query(query): Query<RosterQuery>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let schedule = fetch_schedule(&pool, id).await?;
+    let date = parse_date(query.date.as_deref())?
+        .format("%Y-%m-%d")
+        .to_string();
+
+    // students enrolled on that date, so past roll-calls keep those who left since
+    let roster = sqlx::query_as::<_, RosterEntry>(&format!(
+        r#"
+        SELECT
+            e.id_control_escolar AS student_id,
+            (e.nombres || ' ' || e.apellido_paterno) AS nombre_completo,
+            (
+                SELECT a.presente FROM asistencias a
+                WHERE a.id_control_escolar = e.id_control_escolar
+                AND a.id_horario = ?
+                AND substr(a.fecha_asistencia, 1, 10) = ?
+                ORDER BY a.id_asistencia DESC
+                LIMIT 1
+            ) AS present
+        FROM estudiantes e
+        WHERE e.grupo = ? AND {}
+        ORDER BY e.apellido_paterno, e.nombres
+        "#,
+        active_on_date()
+    ))
+    .bind(id)
+    .bind(&date)
+    .bind(&schedule.group)
+    .bind(&date)
+    .fetch_all(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar la lista"})),
+        )
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "schedule": schedule,
+        "date": date,
+        "students": roster
+    })))
+}
+
+pub async fn take_roll_call(
+    Path(id): Path<i64>,
+    State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
+    Json(payload): Json<RollCallRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let schedule = fetch_schedule(&pool, id).await?;
+    let date = parse_date(payload.date.as_deref())?;
+
+    // un docente solo puede pasar lista en sus propias clases
+    if session.has_role(&["Docente"]) && session.user_id != schedule.teacher_id {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "El horario pertenece a otro docente"})),
+        ));
+    }
+
+    if date.weekday().number_from_monday() as i64 != schedule.weekday {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "La fecha no corresponde al día de la clase"})),
+        ));
+    }
+
+    let date_str = date.format("%Y-%m-%d").to_string();
+    let timestamp = if date == Local::now().date_naive() {
+        Local::now().to_rfc3339()
+    } else {
+        format!("{}T{}:00", date_str, schedule.start_time)
+    };
+
+    let mut tx = pool.begin().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al iniciar la transacción"})),
+        )
+    })?;
+
+    let mut recorded = 0;
+    let mut rejected = Vec::new();
+    for entry in &payload.records {
+        let in_group: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM estudiantes WHERE id_control_escolar = ? AND grupo = ?)",
+        )
+        .bind(&entry.student_id)
+        .bind(&schedule.group)
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap_or(false);
+
+        if !in_group {
+            rejected.push(entry.student_id.clone());
+            continue;
+        }
+
+        // volver a pasar lista reemplaza la anterior del mismo dia
+        sqlx::query(
+            "DELETE FROM asistencias WHERE id_control_escolar = ? AND id_horario = ? AND substr(fecha_asistencia, 1, 10) = ?",
+        )
+        .bind(&entry.student_id)
+        .bind(id)
+        .bind(&date_str)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al registrar la lista"})),
+            )
+        })?;
+
+        sqlx::query(&format!(
+            "INSERT INTO asistencias (id_control_escolar, registrado_por, fecha_asistencia, salon_clase, presente, id_horario, grupo, id_periodo) VALUES (?, ?, ?, ?, ?, ?, ?, {})",
+            TERM_FOR_DATE
+        ))
+        .bind(&entry.student_id)
+        .bind(session.user_id)
+        .bind(&timestamp)
+        .bind(&schedule.classroom)
+        .bind(entry.present)
+        .bind(id)
+        .bind(&schedule.group)
+        .bind(&date_str)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al registrar la lista"})),
+            )
+        })?;
+        recorded += 1;
+    }
+
+    tx.commit().await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al guardar la lista"})),
+        )
+    })?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Lista registrada",
+        "schedule_id": id,
+        "date": date_str,
+        "recorded": recorded,
+        "rejected": rejected
+    })))
+}
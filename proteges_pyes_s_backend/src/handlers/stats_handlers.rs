@@ -2,6 +2,8 @@ use axum::{extract::State, http::StatusCode, Json};
 use serde::Serialize;
 use sqlx::{Pool, Row, Sqlite};
 
+use crate::utils::enrollment::{active_on_date, today};
+
 #[derive(Serialize)]
 pub struct Stats {
     pub total_students: i64,
@@ -13,11 +15,15 @@ pub struct Stats {
 pub async fn get_stats(
     State(pool): State<Pool<Sqlite>>,
 ) -> Result<Json<Stats>, (StatusCode, Json<serde_json::Value>)> {
-    let total_students: i64 = sqlx::query("SELECT COUNT(*) FROM estudiantes")
-        .fetch_one(&pool)
-        .await
-        .map(|row| row.get(0))
-        .unwrap_or(0);
+    let total_students: i64 = sqlx::query(&format!(
+        "SELECT COUNT(*) FROM estudiantes e WHERE {}",
+        active_on_date()
+    ))
+    .bind(today())
+    .fetch_one(&pool)
+    .await
+    .map(|row| row.get(0))
+    .unwrap_or(0);
 
     let total_users: i64 = sqlx::query("SELECT COUNT(*) FROM usuarios")
         .fetch_one(&pool)
@@ -31,7 +37,7 @@ pub async fn get_stats(
         .map(|row| row.get(0))
         .unwrap_or(0);
 
-    let total_groups: i64 = sqlx::query("SELECT COUNT(*) FROM grupos")
+    let total_groups: i64 = sqlx::query("SELECT COUNT(*) FROM grupos WHERE NOT archivado")
         .fetch_one(&pool)
         .await
         .map(|row| row.get(0))
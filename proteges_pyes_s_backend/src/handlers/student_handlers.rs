@@ -1,32 +1,79 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use sqlx::{Pool, Sqlite};
 
+use crate::constants;
 use crate::models::{
     scan::UpdateStudentGroupRequest,
-    student::{CreateStudentRequest, Student, UpdateStudentRequest},
+    student::{
+        CreateStudentRequest, EnrollmentInfo, EnrollmentRequest, Student, StudentListQuery,
+        UpdateStudentRequest,
+    },
 };
+use crate::utils::{
+    auth::StaffSession,
+    calendar::parse_date,
+    enrollment::{enrollment_history, status_on, status_on_date, today},
+    groups::group_unavailable,
+    guardians::sync_guardian_phones,
+};
+
+fn require_student_admin(
+    session: &StaffSession,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if session.has_role(&constants::STUDENT_ADMIN_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "No tienes permiso para editar alumnos"})),
+        ))
+    }
+}
+
+// Students can only be placed in an existing, non-archived group
+async fn check_group(
+    pool: &Pool<Sqlite>,
+    group: &str,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let unavailable = group_unavailable(pool, group).await.map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar la base de datos"})),
+        )
+    })?;
+    match unavailable {
+        Some(message) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": message})),
+        )),
+        None => Ok(()),
+    }
+}
 
 pub async fn get_student(
     Path(id): Path<String>,
     State(pool): State<Pool<Sqlite>>,
 ) -> Result<Json<Student>, (StatusCode, Json<serde_json::Value>)> {
-    let student =
-        sqlx::query_as::<_, Student>("SELECT * FROM estudiantes WHERE id_control_escolar = ?")
-            .bind(id)
-            .fetch_optional(&pool)
-            .await
-            .map_err(|_| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({
-                        "error": "Error al consultar la base de datos"
-                    })),
-                )
-            })?;
+    let student = sqlx::query_as::<_, Student>(&format!(
+        "SELECT e.*, {} AS enrollment_status FROM estudiantes e WHERE e.id_control_escolar = ?",
+        status_on_date("?")
+    ))
+    .bind(today())
+    .bind(id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": "Error al consultar la base de datos"
+            })),
+        )
+    })?;
     match student {
         Some(student) => Ok(Json(student)),
         None => Err((
@@ -36,11 +83,18 @@ pub async fn get_student(
     }
 }
 
+// Enrolled students; withdrawn, graduated and suspended ones only with `include_inactive=true`
 pub async fn get_all_students(
     State(pool): State<Pool<Sqlite>>,
+    Query(query): Query<StudentListQuery>,
 ) -> Result<Json<Vec<Student>>, (StatusCode, Json<serde_json::Value>)> {
-    let students = sqlx::query_as::<_, Student>("SELECT * FROM estudiantes")
-        .fetch_all(&pool)
+    let students = sqlx::query_as::<_, Student>(&format!(
+        "SELECT * FROM (SELECT e.*, {} AS enrollment_status FROM estudiantes e) WHERE ? OR enrollment_status = 'ACTIVO'",
+        status_on_date("?")
+    ))
+    .bind(today())
+    .bind(query.include_inactive.unwrap_or(false))
+    .fetch_all(&pool)
         .await
         .map_err(|_| {
             (
@@ -52,18 +106,22 @@ pub async fn get_all_students(
 }
 
 pub async fn create_student(
+    session: StaffSession,
     State(pool): State<Pool<Sqlite>>,
     Json(payload): Json<CreateStudentRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_student_admin(&session)?;
+    check_group(&pool, &payload.group).await?;
+
     sqlx::query(
         r#"
         INSERT INTO estudiantes (
             id_control_escolar, nombres, apellido_paterno, apellido_materno,
             fecha_nacimiento, especialidad, grupo, tipo_de_sangre,
-            alergias, enfermedades_cronicas, domicilio, telefono_personal,
+            domicilio, telefono_personal,
             telefono_tutor_principal, telefono_tutor_secundario, telefono_emergencia
         )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
     .bind(&payload.id)
@@ -74,8 +132,6 @@ pub async fn create_student(
     .bind(&payload.major)
     .bind(&payload.group)
     .bind(&payload.blood_type)
-    .bind(&payload.allergies)
-    .bind(&payload.chronic_diseases)
     .bind(&payload.domicile)
     .bind(&payload.personal_phone)
     .bind(&payload.primary_guardian_phone)
@@ -97,22 +153,45 @@ pub async fn create_student(
         }
     })?;
 
+    // Guardian phones become guardian records linked to the student
+    sync_guardian_phones(
+        &pool,
+        &payload.id,
+        payload.primary_guardian_phone.as_deref(),
+        payload.secondary_guardian_phone.as_deref(),
+    )
+    .await;
+
     Ok(Json(
         serde_json::json!({"message": "Estudiante creado correctamente"}),
     ))
 }
 
 pub async fn update_student(
+    session: StaffSession,
     Path(id): Path<String>,
     State(pool): State<Pool<Sqlite>>,
     Json(payload): Json<UpdateStudentRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_student_admin(&session)?;
+    // a student already in an archived group may keep it while other data is edited
+    let current_group: Option<String> =
+        sqlx::query_scalar("SELECT grupo FROM estudiantes WHERE id_control_escolar = ?")
+            .bind(&id)
+            .fetch_optional(&pool)
+            .await
+            .unwrap_or(None);
+    if current_group.as_deref() != Some(payload.group.as_str()) {
+        check_group(&pool, &payload.group).await?;
+    }
+
+    // the edit form never receives the blood type, so leaving it out keeps it
     let result = sqlx::query(
         r#"
         UPDATE estudiantes SET 
             nombres=?, apellido_paterno=?, apellido_materno=?,
-            fecha_nacimiento=?, especialidad=?, grupo=?, tipo_de_sangre=?,
-            alergias=?, enfermedades_cronicas=?, domicilio=?, telefono_personal=?,
+            fecha_nacimiento=?, especialidad=?, grupo=?,
+            tipo_de_sangre=COALESCE(?, tipo_de_sangre), domicilio=?, telefono_personal=?,
             telefono_tutor_principal=?, telefono_tutor_secundario=?, telefono_emergencia=?
         WHERE id_control_escolar=?
         "#,
@@ -124,8 +203,6 @@ pub async fn update_student(
     .bind(&payload.major)
     .bind(&payload.group)
     .bind(&payload.blood_type)
-    .bind(&payload.allergies)
-    .bind(&payload.chronic_diseases)
     .bind(&payload.domicile)
     .bind(&payload.personal_phone)
     .bind(&payload.primary_guardian_phone)
@@ -148,16 +225,28 @@ pub async fn update_student(
         ));
     }
 
+    sync_guardian_phones(
+        &pool,
+        &id,
+        payload.primary_guardian_phone.as_deref(),
+        payload.secondary_guardian_phone.as_deref(),
+    )
+    .await;
+
     Ok(Json(
         serde_json::json!({"message": "Estudiante actualizado correctamente"}),
     ))
 }
 
 pub async fn update_student_group(
+    session: StaffSession,
     Path(id): Path<String>,
     State(pool): State<Pool<Sqlite>>,
     Json(payload): Json<UpdateStudentGroupRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_student_admin(&session)?;
+    check_group(&pool, &payload.group).await?;
+
     let result = sqlx::query("UPDATE estudiantes SET grupo = ? WHERE id_control_escolar = ?")
         .bind(&payload.group)
         .bind(&id)
@@ -181,3 +270,202 @@ pub async fn update_student_group(
         serde_json::json!({"message": "Grupo actualizado correctamente"}),
     ))
 }
+
+fn require_enrollment_admin(
+    session: &StaffSession,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if session.has_role(&constants::ENROLLMENT_ADMIN_ROLES) {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "No tienes permiso para cambiar la inscripción"})),
+        ))
+    }
+}
+
+async fn enrollment_info(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+) -> Result<EnrollmentInfo, (StatusCode, Json<serde_json::Value>)> {
+    let db_error = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al consultar la base de datos"})),
+        )
+    };
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM estudiantes WHERE id_control_escolar = ?)")
+            .bind(student_id)
+            .fetch_one(pool)
+            .await
+            .map_err(db_error)?;
+    if !exists {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+        ));
+    }
+
+    Ok(EnrollmentInfo {
+        student_id: student_id.to_string(),
+        status: status_on(pool, student_id, &today())
+            .await
+            .map_err(db_error)?,
+        history: enrollment_history(pool, student_id)
+            .await
+            .map_err(db_error)?,
+    })
+}
+
+pub async fn get_enrollment(
+    Path(id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<EnrollmentInfo>, (StatusCode, Json<serde_json::Value>)> {
+    Ok(Json(enrollment_info(&pool, &id).await?))
+}
+
+// Withdrawal, graduation, suspension or re-enrollment from a given date (may be in the
+// future); attendance and every other record of the student are kept
+pub async fn set_enrollment(
+    session: StaffSession,
+    Path(id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+    Json(payload): Json<EnrollmentRequest>,
+) -> Result<Json<EnrollmentInfo>, (StatusCode, Json<serde_json::Value>)> {
+    require_enrollment_admin(&session)?;
+    let current = enrollment_info(&pool, &id).await?;
+
+    let bad_request = |message: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": message})),
+        )
+    };
+    let status = payload.status.trim().to_uppercase();
+    if !constants::ENROLLMENT_STATUSES.contains(&status.as_str()) {
+        return Err(bad_request(format!(
+            "Estatus inválido. Valores permitidos: {}",
+            constants::ENROLLMENT_STATUSES.join(", ")
+        )));
+    }
+    let since = match payload
+        .effective_date
+        .as_deref()
+        .map(str::trim)
+        .filter(|date| !date.is_empty())
+    {
+        Some(date) => parse_date(date)
+            .ok_or_else(|| bad_request("La fecha debe tener el formato YYYY-MM-DD".to_string()))?
+            .format("%Y-%m-%d")
+            .to_string(),
+        None => today(),
+    };
+    // re-enrolling needs a group that still takes students
+    if status == "ACTIVO" && current.status != "ACTIVO" {
+        let group: String =
+            sqlx::query_scalar("SELECT grupo FROM estudiantes WHERE id_control_escolar = ?")
+                .bind(&id)
+                .fetch_one(&pool)
+                .await
+                .map_err(|_| {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(serde_json::json!({"error": "Error al consultar la base de datos"})),
+                    )
+                })?;
+        check_group(&pool, &group).await?;
+    }
+
+    sqlx::query(
+        "INSERT INTO historial_inscripcion (id_control_escolar, estatus, desde, motivo, registrado_por, registrado_en) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(&status)
+    .bind(&since)
+    .bind(
+        payload
+            .reason
+            .as_deref()
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty()),
+    )
+    .bind(session.user_id)
+    .bind(chrono::Local::now().to_rfc3339())
+    .execute(&pool)
+    .await
+    .map_err(|_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": "Error al actualizar la inscripción"})),
+        )
+    })?;
+
+    Ok(Json(enrollment_info(&pool, &id).await?))
+}
+
+// Only for students registered by mistake: anyone with attendance or other history has to
+// be withdrawn instead
+pub async fn delete_student(
+    session: StaffSession,
+    Path(id): Path<String>,
+    State(pool): State<Pool<Sqlite>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    require_enrollment_admin(&session)?;
+
+    let delete = async {
+        let mut tx = pool.begin().await?;
+        // these tables cascade with the student, so the foreign keys would not stop the
+        // delete and the emergency, enrollment and promotion history would be lost
+        let has_history: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM estado_alumnos_emergencia WHERE id_control_escolar = ?1)
+                OR EXISTS(SELECT 1 FROM historial_inscripcion WHERE id_control_escolar = ?1)
+                OR EXISTS(SELECT 1 FROM promociones_alumnos WHERE id_control_escolar = ?1)
+            "#,
+        )
+        .bind(&id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if has_history {
+            return Ok(None);
+        }
+        // device tokens registered for the student are not history
+        sqlx::query("DELETE FROM push_tokens WHERE student_id = ?")
+            .bind(&id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM estudiantes WHERE id_control_escolar = ?")
+            .bind(&id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(Some(result.rows_affected()))
+    };
+    let has_history = || {
+        (
+            StatusCode::CONFLICT,
+            Json(
+                serde_json::json!({"error": "El estudiante tiene historial; dalo de baja en su lugar"}),
+            ),
+        )
+    };
+    let deleted = delete
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db) if db.is_foreign_key_violation() => has_history(),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Error al eliminar el estudiante"})),
+            ),
+        })?
+        .ok_or_else(has_history)?;
+    if deleted == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Estudiante no encontrado"})),
+        ));
+    }
+
+    Ok(Json(serde_json::json!({"message": "Estudiante eliminado"})))
+}
@@ -9,6 +9,7 @@ use sqlx::{Pool, Sqlite};
 use crate::{
     constants,
     models::user::{CreateUserRequest, User},
+    utils::auth::StaffSession,
 };
 
 pub async fn get_user(
@@ -51,10 +52,18 @@ pub async fn get_all_users(
     Ok(Json(users))
 }
 
+// Only Director/Operador accounts may create staff users
 pub async fn create_user(
     State(pool): State<Pool<Sqlite>>,
+    session: StaffSession,
     Json(payload): Json<CreateUserRequest>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    if !session.has_role(&constants::USER_ADMIN_ROLES) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({"error": "No tienes permiso para dar de alta usuarios"})),
+        ));
+    }
     if payload.username.trim().is_empty() || payload.password.trim().len() < 4 {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -20,6 +20,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // inicializar tracing para logging asicronono con axum y sqlx
     tracing_subscriber::fmt::init();
 
+    // las credenciales, enlaces de evidencias y sesiones se firman con esta llave
+    utils::tokens::check_secret()?;
+
     let pool = SqlitePoolOptions::new()
         .connect_with(
             sqlx::sqlite::SqliteConnectOptions::new()
@@ -59,6 +62,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Estado de emergencia cargado: {}", emergency_state);
 
+    // carpeta de evidencias (los justificantes la necesitan desde la primera subida)
+    utils::uploads::ensure_upload_dir().await?;
+
+    // job que registra las faltas despues de la hora de corte de cada turno
+    tokio::spawn(utils::absences::run_absence_scheduler(pool.clone()));
+
     // crear el estado compartido
     let state = AppState {
         db: pool.clone(),
@@ -75,6 +84,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // iniciar el servidor
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    // la ip del cliente se usa en la bitacora de accesos
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
     Ok(())
 }
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// Building zone (building, courtyard, sports field)
+#[derive(Debug, Serialize, FromRow)]
+pub struct Zone {
+    pub id: i64,
+    #[sqlx(rename = "nombre")]
+    pub name: String,
+    #[sqlx(rename = "descripcion")]
+    pub description: Option<String>,
+    #[sqlx(rename = "activa")]
+    pub active: bool,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct AssemblyPoint {
+    pub id: i64,
+    #[sqlx(rename = "nombre")]
+    pub name: String,
+    #[sqlx(rename = "id_zona")]
+    pub zone_id: i64,
+    #[sqlx(default)]
+    pub zone_name: Option<String>,
+    #[sqlx(rename = "descripcion")]
+    pub description: Option<String>,
+    #[sqlx(rename = "activo")]
+    pub active: bool,
+}
+
+// Used for both zones and assembly points (zone_id only applies to points)
+#[derive(Debug, Deserialize)]
+pub struct AssemblyRequest {
+    pub name: String,
+    pub zone_id: Option<i64>,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GroupAssemblyPointRequest {
+    pub assembly_point_id: Option<i64>, // null removes the assignment
+}
+
+// Where a student was scanned in the session and where the group should be
+#[derive(Debug, FromRow)]
+pub struct StudentPlacement {
+    pub student_id: String,
+    pub zone_id: Option<i64>,
+    pub zone_name: Option<String>,
+    pub point_id: Option<i64>,
+    pub point_name: Option<String>,
+    pub expected_point_id: Option<i64>,
+    pub expected_point_name: Option<String>,
+    pub expected_zone_id: Option<i64>,
+    pub status: String,
+}
+
+impl StudentPlacement {
+    // scanned somewhere other than the group's point (or its zone when only the zone was recorded)
+    pub fn wrong_point(&self) -> bool {
+        if self.status == "FALTANTE" {
+            return false;
+        }
+        match (self.point_id, self.zone_id, self.expected_point_id) {
+            (_, _, None) => false,
+            (Some(point), _, Some(expected)) => point != expected,
+            (None, Some(zone), Some(_)) => Some(zone) != self.expected_zone_id,
+            (None, None, Some(_)) => false,
+        }
+    }
+}
+
+// Headcount of one zone for first responders
+#[derive(Debug, Serialize)]
+pub struct ZoneHeadcount {
+    pub zone_id: Option<i64>, // null: located without a recorded zone
+    pub zone_name: String,
+    pub present: i64, // located or injured, not yet released
+    pub injured: i64,
+    pub expected: i64,    // students whose group's point is in this zone
+    pub wrong_point: i64, // present here but expected somewhere else
+}
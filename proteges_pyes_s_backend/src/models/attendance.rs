@@ -1,18 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
-pub struct AttendanceRecord {
-    pub id_asistencia: i64,
-    pub id_control_escolar: String,
-    pub registrado_por: i64,
-    pub fecha_asistencia: String,
-    pub salon_clase: String,
-    pub presente: bool,
-    pub justificacion: Option<String>,
-    pub fecha_modificacion: Option<String>,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct CreateAttendanceRequest {
     pub student_id: String,
@@ -29,4 +17,64 @@ pub struct AttendanceHistoryResponse {
     pub fecha_asistencia: String,
     pub salon_clase: String,
     pub presente: bool,
+    pub tipo_registro: Option<String>,
+    pub clasificacion: Option<String>,
+    pub justificacion: Option<String>,
+    pub id_justificante: Option<i64>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct SchoolDayRule {
+    #[sqlx(rename = "turno")]
+    pub shift: String,
+    #[sqlx(rename = "hora_entrada")]
+    pub entry_time: String,
+    #[sqlx(rename = "minutos_tolerancia")]
+    pub grace_minutes: i64,
+    #[sqlx(rename = "hora_salida")]
+    pub dismissal_time: String,
+    #[sqlx(rename = "hora_corte_faltas")]
+    pub absence_cutoff: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSchoolDayRuleRequest {
+    pub entry_time: String,
+    pub grace_minutes: i64,
+    pub dismissal_time: String,
+    pub absence_cutoff: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunAbsencesRequest {
+    pub date: Option<String>, // YYYY-MM-DD, hoy por defecto
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttendanceReportQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub term_id: Option<i64>, // whole term, with the group the student had then
+}
+
+// Status of a student on one school day: PRESENTE, RETARDO, RETARDO_JUSTIFICADO,
+// FALTA, FALTA_JUSTIFICADA or SIN_REGISTRO
+#[derive(Debug, Serialize)]
+pub struct DayAttendance {
+    pub date: String,
+    pub status: String,
+}
+
+// Totals over school days only (holidays and weekends are not counted)
+#[derive(Debug, Serialize)]
+pub struct AttendanceSummary {
+    pub school_days: i64,
+    pub present: i64, // includes late arrivals
+    pub late: i64,
+    pub justified_late: i64,
+    pub absences: i64, // justified + unjustified
+    pub justified_absences: i64,
+    pub unjustified_absences: i64,
+    pub unrecorded: i64,
+    pub attendance_rate: Option<f64>,
 }
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct SchoolYear {
+    pub id: i64,
+    #[sqlx(rename = "nombre")]
+    pub name: String,
+    #[sqlx(rename = "fecha_inicio")]
+    pub start_date: String,
+    #[sqlx(rename = "fecha_fin")]
+    pub end_date: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Term {
+    pub id: i64,
+    #[sqlx(rename = "id_ciclo")]
+    pub school_year_id: i64,
+    #[sqlx(rename = "nombre")]
+    pub name: String,
+    #[sqlx(rename = "fecha_inicio")]
+    pub start_date: String,
+    #[sqlx(rename = "fecha_fin")]
+    pub end_date: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CalendarException {
+    pub id: i64,
+    #[sqlx(rename = "fecha_inicio")]
+    pub start_date: String,
+    #[sqlx(rename = "fecha_fin")]
+    pub end_date: String,
+    #[sqlx(rename = "tipo")]
+    pub kind: String, // FESTIVO, SUSPENSION, DIA_HABIL
+    #[sqlx(rename = "grupo")]
+    pub group: Option<String>,
+    #[sqlx(rename = "descripcion")]
+    pub description: Option<String>,
+}
+
+// Used for both school years and terms
+#[derive(Debug, Deserialize)]
+pub struct DateRangeRequest {
+    pub name: String,
+    pub start_date: String,
+    pub end_date: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCalendarExceptionRequest {
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub kind: String,
+    pub group: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalendarQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub group: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalendarImportQuery {
+    pub kind: Option<String>, // tipo por defecto de los eventos importados
+    pub group: Option<String>,
+}
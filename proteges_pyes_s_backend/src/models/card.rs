@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+// formato de salida de la credencial: "pdf" (por defecto) o "png" (la credencial completa)
+#[derive(Debug, Deserialize)]
+pub struct CardQuery {
+    pub format: Option<String>,
+}
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct EmergencySession {
+    pub id: i64,
+    #[sqlx(rename = "fase")]
+    pub phase: String, // EVACUACION, REUNIFICACION, CERRADA
+    #[sqlx(rename = "iniciada_por")]
+    pub started_by: i64,
+    #[sqlx(rename = "iniciada_en")]
+    pub started_at: String,
+    #[sqlx(rename = "reunificacion_en")]
+    pub reunification_at: Option<String>,
+    #[sqlx(rename = "punto_reunificacion")]
+    pub reunification_point: Option<String>,
+    #[sqlx(rename = "cerrada_por")]
+    pub closed_by: Option<i64>,
+    #[sqlx(rename = "cerrada_en")]
+    pub closed_at: Option<String>,
+    #[sqlx(rename = "tipo")]
+    pub kind: String, // SIMULACRO_INCENDIO, SIMULACRO_SISMO, EVENTO_REAL, CONFINAMIENTO
+    #[sqlx(rename = "notas")]
+    pub notes: Option<String>,
+    #[sqlx(rename = "protocolo")]
+    pub protocol: String, // EVACUACION, CONFINAMIENTO, RESGUARDO
+}
+
+// Manual status change of a student in the open session
+#[derive(Debug, Deserialize)]
+pub struct StudentStatusRequest {
+    pub status: String, // LOCALIZADO, LESIONADO, FALTANTE (ENTREGADO goes through /pickups/release)
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmergencyCounts {
+    pub total: i64,
+    pub located: i64,
+    pub injured: i64,
+    pub released: i64,
+    pub missing: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionUpdateRequest {
+    pub kind: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionQuery {
+    pub kind: Option<String>,
+    pub from: Option<String>, // YYYY-MM-DD
+    pub to: Option<String>,
+    pub format: Option<String>, // csv (drill report only)
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentFound {
+    pub student_id: String,
+    pub name: String,
+    pub group: String,
+    pub found_at: String,
+    pub seconds: i64, // since the emergency started
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupMetric {
+    pub group: String,
+    pub total: i64,
+    pub accounted: i64,
+    pub seconds_to_complete: Option<i64>, // None while someone in the group is missing
+}
+
+// Timings computed from the moment each student was first accounted for
+#[derive(Debug, Serialize)]
+pub struct SessionMetrics {
+    pub session: EmergencySession,
+    pub counts: EmergencyCounts,
+    pub evacuation_seconds: Option<i64>,
+    pub seconds_to_50: Option<i64>,
+    pub seconds_to_90: Option<i64>,
+    pub seconds_to_100: Option<i64>,
+    pub slowest_groups: Vec<GroupMetric>,
+    pub last_found: Vec<StudentFound>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DrillComparisonRow {
+    pub session_id: i64,
+    pub kind: String,
+    pub started_at: String,
+    pub total: i64,
+    pub accounted: i64,
+    pub seconds_to_50: Option<i64>,
+    pub seconds_to_90: Option<i64>,
+    pub seconds_to_100: Option<i64>,
+    pub change_to_90: Option<i64>, // against the previous drill of the same kind; negative is faster
+}
+
+#[derive(Debug, Serialize)]
+pub struct DrillComparison {
+    pub drills: Vec<DrillComparisonRow>,
+    pub average_seconds_to_90: Option<i64>,
+    pub best_seconds_to_90: Option<i64>,
+}
+
+// Teacher report during a lockdown or shelter-in-place: who is with them and where
+#[derive(Debug, Deserialize)]
+pub struct RoomReportRequest {
+    pub room: String,
+    pub student_ids: Vec<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct RoomReport {
+    pub room: String,
+    pub reported_by: i64,
+    pub reported_by_name: Option<String>,
+    pub students: i64, // currently located in the room
+    pub notes: Option<String>,
+    pub reported_at: String,
+}
+
+// Staff member or visitor on campus when the emergency started (or who arrived during it)
+#[derive(Debug, Serialize, FromRow)]
+pub struct EmergencyPerson {
+    #[sqlx(rename = "tipo")]
+    pub kind: String, // PERSONAL, VISITANTE
+    #[sqlx(rename = "id_persona")]
+    pub person_id: i64,
+    #[sqlx(rename = "nombre")]
+    pub name: String,
+    #[sqlx(rename = "estado")]
+    pub status: String, // LOCALIZADO, LESIONADO, FALTANTE, RETIRADO
+    #[sqlx(rename = "notas")]
+    pub notes: Option<String>,
+    #[sqlx(rename = "actualizado_en")]
+    pub updated_at: String,
+    #[sqlx(rename = "localizado_en")]
+    pub located_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PersonStatusRequest {
+    pub status: String, // LOCALIZADO, LESIONADO, FALTANTE, RETIRADO
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PeopleCounts {
+    pub total: i64,
+    pub located: i64,
+    pub injured: i64,
+    pub left: i64, // signed out during the emergency
+    pub missing: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MedicalSummaryQuery {
+    pub format: Option<String>, // json (default) or pdf
+}
+
+#[derive(Debug, Serialize)]
+pub struct MedicalContact {
+    pub name: String,
+    pub relationship: String,
+    pub phone: Option<String>,
+}
+
+// Missing or injured student as handed to paramedics
+#[derive(Debug, Serialize)]
+pub struct MedicalSummaryEntry {
+    pub student_id: String,
+    pub name: String,
+    pub group: String,
+    pub status: String, // LESIONADO, FALTANTE
+    pub status_notes: Option<String>,
+    pub location: Option<String>,
+    pub birth_date: String,
+    pub blood_type: Option<String>,
+    pub allergies: Option<String>,
+    pub chronic_diseases: Option<String>,
+    pub medications: Option<String>,
+    pub emergency_phone: Option<String>,
+    pub contacts: Vec<MedicalContact>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportQuery {
+    pub format: Option<String>, // json (default), pdf or csv
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelineEvent {
+    pub at: String,
+    pub event: String,
+    pub actor: Option<String>,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct GroupAccount {
+    pub group: String,
+    pub total: i64,
+    pub located: i64,
+    pub injured: i64,
+    pub released: i64,
+    pub missing: i64,
+}
+
+// Student never scanned in the session (still missing, or resolved without a scan)
+#[derive(Debug, Serialize, FromRow)]
+pub struct NeverFoundStudent {
+    pub student_id: String,
+    pub name: String,
+    pub group: String,
+    pub final_status: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct NotificationDelivery {
+    pub student_id: Option<String>, // null for the general emergency notices
+    pub title: String,
+    pub devices: i64,
+    pub delivered: i64,
+    pub failed: i64,
+    pub error: Option<String>,
+    pub sent_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationSummary {
+    pub sends: i64,
+    pub devices: i64,
+    pub delivered: i64,
+    pub failed: i64,
+    pub deliveries: Vec<NotificationDelivery>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct StaffScans {
+    pub user_id: i64,
+    pub name: String,
+    pub scans: i64,
+    pub first_scan: String,
+    pub last_scan: String,
+}
+
+// After-action report of one emergency session
+#[derive(Debug, Serialize)]
+pub struct AfterActionReport {
+    pub session: EmergencySession,
+    pub activated_by: Option<String>,
+    pub deactivated_by: Option<String>,
+    pub duration_seconds: Option<i64>,
+    pub counts: EmergencyCounts,
+    pub people: PeopleCounts,
+    pub timeline: Vec<TimelineEvent>,
+    pub groups: Vec<GroupAccount>,
+    pub never_found: Vec<NeverFoundStudent>,
+    pub notifications: NotificationSummary,
+    pub scans_by_staff: Vec<StaffScans>,
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct EvidenceLink {
+    pub url: String,
+    pub expires_at: String,
+}
+
+// parametros del enlace firmado
+#[derive(Debug, Deserialize)]
+pub struct SignedFileQuery {
+    pub viewer: String,
+    pub expires: i64,
+    pub signature: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct EvidenceFile {
+    pub id: i64,
+    pub nombre_archivo: String,
+    pub tipo_mime: String,
+}
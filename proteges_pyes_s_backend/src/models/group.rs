@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
+use std::collections::HashMap;
+
+use crate::models::calendar::Term;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Group {
@@ -9,4 +12,97 @@ pub struct Group {
     pub major: String,
     #[sqlx(rename = "descripcion")]
     pub description: Option<String>,
+    #[sqlx(rename = "turno")]
+    pub shift: Option<String>,
+    #[sqlx(rename = "punto_reunion")]
+    pub assembly_point_id: Option<i64>,
+    #[sqlx(rename = "grado")]
+    pub grade: Option<i64>,
+    #[sqlx(rename = "semestre")]
+    pub semester: Option<i64>,
+    #[sqlx(rename = "id_tutor")]
+    pub tutor_id: Option<i64>, // Docente in charge of the group
+    #[sqlx(default)]
+    pub tutor_name: Option<String>,
+    #[sqlx(rename = "archivado")]
+    pub archived: bool,
+    #[sqlx(rename = "archivado_en")]
+    pub archived_at: Option<String>,
+    #[sqlx(default)]
+    pub student_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GroupQuery {
+    pub include_archived: Option<bool>,
+}
+
+// `id` is only read on creation; a group's nomenclature never changes. An update replaces
+// every other field, so clients send the whole group, not only what changed
+#[derive(Debug, Deserialize)]
+pub struct GroupRequest {
+    pub id: Option<String>,
+    pub major: String,
+    pub description: Option<String>,
+    pub shift: Option<String>, // MATUTINO, VESPERTINO; none uses the GENERAL rules
+    pub semester: Option<i64>,
+    pub grade: Option<i64>, // derived from the semester when omitted
+    pub tutor_id: Option<i64>,
+}
+
+// Promotes every active group to the next semester at the start of `term_id`.
+fn preview_by_default() -> bool {
+    true
+}
+
+// Students are promoted unless listed as repeaters; last-semester students and
+// those in `graduates` are enrolled as EGRESADO from the term's start date
+#[derive(Debug, Deserialize)]
+pub struct RolloverRequest {
+    pub term_id: i64,
+    // a request without `dry_run` only previews; applying takes an explicit false
+    #[serde(default = "preview_by_default")]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub repeaters: Vec<String>,
+    #[serde(default)]
+    pub graduates: Vec<String>,
+    // next group per current group when the nomenclature doesn't start with the semester
+    #[serde(default)]
+    pub targets: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RolloverGroup {
+    pub from: String,
+    pub to: Option<String>, // none for last-semester groups
+    pub created: bool,      // `to` is created from `from` as template
+    pub promoted: i64,
+    pub repeaters: i64,
+    pub graduates: i64,
+    pub archived: bool, // `from` is left without students
+}
+
+#[derive(Debug, Serialize)]
+pub struct RolloverStudent {
+    pub student_id: String,
+    pub name: String,
+    pub from_group: String,
+    pub to_group: Option<String>,
+    pub result: String, // PROMOVIDO, REPITE, EGRESADO
+}
+
+#[derive(Debug, Serialize)]
+pub struct RolloverSkippedGroup {
+    pub group: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RolloverPlan {
+    pub dry_run: bool,
+    pub term: Term,
+    pub groups: Vec<RolloverGroup>,
+    pub skipped_groups: Vec<RolloverSkippedGroup>, // their students stay where they are
+    pub students: Vec<RolloverStudent>,
 }
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Guardian {
+    #[sqlx(rename = "id_tutor")]
+    pub id: i64,
+    #[sqlx(rename = "nombre")]
+    pub name: String,
+    #[sqlx(rename = "telefono")]
+    pub phone: Option<String>,
+    #[sqlx(rename = "telefono_alterno")]
+    pub alternate_phone: Option<String>,
+    #[sqlx(rename = "correo")]
+    pub email: Option<String>,
+    #[sqlx(rename = "notificar_push")]
+    pub notify_push: bool,
+    #[sqlx(rename = "notificar_sms")]
+    pub notify_sms: bool,
+    #[sqlx(rename = "avisos_entrada_salida")]
+    pub gate_notices: bool, // a notice for every entry/exit, not only late arrivals and absences
+    #[sqlx(rename = "creado_en")]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GuardianRequest {
+    pub name: String,
+    pub phone: Option<String>,
+    pub alternate_phone: Option<String>,
+    pub email: Option<String>,
+    pub notify_push: Option<bool>,
+    pub notify_sms: Option<bool>,
+    pub gate_notices: Option<bool>,
+}
+
+// Guardian ↔ student link
+#[derive(Debug, Serialize, FromRow)]
+pub struct GuardianLink {
+    pub guardian_id: i64,
+    pub guardian_name: String,
+    pub guardian_phone: Option<String>,
+    pub student_id: String,
+    pub student_name: String,
+    pub group: String,
+    pub relationship: String,
+    pub primary: bool,
+    pub can_pick_up: bool,
+    pub receives_notifications: bool,
+    pub release_restricted: bool, // may not take the student home (e.g. a court order)
+    pub custody_notes: Option<String>, // shown to staff, does not block a release by itself
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GuardianLinkRequest {
+    pub relationship: Option<String>,
+    pub primary: Option<bool>,
+    pub can_pick_up: Option<bool>,
+    pub receives_notifications: Option<bool>,
+    pub release_restricted: Option<bool>,
+    pub custody_notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GuardianQuery {
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GuardianDetail {
+    #[serde(flatten)]
+    pub guardian: Guardian,
+    pub students: Vec<GuardianLink>,
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct HallPass {
+    pub id: i64,
+    #[sqlx(rename = "id_control_escolar")]
+    pub student_id: String,
+    #[sqlx(default)]
+    pub student_name: Option<String>,
+    #[sqlx(default)]
+    pub group: Option<String>,
+    #[sqlx(rename = "destino")]
+    pub destination: String,
+    #[sqlx(rename = "motivo")]
+    pub reason: Option<String>,
+    #[sqlx(rename = "salon_origen")]
+    pub classroom: Option<String>,
+    #[sqlx(rename = "emitido_por")]
+    pub issued_by: i64,
+    #[sqlx(default)]
+    pub issued_by_name: Option<String>,
+    #[sqlx(rename = "emitido_en")]
+    pub issued_at: String,
+    #[sqlx(rename = "vence_en")]
+    pub expires_at: String,
+    #[sqlx(rename = "cerrado_en")]
+    pub closed_at: Option<String>,
+    #[sqlx(rename = "cerrado_por")]
+    pub closed_by: Option<i64>,
+    #[sqlx(rename = "cierre")]
+    pub closure: Option<String>, // REGRESO, SALIDA_PLANTEL
+    #[sqlx(skip)]
+    pub overdue: bool, // still open after expires_at
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HallPassRequest {
+    pub student_id: String, // id_control_escolar, RFID UID or card QR code
+    pub destination: String,
+    pub reason: Option<String>,
+    pub classroom: Option<String>,
+    pub minutes: Option<i64>, // defaults to HALL_PASS_DEFAULT_MINUTES
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HallPassQuery {
+    pub student_id: Option<String>,
+    pub date: Option<String>,  // YYYY-MM-DD; without it only open passes
+    pub overdue: Option<bool>, // only overdue open passes
+}
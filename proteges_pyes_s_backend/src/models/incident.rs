@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Incident {
+    pub id: i64,
+    #[sqlx(rename = "categoria")]
+    pub category: String,
+    #[sqlx(rename = "severidad")]
+    pub severity: String, // LEVE, MODERADA, GRAVE
+    #[sqlx(rename = "descripcion")]
+    pub description: String,
+    #[sqlx(rename = "lugar")]
+    pub location: Option<String>,
+    #[sqlx(rename = "ocurrido_en")]
+    pub occurred_at: String,
+    #[sqlx(rename = "reportado_por")]
+    pub reported_by: i64,
+    #[sqlx(default)]
+    pub reported_by_name: Option<String>,
+    #[sqlx(rename = "estado")]
+    pub status: String, // ABIERTO, CERRADO
+    #[sqlx(rename = "creado_en")]
+    pub created_at: String,
+}
+
+// Student involved in an incident and whether a guardian acknowledged it
+#[derive(Debug, Serialize, FromRow)]
+pub struct IncidentStudent {
+    pub student_id: String,
+    pub name: String,
+    pub group: String,
+    pub acknowledged_by: Option<i64>,
+    pub acknowledged_by_name: Option<String>,
+    pub acknowledged_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct IncidentAction {
+    pub id: i64,
+    #[sqlx(rename = "id_incidente")]
+    pub incident_id: i64,
+    #[sqlx(rename = "accion")]
+    pub action: String,
+    #[sqlx(rename = "notas")]
+    pub notes: Option<String>,
+    #[sqlx(rename = "registrado_por")]
+    pub recorded_by: i64,
+    #[sqlx(default)]
+    pub recorded_by_name: Option<String>,
+    #[sqlx(rename = "registrado_en")]
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IncidentDetail {
+    #[serde(flatten)]
+    pub incident: Incident,
+    pub students: Vec<IncidentStudent>,
+    pub actions: Vec<IncidentAction>,
+    pub evidence_ids: Vec<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IncidentListQuery {
+    pub student_id: Option<String>,
+    pub status: Option<String>,
+    pub category: Option<String>,
+    pub from: Option<String>, // YYYY-MM-DD
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IncidentActionRequest {
+    pub action: String,
+    pub notes: Option<String>,
+}
+
+// What a guardian sees: only their own child, without the other students involved
+#[derive(Debug, Serialize, FromRow)]
+pub struct PortalIncident {
+    pub id: i64,
+    pub student_id: String,
+    pub category: String,
+    pub severity: String,
+    pub description: String,
+    pub location: Option<String>,
+    pub occurred_at: String,
+    pub status: String,
+    pub acknowledged_at: Option<String>,
+    #[sqlx(skip)]
+    pub actions: Vec<IncidentAction>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PortalIncidentQuery {
+    pub student_id: Option<String>,
+}
@@ -5,9 +5,15 @@ pub struct Justification {
     pub id: i64,
     pub estudiante_id: String,
     pub fecha_justificacion: String,
+    pub fecha_fin: Option<String>, // inclusive; same as fecha_justificacion for one day
     pub motivo: String,
     pub evidencia_url: Option<String>,
+    pub evidencia_id: Option<i64>,
     pub estado: String,
+    pub comentario_admin: Option<String>,
+    pub revisado_por: Option<i64>,
+    pub fecha_revision: Option<String>,
+    pub reenvio_de: Option<i64>, // rejected justificante this one resubmits
     pub fecha_solicitud: String,
 }
 
@@ -19,6 +25,30 @@ pub struct CreateJustificationResponse {
 
 #[derive(Deserialize, Debug)]
 pub struct UpdateJustificationStatusRequest {
-    pub status: String, // APROBADO, RECHAZADO
+    pub status: String, // APROBADO, RECHAZADO, REVOCADO
     pub admin_comment: Option<String>,
 }
+
+#[derive(Deserialize, Debug)]
+pub struct JustificationListQuery {
+    pub student_id: Option<String>,
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct JustificationPage {
+    pub items: Vec<Justification>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
+
+// A justificante with the earlier attempts it resubmits (most recent first)
+#[derive(Serialize, Debug)]
+pub struct JustificationDetail {
+    #[serde(flatten)]
+    pub justification: Justification,
+    pub previous_attempts: Vec<Justification>,
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// Allergy, condition or current medication of a student
+#[derive(Debug, Serialize, FromRow)]
+pub struct MedicalItem {
+    pub id: i64,
+    #[sqlx(rename = "id_control_escolar")]
+    pub student_id: String,
+    #[sqlx(rename = "tipo")]
+    pub kind: String, // ALERGIA, CONDICION, MEDICAMENTO
+    #[sqlx(rename = "nombre")]
+    pub name: String,
+    #[sqlx(rename = "detalle")]
+    pub detail: Option<String>, // reaction, treatment or dose and schedule
+    #[sqlx(rename = "severidad")]
+    pub severity: Option<String>, // LEVE, MODERADA, GRAVE
+    #[sqlx(rename = "activo")]
+    pub active: bool,
+    #[sqlx(rename = "registrado_por")]
+    pub recorded_by: Option<i64>,
+    #[sqlx(rename = "actualizado_en")]
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MedicalItemRequest {
+    pub kind: Option<String>, // required when creating
+    pub name: String,
+    pub detail: Option<String>,
+    pub severity: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct InfirmaryVisit {
+    pub id: i64,
+    #[sqlx(rename = "id_control_escolar")]
+    pub student_id: String,
+    #[sqlx(default)]
+    pub student_name: Option<String>,
+    #[sqlx(rename = "atendido_por")]
+    pub seen_by: i64,
+    #[sqlx(default)]
+    pub seen_by_name: Option<String>,
+    #[sqlx(rename = "llegada")]
+    pub arrived_at: String,
+    #[sqlx(rename = "sintomas")]
+    pub symptoms: String,
+    #[sqlx(rename = "tratamiento")]
+    pub treatment: Option<String>,
+    #[sqlx(rename = "enviado_a_casa")]
+    pub sent_home: bool,
+    #[sqlx(rename = "notas")]
+    pub notes: Option<String>,
+    #[sqlx(rename = "registrado_en")]
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InfirmaryVisitRequest {
+    pub arrived_at: Option<String>, // RFC 3339, defaults to now
+    pub symptoms: String,
+    pub treatment: Option<String>,
+    pub sent_home: Option<bool>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MedicalQuery {
+    pub student_id: Option<String>,
+    pub from: Option<String>, // YYYY-MM-DD
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MedicalRecord {
+    pub student_id: String,
+    pub name: String,
+    pub group: String,
+    pub blood_type: Option<String>,
+    pub allergies: Vec<MedicalItem>,
+    pub conditions: Vec<MedicalItem>,
+    pub medications: Vec<MedicalItem>,
+    pub visits: Vec<InfirmaryVisit>,
+}
@@ -1,7 +1,19 @@
+pub mod assembly;
 pub mod attendance;
+pub mod calendar;
+pub mod card;
+pub mod emergency;
+pub mod evidence;
 pub mod group;
+pub mod guardian;
+pub mod hall_pass;
+pub mod incident;
 pub mod justification;
-pub mod notification;
+pub mod medical;
+pub mod pickup;
+pub mod portal;
+pub mod presence;
 pub mod scan;
+pub mod schedule;
 pub mod student;
 pub mod user;
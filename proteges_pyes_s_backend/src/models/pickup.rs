@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// Person other than a guardian allowed to pick up the student
+#[derive(Debug, Serialize, FromRow)]
+pub struct AuthorizedPerson {
+    #[sqlx(rename = "id_persona")]
+    pub id: i64,
+    #[sqlx(rename = "id_control_escolar")]
+    pub student_id: String,
+    #[sqlx(rename = "nombre")]
+    pub name: String,
+    #[sqlx(rename = "parentesco")]
+    pub relationship: String,
+    #[sqlx(rename = "telefono")]
+    pub phone: Option<String>,
+    #[sqlx(rename = "tipo_identificacion")]
+    pub id_type: Option<String>,
+    #[sqlx(rename = "numero_identificacion")]
+    pub id_number: Option<String>,
+    #[sqlx(rename = "foto_id")]
+    pub photo_id: Option<i64>,
+    #[sqlx(rename = "activa")]
+    pub active: bool,
+    #[sqlx(rename = "creado_en")]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuthorizedPersonRequest {
+    pub name: String,
+    pub relationship: Option<String>,
+    pub phone: Option<String>,
+    pub id_type: Option<String>,
+    pub id_number: Option<String>,
+    pub active: Option<bool>,
+}
+
+// Everyone who may show up to pick up a student, as listed to the prefecto
+#[derive(Debug, Serialize)]
+pub struct PickupContact {
+    pub kind: &'static str, // TUTOR, PERSONA
+    pub id: i64,
+    pub name: String,
+    pub relationship: String,
+    pub phone: Option<String>,
+    pub id_type: Option<String>,
+    pub id_number: Option<String>,
+    pub photo_id: Option<i64>,
+    pub allowed: bool,
+    pub custody_notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReleaseRequest {
+    pub student_id: String,
+    // exactly one of the two
+    pub guardian_id: Option<i64>,
+    pub authorized_person_id: Option<i64>,
+    pub id_verified: Option<bool>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Release {
+    pub id: i64,
+    pub student_id: String,
+    pub student_name: String,
+    pub guardian_id: Option<i64>,
+    pub authorized_person_id: Option<i64>,
+    pub received_by: String,
+    pub released_by: i64,
+    pub released_by_name: Option<String>,
+    pub id_verified: bool,
+    pub during_emergency: bool,
+    pub notes: Option<String>,
+    pub released_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReleaseQuery {
+    pub student_id: Option<String>,
+    pub date: Option<String>, // YYYY-MM-DD, defaults to today
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::guardian::{Guardian, GuardianLink};
+
+#[derive(Debug, Deserialize)]
+pub struct RequestCodeRequest {
+    pub phone: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyCodeRequest {
+    pub phone: String,
+    pub code: String,
+    pub device_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalSession {
+    pub token: String,
+    pub expires_at: String,
+    pub guardian: Guardian,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalProfile {
+    pub guardian: Guardian,
+    pub students: Vec<GuardianLink>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PortalDeviceRequest {
+    pub token: String,
+    pub device_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PortalJustificationQuery {
+    pub student_id: Option<String>,
+}
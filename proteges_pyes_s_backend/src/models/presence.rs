@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// Staff check-in / check-out on campus
+#[derive(Debug, Serialize, FromRow)]
+pub struct StaffPresence {
+    pub id: i64,
+    #[sqlx(rename = "id_usuario")]
+    pub user_id: i64,
+    #[sqlx(default)]
+    pub name: Option<String>,
+    #[sqlx(default)]
+    pub role: Option<String>,
+    #[sqlx(rename = "entrada")]
+    pub checked_in_at: String,
+    #[sqlx(rename = "salida")]
+    pub checked_out_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct Visitor {
+    pub id: i64,
+    #[sqlx(rename = "nombre")]
+    pub name: String,
+    #[sqlx(rename = "motivo")]
+    pub reason: Option<String>,
+    #[sqlx(rename = "visita_a")]
+    pub host: Option<String>,
+    #[sqlx(rename = "telefono")]
+    pub phone: Option<String>,
+    #[sqlx(rename = "tipo_identificacion")]
+    pub id_type: Option<String>,
+    #[sqlx(rename = "numero_identificacion")]
+    pub id_number: Option<String>,
+    #[sqlx(rename = "registrado_por")]
+    pub registered_by: i64,
+    #[sqlx(rename = "entrada")]
+    pub signed_in_at: String,
+    #[sqlx(rename = "salida")]
+    pub signed_out_at: Option<String>,
+    #[sqlx(rename = "salida_registrada_por")]
+    pub signed_out_by: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VisitorRequest {
+    pub name: String,
+    pub reason: Option<String>,
+    pub host: Option<String>,
+    pub phone: Option<String>,
+    pub id_type: Option<String>,
+    pub id_number: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VisitorQuery {
+    pub date: Option<String>, // YYYY-MM-DD, defaults to today
+    pub on_campus: Option<bool>,
+}
@@ -7,6 +7,9 @@ pub struct RegisterScanRequest {
     pub student_id: String,
     pub user_id: i64,
     pub scan_type: String, // "entry", "exit", "emergency"
+    // where the scan happened; the zone comes from the point when both are sent
+    pub zone_id: Option<i64>,
+    pub assembly_point_id: Option<i64>,
 }
 
 // Request to toggle scan status (safe/missing)
@@ -14,6 +17,8 @@ pub struct RegisterScanRequest {
 pub struct ToggleScanRequest {
     pub student_id: String,
     pub user_id: i64,
+    pub zone_id: Option<i64>,
+    pub assembly_point_id: Option<i64>,
 }
 
 // Scan history item
@@ -41,7 +46,18 @@ pub struct EmergencyStudent {
     pub maternal_last_name: String,
     pub group: String,
     pub major: String,
-    pub scanned: bool,
+    pub scanned: bool,  // accounted for: located, injured or released
+    pub status: String, // LOCALIZADO, LESIONADO, ENTREGADO, FALTANTE
+    pub released: bool,
+    pub released_to: Option<String>, // who picked the student up during the emergency
+    pub location: Option<String>,    // room reported by a teacher
+    pub zone: Option<String>,        // zone of the last scan
+    pub assembly_point: Option<String>,
+    pub expected_assembly_point: Option<String>,
+    pub wrong_point: bool, // found at a point other than the group's
+    // hall pass open during the emergency: where the student was last known to be
+    pub pass_destination: Option<String>,
+    pub pass_issued_at: Option<String>,
     pub teacher_color: Option<String>,
 }
 
@@ -49,13 +65,22 @@ pub struct EmergencyStudent {
 #[derive(Debug, Deserialize)]
 pub struct EmergencyTriggerRequest {
     pub active: bool,
-    pub user_id: i64,
+    // where guardians should go; used when `active: false` ends the evacuation
+    pub reunification_point: Option<String>,
+    // used when `active: true` starts the session
+    pub kind: Option<String>,     // defaults to EVENTO_REAL
+    pub protocol: Option<String>, // defaults to EVACUACION (CONFINAMIENTO for that kind)
+    pub notes: Option<String>,
 }
 
 // Emergency status response
 #[derive(Debug, Serialize)]
 pub struct EmergencyStatus {
     pub active: bool,
+    pub phase: Option<String>, // EVACUACION, REUNIFICACION
+    pub protocol: Option<String>,
+    pub session_id: Option<i64>,
+    pub reunification_point: Option<String>,
 }
 
 // Update student group request
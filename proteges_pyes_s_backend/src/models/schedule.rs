@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Schedule {
+    #[sqlx(rename = "id_horario")]
+    pub id: i64,
+    #[sqlx(rename = "grupo")]
+    pub group: String,
+    #[sqlx(rename = "materia")]
+    pub subject: String,
+    #[sqlx(rename = "docente")]
+    pub teacher_id: i64,
+    #[sqlx(default)]
+    pub teacher_name: Option<String>,
+    #[sqlx(rename = "salon_clase")]
+    pub classroom: String,
+    #[sqlx(rename = "dia_semana")]
+    pub weekday: i64, // 1 = lunes ... 7 = domingo
+    #[sqlx(rename = "hora_inicio")]
+    pub start_time: String,
+    #[sqlx(rename = "hora_fin")]
+    pub end_time: String,
+}
+
+// Used for both create and update
+#[derive(Debug, Deserialize)]
+pub struct ScheduleRequest {
+    pub group: String,
+    pub subject: String,
+    pub teacher_id: i64,
+    pub classroom: String,
+    pub weekday: i64,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleQuery {
+    pub group: Option<String>,
+    pub teacher_id: Option<i64>,
+    pub weekday: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RosterQuery {
+    pub date: Option<String>, // YYYY-MM-DD, hoy por defecto
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RollCallEntry {
+    pub student_id: String,
+    pub present: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RollCallRequest {
+    pub date: Option<String>,
+    pub records: Vec<RollCallEntry>,
+}
+
+// Expected student for a class period and whether roll was taken
+#[derive(Debug, Serialize, FromRow)]
+pub struct RosterEntry {
+    pub student_id: String,
+    pub nombre_completo: String,
+    pub present: Option<bool>, // None = todavia no se pasa lista
+}
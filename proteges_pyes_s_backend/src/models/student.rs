@@ -17,11 +17,16 @@ pub struct Student {
     pub major: String,
     #[sqlx(rename = "grupo")]
     pub group: String,
+    // Medical columns are never sent by the general student endpoints; staff read them
+    // through /medical, which checks the role and logs every access
     #[sqlx(rename = "tipo_de_sangre")]
+    #[serde(skip_serializing)]
     pub blood_type: Option<String>,
     #[sqlx(rename = "alergias")]
+    #[serde(skip_serializing)]
     pub allergies: Option<String>,
     #[sqlx(rename = "enfermedades_cronicas")]
+    #[serde(skip_serializing)]
     pub chronic_diseases: Option<String>,
     #[sqlx(rename = "domicilio")]
     pub domicile: Option<String>,
@@ -33,8 +38,14 @@ pub struct Student {
     pub secondary_guardian_phone: Option<String>,
     #[sqlx(rename = "telefono_emergencia")]
     pub emergency_phone: Option<String>,
+    // ACTIVO, BAJA, EGRESADO, SUSPENDIDO; only filled by the student endpoints
+    #[sqlx(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enrollment_status: Option<String>,
 }
 
+// Allergies and chronic conditions are not taken here: medical staff record them in the
+// structured medical record (/medical)
 #[derive(Debug, Deserialize)]
 pub struct CreateStudentRequest {
     pub id: String,
@@ -45,8 +56,6 @@ pub struct CreateStudentRequest {
     pub major: String,
     pub group: String,
     pub blood_type: Option<String>,
-    pub allergies: Option<String>,
-    pub chronic_diseases: Option<String>,
     pub domicile: Option<String>,
     pub personal_phone: Option<String>,
     pub primary_guardian_phone: Option<String>,
@@ -63,11 +72,48 @@ pub struct UpdateStudentRequest {
     pub major: String,
     pub group: String,
     pub blood_type: Option<String>,
-    pub allergies: Option<String>,
-    pub chronic_diseases: Option<String>,
     pub domicile: Option<String>,
     pub personal_phone: Option<String>,
     pub primary_guardian_phone: Option<String>,
     pub secondary_guardian_phone: Option<String>,
     pub emergency_phone: Option<String>,
 }
+
+#[derive(Debug, Deserialize)]
+pub struct StudentListQuery {
+    pub include_inactive: Option<bool>,
+}
+
+// Enrollment status change effective from `since`
+#[derive(Debug, Serialize, FromRow)]
+pub struct EnrollmentChange {
+    pub id: i64,
+    #[sqlx(rename = "id_control_escolar")]
+    pub student_id: String,
+    #[sqlx(rename = "estatus")]
+    pub status: String,
+    #[sqlx(rename = "desde")]
+    pub since: String,
+    #[sqlx(rename = "motivo")]
+    pub reason: Option<String>,
+    #[sqlx(rename = "registrado_por")]
+    pub recorded_by: i64,
+    #[sqlx(default)]
+    pub recorded_by_name: Option<String>,
+    #[sqlx(rename = "registrado_en")]
+    pub recorded_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnrollmentRequest {
+    pub status: String,
+    pub effective_date: Option<String>, // YYYY-MM-DD, defaults to today
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnrollmentInfo {
+    pub student_id: String,
+    pub status: String, // as of today
+    pub history: Vec<EnrollmentChange>,
+}
@@ -23,9 +23,20 @@ pub struct User {
 #[derive(Deserialize)]
 pub struct CreateUserRequest {
     pub username: String,
+    // the admin screen sends it as `nombre`
+    #[serde(alias = "nombre")]
     pub display_name: String,
     pub password: String,
     pub role: String,
     pub color: Option<String>,
     pub phone_number: Option<String>,
 }
+
+// Login response: the user plus the Bearer token for staff-only endpoints
+#[derive(Debug, Serialize)]
+pub struct StaffLogin {
+    #[serde(flatten)]
+    pub user: User,
+    pub token: String,
+    pub expires_at: String,
+}
@@ -0,0 +1,22 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+
+use crate::handlers::assembly_handlers::{
+    create_assembly_point, create_zone, get_assembly_points, get_zones, set_group_assembly_point,
+    update_assembly_point, update_zone,
+};
+use crate::state::SharedState;
+
+pub fn assembly_routes() -> Router<SharedState> {
+    Router::<SharedState>::new()
+        .route("/zones", get(get_zones).post(create_zone))
+        .route("/zones/{id}", put(update_zone))
+        .route(
+            "/points",
+            get(get_assembly_points).post(create_assembly_point),
+        )
+        .route("/points/{id}", put(update_assembly_point))
+        .route("/groups/{group_id}", put(set_group_assembly_point))
+}
@@ -1,7 +1,10 @@
-use crate::handlers::attendance_handlers::{get_attendance_history, register_attendance};
+use crate::handlers::attendance_handlers::{
+    get_attendance_history, get_school_day_rules, get_student_attendance_report,
+    register_attendance, run_absences, update_school_day_rule,
+};
 use crate::state::SharedState;
 use axum::{
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
 
@@ -9,4 +12,8 @@ pub fn attendance_routes() -> Router<SharedState> {
     Router::new()
         .route("/", post(register_attendance))
         .route("/history", get(get_attendance_history))
+        .route("/rules", get(get_school_day_rules))
+        .route("/rules/{shift}", put(update_school_day_rule))
+        .route("/absences/run", post(run_absences))
+        .route("/report/{student_id}", get(get_student_attendance_report))
 }
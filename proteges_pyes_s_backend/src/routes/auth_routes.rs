@@ -1,9 +1,11 @@
 use axum::{Router, routing::post};
 
-use crate::handlers::auth_handlers::login_request;
+use crate::handlers::auth_handlers::{login_request, logout_request};
 
 use crate::state::SharedState;
 
 pub fn auth_routes() -> Router<SharedState> {
-    Router::<SharedState>::new().route("/login", post(login_request))
+    Router::<SharedState>::new()
+        .route("/login", post(login_request))
+        .route("/logout", post(logout_request))
 }
@@ -0,0 +1,23 @@
+use axum::{
+    routing::{delete, get, post},
+    Router,
+};
+
+use crate::handlers::calendar_handlers::{
+    create_calendar_exception, create_school_year, create_term, delete_calendar_exception,
+    get_calendar_exceptions, get_school_days, get_school_years, import_ical,
+};
+use crate::state::SharedState;
+
+pub fn calendar_routes() -> Router<SharedState> {
+    Router::<SharedState>::new()
+        .route("/years", get(get_school_years).post(create_school_year))
+        .route("/years/{id}/terms", post(create_term))
+        .route(
+            "/exceptions",
+            get(get_calendar_exceptions).post(create_calendar_exception),
+        )
+        .route("/exceptions/{id}", delete(delete_calendar_exception))
+        .route("/import", post(import_ical))
+        .route("/school-days", get(get_school_days))
+}
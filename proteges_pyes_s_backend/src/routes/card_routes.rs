@@ -0,0 +1,10 @@
+use axum::{routing::get, Router};
+
+use crate::handlers::card_handlers::{get_group_cards, get_student_card};
+use crate::state::SharedState;
+
+pub fn card_routes() -> Router<SharedState> {
+    Router::<SharedState>::new()
+        .route("/student/{id}", get(get_student_card))
+        .route("/group/{id}", get(get_group_cards))
+}
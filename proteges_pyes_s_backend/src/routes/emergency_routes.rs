@@ -1,10 +1,13 @@
 use axum::{
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
 
 use crate::handlers::emergency_handlers::{
-    get_emergency_history, get_emergency_status, get_emergency_students, trigger_emergency,
+    close_emergency, get_drill_report, get_emergency_history, get_emergency_people,
+    get_emergency_status, get_emergency_students, get_medical_summary, get_room_reports,
+    get_session_metrics, get_session_report, get_sessions, get_zone_headcounts, report_room,
+    trigger_emergency, update_person_status, update_session, update_student_status,
 };
 use crate::state::SharedState;
 
@@ -14,4 +17,16 @@ pub fn emergency_routes() -> Router<SharedState> {
         .route("/history", get(get_emergency_history))
         .route("/trigger", post(trigger_emergency))
         .route("/status", get(get_emergency_status))
+        .route("/close", post(close_emergency))
+        .route("/students/{student_id}/status", put(update_student_status))
+        .route("/rooms", get(get_room_reports).post(report_room))
+        .route("/zones", get(get_zone_headcounts))
+        .route("/people", get(get_emergency_people))
+        .route("/medical", get(get_medical_summary))
+        .route("/people/{kind}/{id}/status", put(update_person_status))
+        .route("/sessions", get(get_sessions))
+        .route("/sessions/{id}", put(update_session))
+        .route("/sessions/{id}/metrics", get(get_session_metrics))
+        .route("/sessions/{id}/report", get(get_session_report))
+        .route("/drills/report", get(get_drill_report))
 }
@@ -0,0 +1,8 @@
+use axum::{routing::get, Router};
+
+use crate::handlers::file_handlers::download_evidence;
+use crate::state::SharedState;
+
+pub fn file_routes() -> Router<SharedState> {
+    Router::<SharedState>::new().route("/evidence/{id}", get(download_evidence))
+}
@@ -1,8 +1,22 @@
-use axum::{routing::get, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 
-use crate::handlers::group_handlers::get_all_groups;
+use crate::handlers::group_handlers::{
+    archive_group, create_group, delete_group, get_all_groups, get_group, restore_group,
+    rollover_groups, update_group,
+};
 use crate::state::SharedState;
 
 pub fn group_routes() -> Router<SharedState> {
-    Router::<SharedState>::new().route("/", get(get_all_groups))
+    Router::<SharedState>::new()
+        .route("/", get(get_all_groups).post(create_group))
+        .route("/rollover", post(rollover_groups))
+        .route(
+            "/{id}",
+            get(get_group).put(update_group).delete(delete_group),
+        )
+        .route("/{id}/archive", post(archive_group))
+        .route("/{id}/restore", post(restore_group))
 }
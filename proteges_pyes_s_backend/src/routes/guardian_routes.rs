@@ -0,0 +1,21 @@
+use axum::{
+    routing::{get, put},
+    Router,
+};
+
+use crate::handlers::guardian_handlers::{
+    create_guardian, get_guardian, get_guardians, get_student_guardians, link_student,
+    unlink_student, update_guardian,
+};
+use crate::state::SharedState;
+
+pub fn guardian_routes() -> Router<SharedState> {
+    Router::<SharedState>::new()
+        .route("/", get(get_guardians).post(create_guardian))
+        .route("/{id}", get(get_guardian).put(update_guardian))
+        .route("/student/{student_id}", get(get_student_guardians))
+        .route(
+            "/{id}/students/{student_id}",
+            put(link_student).delete(unlink_student),
+        )
+}
@@ -0,0 +1,13 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::hall_pass_handlers::{issue_pass, list_passes, return_pass};
+use crate::state::SharedState;
+
+pub fn hall_pass_routes() -> Router<SharedState> {
+    Router::<SharedState>::new()
+        .route("/", get(list_passes).post(issue_pass))
+        .route("/{id}/return", post(return_pass))
+}
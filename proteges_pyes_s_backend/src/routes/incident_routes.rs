@@ -0,0 +1,32 @@
+use axum::{
+    extract::DefaultBodyLimit,
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::incident_handlers::{
+    add_incident_action, create_incident, get_incident, get_incident_evidence_link,
+    get_student_incidents, list_incidents, MAX_INCIDENT_EVIDENCE,
+};
+use crate::state::SharedState;
+use crate::utils::uploads::MAX_UPLOAD_BYTES;
+
+pub fn incident_routes() -> Router<SharedState> {
+    Router::<SharedState>::new()
+        .route(
+            "/",
+            // every evidence file may use the full per-file cap
+            get(list_incidents)
+                .post(create_incident)
+                .layer(DefaultBodyLimit::max(
+                    MAX_INCIDENT_EVIDENCE * MAX_UPLOAD_BYTES + 64 * 1024,
+                )),
+        )
+        .route("/{id}", get(get_incident))
+        .route("/{id}/actions", post(add_incident_action))
+        .route(
+            "/{id}/evidence/{evidence_id}",
+            get(get_incident_evidence_link),
+        )
+        .route("/students/{student_id}", get(get_student_incidents))
+}
@@ -1,17 +1,27 @@
 use crate::{
     handlers::justification_handlers::{
-        get_pending_justifications, submit_justification, update_justification_status,
+        get_evidence_link, get_justification, get_pending_justifications, list_justifications,
+        submit_justification, update_justification_status,
     },
     state::SharedState,
+    utils::uploads::MAX_UPLOAD_BYTES,
 };
 use axum::{
+    extract::DefaultBodyLimit,
     routing::{get, post, put},
     Router,
 };
 
 pub fn justification_routes() -> Router<SharedState> {
     Router::<SharedState>::new()
-        .route("/submit", post(submit_justification))
+        .route(
+            "/submit",
+            // room for the text fields and multipart framing on top of the file cap
+            post(submit_justification).layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES + 64 * 1024)),
+        )
+        .route("/", get(list_justifications))
         .route("/pending", get(get_pending_justifications))
+        .route("/{id}", get(get_justification))
         .route("/{id}/status", put(update_justification_status))
+        .route("/{id}/evidence", get(get_evidence_link))
 }
@@ -0,0 +1,25 @@
+use axum::{
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::handlers::medical_handlers::{
+    create_medical_item, deactivate_medical_item, get_infirmary_visits, get_medical_record,
+    record_infirmary_visit, update_medical_item,
+};
+use crate::state::SharedState;
+
+pub fn medical_routes() -> Router<SharedState> {
+    Router::<SharedState>::new()
+        .route("/students/{student_id}", get(get_medical_record))
+        .route("/students/{student_id}/items", post(create_medical_item))
+        .route(
+            "/items/{id}",
+            put(update_medical_item).delete(deactivate_medical_item),
+        )
+        .route(
+            "/students/{student_id}/visits",
+            post(record_infirmary_visit),
+        )
+        .route("/visits", get(get_infirmary_visits))
+}
@@ -1,27 +1,46 @@
+mod assembly_routes;
 mod attendance_routes;
 mod auth_routes;
+mod calendar_routes;
+mod card_routes;
 mod emergency_routes;
+mod file_routes;
 mod group_routes;
+mod guardian_routes;
+mod hall_pass_routes;
+mod incident_routes;
 mod justification_routes;
-mod notification_routes;
+mod medical_routes;
 mod parent_routes;
+mod pickup_routes;
+mod presence_routes;
 mod scan_routes;
+mod schedule_routes;
 mod stats_routes;
 mod student_routes;
 mod user_routes;
 
 use axum::Router;
 use tower_http::cors::{Any, CorsLayer};
-use tower_http::services::ServeDir;
 
+use crate::routes::assembly_routes::assembly_routes;
 use crate::routes::attendance_routes::attendance_routes;
 use crate::routes::auth_routes::auth_routes;
+use crate::routes::calendar_routes::calendar_routes;
+use crate::routes::card_routes::card_routes;
 use crate::routes::emergency_routes::emergency_routes;
+use crate::routes::file_routes::file_routes;
 use crate::routes::group_routes::group_routes;
+use crate::routes::guardian_routes::guardian_routes;
+use crate::routes::hall_pass_routes::hall_pass_routes;
+use crate::routes::incident_routes::incident_routes;
 use crate::routes::justification_routes::justification_routes;
-use crate::routes::notification_routes::notification_routes;
+use crate::routes::medical_routes::medical_routes;
 use crate::routes::parent_routes::parent_routes;
+use crate::routes::pickup_routes::pickup_routes;
+use crate::routes::presence_routes::presence_routes;
 use crate::routes::scan_routes::scan_routes;
+use crate::routes::schedule_routes::schedule_routes;
 use crate::routes::stats_routes::stats_routes;
 use crate::routes::student_routes::student_routes;
 use crate::routes::user_routes::user_routes;
@@ -40,11 +59,20 @@ pub fn create_router() -> Router<SharedState> {
         .nest("/scan", scan_routes())
         .nest("/emergency", emergency_routes())
         .nest("/groups", group_routes())
+        .nest("/assembly", assembly_routes())
+        .nest("/guardians", guardian_routes())
+        .nest("/pickups", pickup_routes())
+        .nest("/presence", presence_routes())
+        .nest("/cards", card_routes())
         .nest("/stats", stats_routes())
         .nest("/attendance", attendance_routes())
-        .nest("/public", parent_routes())
-        .nest("/notifications", notification_routes())
+        .nest("/schedules", schedule_routes())
+        .nest("/calendar", calendar_routes())
+        .nest("/portal", parent_routes())
+        .nest("/incidents", incident_routes())
+        .nest("/passes", hall_pass_routes())
         .nest("/justifications", justification_routes())
-        .nest_service("/uploads", ServeDir::new("uploads"))
+        .nest("/medical", medical_routes())
+        .nest("/files", file_routes())
         .layer(cors)
 }
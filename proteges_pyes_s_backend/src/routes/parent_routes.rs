@@ -1,8 +1,29 @@
-use axum::{routing::get, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 
-use crate::handlers::parent_handlers::get_student_portal_info;
+use crate::handlers::parent_handlers::{
+    acknowledge_incident, get_child, get_children, get_incidents, get_justification_evidence,
+    get_justifications, get_profile, logout, register_device, request_code, verify_code,
+};
 use crate::state::SharedState;
 
+// portal de tutores: todo salvo el inicio de sesion requiere `Authorization: Bearer <token>`
 pub fn parent_routes() -> Router<SharedState> {
-    Router::<SharedState>::new().route("/student/{id}", get(get_student_portal_info))
+    Router::<SharedState>::new()
+        .route("/auth/request-code", post(request_code))
+        .route("/auth/verify", post(verify_code))
+        .route("/auth/logout", post(logout))
+        .route("/me", get(get_profile))
+        .route("/students", get(get_children))
+        .route("/students/{id}", get(get_child))
+        .route("/devices", post(register_device))
+        .route("/justifications", get(get_justifications))
+        .route(
+            "/justifications/{id}/evidence",
+            get(get_justification_evidence),
+        )
+        .route("/incidents", get(get_incidents))
+        .route("/incidents/{id}/acknowledge", post(acknowledge_incident))
 }
@@ -0,0 +1,32 @@
+use axum::{
+    extract::DefaultBodyLimit,
+    routing::{get, post, put},
+    Router,
+};
+
+use crate::handlers::pickup_handlers::{
+    create_authorized_person, deactivate_authorized_person, get_person_photo, get_pickup_contacts,
+    get_releases, release_student, update_authorized_person, upload_person_photo,
+};
+use crate::state::SharedState;
+use crate::utils::uploads::MAX_UPLOAD_BYTES;
+
+pub fn pickup_routes() -> Router<SharedState> {
+    Router::<SharedState>::new()
+        .route(
+            "/student/{student_id}",
+            get(get_pickup_contacts).post(create_authorized_person),
+        )
+        .route(
+            "/persons/{id}",
+            put(update_authorized_person).delete(deactivate_authorized_person),
+        )
+        .route(
+            "/persons/{id}/photo",
+            get(get_person_photo)
+                .post(upload_person_photo)
+                .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES + 64 * 1024)),
+        )
+        .route("/release", post(release_student))
+        .route("/releases", get(get_releases))
+}
@@ -0,0 +1,19 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::presence_handlers::{
+    get_staff_on_campus, get_visitors, sign_in_visitor, sign_out_visitor, staff_check_in,
+    staff_check_out,
+};
+use crate::state::SharedState;
+
+pub fn presence_routes() -> Router<SharedState> {
+    Router::<SharedState>::new()
+        .route("/staff", get(get_staff_on_campus))
+        .route("/staff/check-in", post(staff_check_in))
+        .route("/staff/check-out", post(staff_check_out))
+        .route("/visitors", get(get_visitors).post(sign_in_visitor))
+        .route("/visitors/{id}/sign-out", post(sign_out_visitor))
+}
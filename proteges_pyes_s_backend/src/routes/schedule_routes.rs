@@ -0,0 +1,24 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::schedule_handlers::{
+    create_schedule, delete_schedule, get_current_schedule, get_schedule, get_schedule_roster,
+    get_schedules, take_roll_call, update_schedule,
+};
+use crate::state::SharedState;
+
+pub fn schedule_routes() -> Router<SharedState> {
+    Router::<SharedState>::new()
+        .route("/", get(get_schedules).post(create_schedule))
+        .route("/current", get(get_current_schedule))
+        .route(
+            "/{id}",
+            get(get_schedule)
+                .put(update_schedule)
+                .delete(delete_schedule),
+        )
+        .route("/{id}/roster", get(get_schedule_roster))
+        .route("/{id}/roll-call", post(take_roll_call))
+}
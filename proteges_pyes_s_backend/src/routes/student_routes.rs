@@ -4,7 +4,8 @@ use axum::{
 };
 
 use crate::handlers::student_handlers::{
-    create_student, get_all_students, get_student, update_student_group,
+    create_student, delete_student, get_all_students, get_enrollment, get_student, set_enrollment,
+    update_student_group,
 };
 use crate::state::SharedState;
 
@@ -12,10 +13,11 @@ pub fn student_routes() -> Router<SharedState> {
     Router::<SharedState>::new()
         .route("/", post(create_student))
         .route("/all", get(get_all_students))
-        .route("/{id}", get(get_student))
+        .route("/{id}", get(get_student).delete(delete_student))
         .route(
             "/{id}/update",
             post(crate::handlers::student_handlers::update_student),
         )
         .route("/{id}/group", post(update_student_group))
+        .route("/{id}/enrollment", get(get_enrollment).post(set_enrollment))
 }
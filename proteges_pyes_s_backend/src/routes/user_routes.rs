@@ -1,10 +1,14 @@
-use axum::{routing::get, Router};
+use axum::{
+    routing::{get, post},
+    Router,
+};
 
-use crate::handlers::user_handlers::{get_all_users, get_user};
+use crate::handlers::user_handlers::{create_user, get_all_users, get_user};
 use crate::state::SharedState;
 
 pub fn user_routes() -> Router<SharedState> {
     Router::<SharedState>::new()
         .route("/{username}", get(get_user))
         .route("/all", get(get_all_users))
+        .route("/create", post(create_user))
 }
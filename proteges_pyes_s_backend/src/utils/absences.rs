@@ -0,0 +1,190 @@
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use sqlx::{Pool, Sqlite};
+use tracing::{info, warn};
+
+use crate::constants::SYSTEM_USER_ID;
+use crate::models::attendance::SchoolDayRule;
+use crate::utils::calendar::{SchoolCalendar, TERM_FOR_DATE};
+use crate::utils::enrollment::active_on_date;
+use crate::utils::justifications::approved_justification;
+use crate::utils::notifications::{send_push_notification, NotificationKind};
+use crate::utils::schedules::parse_time;
+
+// cada cuanto revisa el job si ya paso la hora de corte de algun turno
+const CHECK_INTERVAL_SECS: u64 = 60;
+
+#[derive(Debug)]
+pub struct ShiftClosure {
+    pub shift: String,
+    pub absences: i64,
+}
+
+// registrar las faltas de un turno en la fecha indicada; regresa cuantas se insertaron
+async fn close_shift(
+    pool: &Pool<Sqlite>,
+    date: NaiveDate,
+    rule: &SchoolDayRule,
+) -> Result<i64, sqlx::Error> {
+    let date_str = date.format("%Y-%m-%d").to_string();
+    let calendar = SchoolCalendar::load(pool, date, date).await?;
+
+    // alumnos inscritos del turno sin entrada en el acceso, sin asistencia en clase y sin falta
+    // previa; GENERAL cubre los grupos sin turno o cuyo turno no tiene regla propia
+    let absentees: Vec<(String, String)> = sqlx::query_as(&format!(
+        r#"
+        SELECT e.id_control_escolar, e.grupo
+        FROM estudiantes e
+        JOIN grupos g ON e.grupo = g.id_nomenclatura
+        WHERE (
+            g.turno = ?
+            OR (? = 'GENERAL' AND (g.turno IS NULL OR g.turno NOT IN (SELECT turno FROM reglas_jornada)))
+        )
+        AND NOT EXISTS (
+            SELECT 1 FROM asistencias a
+            WHERE a.id_control_escolar = e.id_control_escolar
+            AND substr(a.fecha_asistencia, 1, 10) = ?
+            AND (a.tipo_registro IN ('ENTRADA', 'FALTA') OR (a.id_horario IS NOT NULL AND a.presente))
+        )
+        AND {}
+        "#,
+        active_on_date()
+    ))
+    .bind(&rule.shift)
+    .bind(&rule.shift)
+    .bind(&date_str)
+    .bind(&date_str)
+    .fetch_all(pool)
+    .await?;
+
+    let timestamp = format!("{}T{}:00", date_str, rule.absence_cutoff);
+    let mut inserted = 0;
+
+    // solo cuentan los grupos que tuvieron clases (festivos, suspensiones y excepciones)
+    for (student_id, group) in absentees
+        .into_iter()
+        .filter(|(_, group)| calendar.is_school_day(date, Some(group)))
+    {
+        // si ya hay un justificante aprobado para ese dia la falta nace justificada
+        let justification = approved_justification(pool, &student_id, &date_str).await?;
+
+        sqlx::query(&format!(
+            "INSERT INTO asistencias (id_control_escolar, registrado_por, fecha_asistencia, salon_clase, presente, tipo_registro, justificacion, id_justificante, fecha_modificacion, grupo, id_periodo) VALUES (?, ?, ?, ?, FALSE, 'FALTA', ?, ?, ?, ?, {})",
+            TERM_FOR_DATE
+        ))
+        .bind(&student_id)
+        .bind(SYSTEM_USER_ID)
+        .bind(&timestamp)
+        .bind(&group)
+        .bind(justification.as_ref().map(|(_, reason)| reason.clone()))
+        .bind(justification.as_ref().map(|(id, _)| *id))
+        .bind(justification.as_ref().map(|_| Local::now().to_rfc3339()))
+        .bind(&group)
+        .bind(&date_str)
+        .execute(pool)
+        .await?;
+        inserted += 1;
+
+        if justification.is_none() {
+            let pool = pool.clone();
+            let body = format!(
+                "Tu hijo/a no registró entrada el día {}. Si la falta tiene justificación puedes enviar un justificante desde la app.",
+                date_str
+            );
+            tokio::spawn(async move {
+                if let Err(e) = send_push_notification(
+                    &pool,
+                    &student_id,
+                    NotificationKind::Important,
+                    "Inasistencia Registrada",
+                    &body,
+                )
+                .await
+                {
+                    warn!("Error enviando notificación de falta: {}", e);
+                }
+            });
+        }
+    }
+
+    sqlx::query(
+        "INSERT INTO cierres_jornada (fecha, turno, faltas) VALUES (?, ?, ?) ON CONFLICT(fecha, turno) DO UPDATE SET faltas = faltas + excluded.faltas, ejecutado_en = CURRENT_TIMESTAMP",
+    )
+    .bind(&date_str)
+    .bind(&rule.shift)
+    .bind(inserted)
+    .execute(pool)
+    .await?;
+
+    Ok(inserted)
+}
+
+// ya paso la hora de corte del turno en el momento indicado
+fn cutoff_passed(rule: &SchoolDayRule, now: NaiveDateTime) -> bool {
+    parse_time(&rule.absence_cutoff)
+        .map(|cutoff| now.time() >= cutoff)
+        .unwrap_or(false)
+}
+
+// cerrar la jornada en la fecha (ejecucion manual); hoy solo se cierran los turnos cuya
+// hora de corte ya paso, los demas los cierra el job
+pub async fn close_school_day(
+    pool: &Pool<Sqlite>,
+    date: NaiveDate,
+) -> Result<Vec<ShiftClosure>, sqlx::Error> {
+    let now = Local::now().naive_local();
+    let mut closures = Vec::new();
+    let rules = sqlx::query_as::<_, SchoolDayRule>("SELECT * FROM reglas_jornada ORDER BY turno")
+        .fetch_all(pool)
+        .await?;
+    for rule in rules {
+        if date == now.date() && !cutoff_passed(&rule, now) {
+            continue;
+        }
+        let absences = close_shift(pool, date, &rule).await?;
+        closures.push(ShiftClosure {
+            shift: rule.shift,
+            absences,
+        });
+    }
+    Ok(closures)
+}
+
+// job en segundo plano: cierra cada turno una vez pasada su hora de corte
+pub async fn run_absence_scheduler(pool: Pool<Sqlite>) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+
+        let now = Local::now();
+        let today = now.date_naive();
+
+        // turnos cuya hora de corte ya paso y que no se han cerrado hoy
+        let pending = sqlx::query_as::<_, SchoolDayRule>(
+            "SELECT r.* FROM reglas_jornada r WHERE NOT EXISTS (SELECT 1 FROM cierres_jornada c WHERE c.fecha = ? AND c.turno = r.turno)",
+        )
+        .bind(today.format("%Y-%m-%d").to_string())
+        .fetch_all(&pool)
+        .await;
+
+        let pending = match pending {
+            Ok(rules) => rules,
+            Err(e) => {
+                warn!("Error al consultar reglas de jornada: {}", e);
+                continue;
+            }
+        };
+
+        for rule in pending {
+            if !cutoff_passed(&rule, now.naive_local()) {
+                continue;
+            }
+            match close_shift(&pool, today, &rule).await {
+                Ok(absences) => info!(
+                    "Jornada {} del turno {} cerrada con {} faltas",
+                    today, rule.shift, absences
+                ),
+                Err(e) => warn!("Error al cerrar la jornada del turno {}: {}", rule.shift, e),
+            }
+        }
+    }
+}
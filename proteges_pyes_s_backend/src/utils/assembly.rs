@@ -0,0 +1,149 @@
+use sqlx::{Pool, Sqlite};
+
+use crate::models::assembly::{StudentPlacement, ZoneHeadcount};
+
+// zona y punto donde se registra un escaneo; la zona se toma del punto cuando se indica.
+// None si el punto o la zona no existen o estan inactivos
+pub async fn resolve_scan_location(
+    pool: &Pool<Sqlite>,
+    zone_id: Option<i64>,
+    point_id: Option<i64>,
+) -> Result<Option<(Option<i64>, Option<i64>)>, sqlx::Error> {
+    if let Some(point_id) = point_id {
+        let zone: Option<i64> =
+            sqlx::query_scalar("SELECT id_zona FROM puntos_reunion WHERE id = ? AND activo")
+                .bind(point_id)
+                .fetch_optional(pool)
+                .await?;
+        return Ok(zone.map(|zone| (Some(zone), Some(point_id))));
+    }
+    if let Some(zone_id) = zone_id {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM zonas WHERE id = ? AND activa)")
+                .bind(zone_id)
+                .fetch_one(pool)
+                .await?;
+        return Ok(exists.then_some((Some(zone_id), None)));
+    }
+    Ok(Some((None, None)))
+}
+
+// punto de reunion del grupo del alumno (id, nombre, zona)
+pub async fn expected_point(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+) -> Result<Option<(i64, String, i64)>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT p.id, p.nombre, p.id_zona
+        FROM estudiantes e
+        JOIN grupos g ON g.id_nomenclatura = e.grupo
+        JOIN puntos_reunion p ON p.id = g.punto_reunion
+        WHERE e.id_control_escolar = ?
+        "#,
+    )
+    .bind(student_id)
+    .fetch_optional(pool)
+    .await
+}
+
+// ultimo escaneo de cada alumno en la sesion junto al punto que le toca a su grupo
+pub async fn student_placements(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+) -> Result<Vec<StudentPlacement>, sqlx::Error> {
+    sqlx::query_as::<_, StudentPlacement>(
+        r#"
+        SELECT
+            s.id_control_escolar AS student_id,
+            h.id_zona AS zone_id,
+            z.nombre AS zone_name,
+            h.id_punto AS point_id,
+            p.nombre AS point_name,
+            g.punto_reunion AS expected_point_id,
+            pe.nombre AS expected_point_name,
+            pe.id_zona AS expected_zone_id,
+            s.estado AS status
+        FROM estado_alumnos_emergencia s
+        JOIN estudiantes e ON e.id_control_escolar = s.id_control_escolar
+        LEFT JOIN grupos g ON g.id_nomenclatura = e.grupo
+        LEFT JOIN puntos_reunion pe ON pe.id = g.punto_reunion
+        LEFT JOIN historial_consulta h ON h.id_escaneo = (
+            SELECT MAX(id_escaneo) FROM historial_consulta
+            WHERE sesion_id = s.sesion_id AND estudiante_consultado = s.id_control_escolar
+        )
+        LEFT JOIN zonas z ON z.id = h.id_zona
+        LEFT JOIN puntos_reunion p ON p.id = h.id_punto
+        WHERE s.sesion_id = ?
+        "#,
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await
+}
+
+// conteo por zona para los cuerpos de emergencia; los localizados sin zona van al final
+pub async fn zone_headcounts(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+) -> Result<Vec<ZoneHeadcount>, sqlx::Error> {
+    let zones: Vec<(i64, String)> =
+        sqlx::query_as("SELECT id, nombre FROM zonas WHERE activa ORDER BY nombre")
+            .fetch_all(pool)
+            .await?;
+    let placements = student_placements(pool, session_id).await?;
+
+    let mut headcounts: Vec<ZoneHeadcount> = zones
+        .into_iter()
+        .map(|(id, name)| ZoneHeadcount {
+            zone_id: Some(id),
+            zone_name: name,
+            present: 0,
+            injured: 0,
+            expected: 0,
+            wrong_point: 0,
+        })
+        .collect();
+    let mut unzoned = ZoneHeadcount {
+        zone_id: None,
+        zone_name: "Sin zona".to_string(),
+        present: 0,
+        injured: 0,
+        expected: 0,
+        wrong_point: 0,
+    };
+
+    for placement in &placements {
+        // los entregados ya no cuentan en ninguna zona
+        if placement.status == "ENTREGADO" {
+            continue;
+        }
+        if let Some(expected_zone) = placement.expected_zone_id {
+            if let Some(headcount) = headcounts
+                .iter_mut()
+                .find(|headcount| headcount.zone_id == Some(expected_zone))
+            {
+                headcount.expected += 1;
+            }
+        }
+        if placement.status == "FALTANTE" {
+            continue;
+        }
+        let headcount = match placement.zone_id.and_then(|zone| {
+            headcounts
+                .iter_mut()
+                .find(|headcount| headcount.zone_id == Some(zone))
+        }) {
+            Some(headcount) => headcount,
+            None => &mut unzoned,
+        };
+        headcount.present += 1;
+        headcount.injured += (placement.status == "LESIONADO") as i64;
+        headcount.wrong_point += placement.wrong_point() as i64;
+    }
+
+    if unzoned.present > 0 {
+        headcounts.push(unzoned);
+    }
+    Ok(headcounts)
+}
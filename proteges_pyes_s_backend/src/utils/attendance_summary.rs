@@ -0,0 +1,110 @@
+use chrono::NaiveDate;
+use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
+
+use crate::models::attendance::{AttendanceSummary, DayAttendance};
+
+// fecha, tipo_registro, clasificacion, presente, id_horario, id_justificante
+type AttendanceRow = (
+    String,
+    Option<String>,
+    Option<String>,
+    bool,
+    Option<i64>,
+    Option<i64>,
+);
+
+// estado de asistencia del alumno en cada dia con clases indicado
+pub async fn attendance_by_day(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+    days: &[NaiveDate],
+) -> Result<Vec<DayAttendance>, sqlx::Error> {
+    let (Some(first), Some(last)) = (days.first(), days.last()) else {
+        return Ok(Vec::new());
+    };
+
+    let rows: Vec<AttendanceRow> = sqlx::query_as(
+        r#"
+        SELECT substr(fecha_asistencia, 1, 10), tipo_registro, clasificacion, presente, id_horario, id_justificante
+        FROM asistencias
+        WHERE id_control_escolar = ?
+        AND substr(fecha_asistencia, 1, 10) BETWEEN ? AND ?
+        ORDER BY fecha_asistencia
+        "#,
+    )
+    .bind(student_id)
+    .bind(first.format("%Y-%m-%d").to_string())
+    .bind(last.format("%Y-%m-%d").to_string())
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_date: HashMap<String, &'static str> = HashMap::new();
+    for (date, record_type, classification, present, schedule_id, justification_id) in &rows {
+        let current = by_date.get(date).copied();
+        let status = match (record_type.as_deref(), current) {
+            // la primera entrada del dia define si llego a tiempo
+            (
+                Some("ENTRADA"),
+                None | Some("FALTA") | Some("FALTA_JUSTIFICADA") | Some("PRESENTE_CLASE"),
+            ) => match (classification.as_deref(), justification_id) {
+                (Some("RETARDO"), Some(_)) => "RETARDO_JUSTIFICADO",
+                (Some("RETARDO"), None) => "RETARDO",
+                _ => "PRESENTE",
+            },
+            (None, None | Some("FALTA") | Some("FALTA_JUSTIFICADA"))
+                if schedule_id.is_some() && *present =>
+            {
+                "PRESENTE_CLASE"
+            }
+            (Some("FALTA"), None) if justification_id.is_some() => "FALTA_JUSTIFICADA",
+            (Some("FALTA"), None) => "FALTA",
+            (_, Some(existing)) => existing,
+            _ => continue,
+        };
+        by_date.insert(date.clone(), status);
+    }
+
+    Ok(days
+        .iter()
+        .map(|day| {
+            let date = day.format("%Y-%m-%d").to_string();
+            let status = match by_date.get(&date).copied() {
+                Some("PRESENTE_CLASE") => "PRESENTE",
+                Some(status) => status,
+                None => "SIN_REGISTRO",
+            };
+            DayAttendance {
+                date,
+                status: status.to_string(),
+            }
+        })
+        .collect())
+}
+
+pub fn summarize(days: &[DayAttendance]) -> AttendanceSummary {
+    let count = |status: &str| days.iter().filter(|day| day.status == status).count() as i64;
+    let justified_late = count("RETARDO_JUSTIFICADO");
+    let late = count("RETARDO") + justified_late;
+    let present = count("PRESENTE") + late;
+    let justified_absences = count("FALTA_JUSTIFICADA");
+    let unjustified_absences = count("FALTA");
+    let absences = justified_absences + unjustified_absences;
+    let attendance_rate = if present + absences > 0 {
+        Some((present as f64 * 1000.0 / (present + absences) as f64).round() / 10.0)
+    } else {
+        None
+    };
+
+    AttendanceSummary {
+        school_days: days.len() as i64,
+        present,
+        late,
+        justified_late,
+        absences,
+        justified_absences,
+        unjustified_absences,
+        unrecorded: count("SIN_REGISTRO"),
+        attendance_rate,
+    }
+}
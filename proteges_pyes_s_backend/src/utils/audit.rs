@@ -0,0 +1,31 @@
+use sqlx::{Pool, Sqlite};
+use tracing::warn;
+
+// registrar un acceso a informacion sensible; un fallo en la bitacora no debe
+// interrumpir la respuesta, solo se reporta en el log
+pub async fn log_access(
+    pool: &Pool<Sqlite>,
+    resource: &str,
+    resource_id: &str,
+    action: &str,
+    actor: &str,
+    ip: Option<&str>,
+) {
+    let result = sqlx::query(
+        "INSERT INTO bitacora_accesos (recurso, recurso_id, accion, actor, ip) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(resource)
+    .bind(resource_id)
+    .bind(action)
+    .bind(actor)
+    .bind(ip)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        warn!(
+            "No se pudo registrar el acceso a {} {}: {}",
+            resource, resource_id, e
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts, StatusCode},
+    Json,
+};
+use sqlx::{Pool, Sqlite};
+
+use crate::{state::AppState, utils::tokens::sign};
+
+// rol del usuario (None si no existe); solo para endpoints que aun reciben el id en la peticion,
+// lo sensible se autoriza con StaffSession
+pub async fn user_role(pool: &Pool<Sqlite>, user_id: i64) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT rol FROM usuarios WHERE id_usuario = ?")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
+
+// los tokens de sesion no se guardan en claro, solo su firma
+pub fn session_token_hash(token: &str) -> String {
+    sign(&format!("sesion.{}", token))
+}
+
+fn bearer_token(parts: &Parts) -> Option<&str> {
+    parts
+        .headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+// tutor autenticado con `Authorization: Bearer <token>` del portal
+#[derive(Debug, Clone)]
+pub struct GuardianSession {
+    pub guardian_id: i64,
+    pub token_hash: String,
+}
+
+impl FromRequestParts<AppState> for GuardianSession {
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let unauthorized = |message: &str| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({ "error": message })),
+            )
+        };
+
+        let token =
+            bearer_token(parts).ok_or_else(|| unauthorized("Inicia sesión en el portal"))?;
+
+        let token_hash = session_token_hash(token);
+        let guardian_id: Option<i64> = sqlx::query_scalar(
+            "SELECT id_tutor FROM sesiones_tutores WHERE token_hash = ? AND NOT revocada AND expira_en > ?",
+        )
+        .bind(&token_hash)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            )
+        })?;
+
+        match guardian_id {
+            Some(guardian_id) => Ok(GuardianSession {
+                guardian_id,
+                token_hash,
+            }),
+            None => Err(unauthorized("La sesión expiró, vuelve a iniciar sesión")),
+        }
+    }
+}
+
+// personal autenticado con `Authorization: Bearer <token>` del login
+#[derive(Debug, Clone)]
+pub struct StaffSession {
+    pub user_id: i64,
+    pub role: String,
+    pub token_hash: String,
+}
+
+impl StaffSession {
+    pub fn has_role(&self, roles: &[&str]) -> bool {
+        roles.contains(&self.role.as_str())
+    }
+
+    // actor para la bitacora de accesos y los enlaces firmados
+    pub fn actor(&self) -> String {
+        format!("usuario:{}", self.user_id)
+    }
+}
+
+impl FromRequestParts<AppState> for StaffSession {
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let unauthorized = |message: &str| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(serde_json::json!({ "error": message })),
+            )
+        };
+
+        let token = bearer_token(parts).ok_or_else(|| unauthorized("Inicia sesión"))?;
+
+        let token_hash = session_token_hash(token);
+        let user: Option<(i64, String)> = sqlx::query_as(
+            r#"
+            SELECT u.id_usuario, u.rol FROM sesiones_usuarios s
+            JOIN usuarios u ON u.id_usuario = s.id_usuario
+            WHERE s.token_hash = ? AND NOT s.revocada AND s.expira_en > ?
+            "#,
+        )
+        .bind(&token_hash)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            )
+        })?;
+
+        match user {
+            Some((user_id, role)) => Ok(StaffSession {
+                user_id,
+                role,
+                token_hash,
+            }),
+            None => Err(unauthorized("La sesión expiró, vuelve a iniciar sesión")),
+        }
+    }
+}
+
+// el tutor tiene vinculado al estudiante
+pub async fn guardian_has_student(
+    pool: &Pool<Sqlite>,
+    guardian_id: i64,
+    student_id: &str,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM tutores_estudiantes WHERE id_tutor = ? AND id_control_escolar = ?)",
+    )
+    .bind(guardian_id)
+    .bind(student_id)
+    .fetch_one(pool)
+    .await
+}
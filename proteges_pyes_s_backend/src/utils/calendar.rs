@@ -0,0 +1,226 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use sqlx::{Pool, Sqlite};
+
+use crate::models::calendar::{CalendarException, Term};
+
+pub const EXCEPTION_KINDS: [&str; 3] = ["FESTIVO", "SUSPENSION", "DIA_HABIL"];
+
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+// calendario cargado para un rango de fechas; evita una consulta por dia
+pub struct SchoolCalendar {
+    terms: Vec<(NaiveDate, NaiveDate)>,
+    exceptions: Vec<(NaiveDate, NaiveDate, String, Option<String>)>,
+}
+
+impl SchoolCalendar {
+    pub async fn load(
+        pool: &Pool<Sqlite>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Self, sqlx::Error> {
+        let from_str = from.format("%Y-%m-%d").to_string();
+        let to_str = to.format("%Y-%m-%d").to_string();
+
+        let mut terms: Vec<(NaiveDate, NaiveDate)> = sqlx::query_as::<_, Term>(
+            "SELECT * FROM periodos_escolares WHERE fecha_inicio <= ? AND fecha_fin >= ?",
+        )
+        .bind(&to_str)
+        .bind(&from_str)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .filter_map(|term| Some((parse_date(&term.start_date)?, parse_date(&term.end_date)?)))
+        .collect();
+
+        // sin periodos registrados cualquier fecha cuenta (solo aplican fines de semana y
+        // excepciones); con periodos, fuera de ellos no hay clases
+        let any_term: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM periodos_escolares)")
+            .fetch_one(pool)
+            .await?;
+        if !any_term {
+            terms.push((NaiveDate::MIN, NaiveDate::MAX));
+        }
+
+        let exceptions = sqlx::query_as::<_, CalendarException>(
+            "SELECT * FROM calendario_excepciones WHERE fecha_inicio <= ? AND fecha_fin >= ?",
+        )
+        .bind(&to_str)
+        .bind(&from_str)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .filter_map(|exception| {
+            Some((
+                parse_date(&exception.start_date)?,
+                parse_date(&exception.end_date)?,
+                exception.kind,
+                exception.group,
+            ))
+        })
+        .collect();
+
+        Ok(Self { terms, exceptions })
+    }
+
+    // dia con clases para el grupo (None = a nivel escuela)
+    pub fn is_school_day(&self, date: NaiveDate, group: Option<&str>) -> bool {
+        let in_term = self
+            .terms
+            .iter()
+            .any(|(start, end)| *start <= date && date <= *end);
+        if !in_term {
+            return false;
+        }
+
+        // la excepcion del grupo tiene prioridad sobre la de toda la escuela
+        let matching = |for_group: bool| {
+            self.exceptions
+                .iter()
+                .find(|(start, end, _, exception_group)| {
+                    *start <= date
+                        && date <= *end
+                        && match (exception_group.as_deref(), for_group) {
+                            (Some(g), true) => Some(g) == group,
+                            (None, false) => true,
+                            _ => false,
+                        }
+                })
+        };
+        if let Some((_, _, kind, _)) = matching(true).or_else(|| matching(false)) {
+            return kind == "DIA_HABIL";
+        }
+
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+    }
+
+    // dias con clases en el rango inclusivo
+    pub fn school_days(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        group: Option<&str>,
+    ) -> Vec<NaiveDate> {
+        from.iter_days()
+            .take_while(|date| *date <= to)
+            .filter(|date| self.is_school_day(*date, group))
+            .collect()
+    }
+}
+
+// los ultimos `count` dias con clases hasta `until` (revisa como maximo un año hacia atras)
+pub async fn last_school_days(
+    pool: &Pool<Sqlite>,
+    until: NaiveDate,
+    count: usize,
+    group: Option<&str>,
+) -> Result<Vec<NaiveDate>, sqlx::Error> {
+    let from = until - Duration::days(366);
+    let calendar = SchoolCalendar::load(pool, from, until).await?;
+    let mut days = calendar.school_days(from, until, group);
+    let skip = days.len().saturating_sub(count);
+    Ok(days.split_off(skip))
+}
+
+// periodo escolar que contiene la fecha del parametro (`?`, admite fecha y hora)
+pub const TERM_FOR_DATE: &str =
+    "(SELECT p.id FROM periodos_escolares p WHERE substr(?, 1, 10) BETWEEN p.fecha_inicio AND p.fecha_fin)";
+
+pub async fn find_term(pool: &Pool<Sqlite>, id: i64) -> Result<Option<Term>, sqlx::Error> {
+    sqlx::query_as::<_, Term>("SELECT * FROM periodos_escolares WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+#[derive(Debug)]
+pub struct IcalEvent {
+    pub uid: Option<String>,
+    pub summary: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate, // inclusiva
+}
+
+#[derive(Default)]
+struct PartialEvent {
+    uid: Option<String>,
+    summary: String,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    all_day: bool,
+}
+
+// lector sencillo de VEVENT (DTSTART/DTEND/SUMMARY/UID) suficiente para calendarios de festivos
+pub fn parse_ical(content: &str) -> Vec<IcalEvent> {
+    // desdoblar lineas continuadas (RFC 5545: la continuacion empieza con espacio o tab)
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let line = raw.trim_end_matches('\r');
+        if (line.starts_with(' ') || line.starts_with('\t')) && !lines.is_empty() {
+            lines.last_mut().unwrap().push_str(&line[1..]);
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<PartialEvent> = None;
+
+    for line in lines {
+        if line == "BEGIN:VEVENT" {
+            current = Some(PartialEvent::default());
+            continue;
+        }
+        if line == "END:VEVENT" {
+            if let Some(event) = current.take() {
+                if let Some(start) = event.start {
+                    // en eventos de dia completo DTEND es exclusiva
+                    let end = match event.end {
+                        Some(end) if event.all_day && end > start => end - Duration::days(1),
+                        Some(end) if end >= start => end,
+                        _ => start,
+                    };
+                    events.push(IcalEvent {
+                        uid: event.uid,
+                        summary: event.summary,
+                        start,
+                        end,
+                    });
+                }
+            }
+            continue;
+        }
+        let Some(event) = current.as_mut() else {
+            continue;
+        };
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = key.split(';').next().unwrap_or("");
+        match name {
+            "UID" => event.uid = Some(value.to_string()),
+            "SUMMARY" => {
+                event.summary = value
+                    .replace("\\,", ",")
+                    .replace("\\;", ";")
+                    .replace("\\n", " ")
+            }
+            "DTSTART" | "DTEND" => {
+                let date = value
+                    .get(..8)
+                    .and_then(|digits| NaiveDate::parse_from_str(digits, "%Y%m%d").ok());
+                if name == "DTSTART" {
+                    event.start = date;
+                    event.all_day = key.contains("VALUE=DATE") || value.len() == 8;
+                } else {
+                    event.end = date;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
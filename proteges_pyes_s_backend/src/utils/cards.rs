@@ -0,0 +1,165 @@
+use qrcode::{Color, QrCode};
+
+use crate::models::student::Student;
+use crate::utils::pdf::{PdfPage, PAGE_HEIGHT};
+use crate::utils::raster::Raster;
+
+// tamaño de credencial CR80 en puntos (85.6 x 54 mm)
+pub const CARD_WIDTH: f32 = 243.0;
+pub const CARD_HEIGHT: f32 = 153.0;
+// acomodo en hoja carta: 2 columnas x 4 filas
+pub const CARDS_PER_ROW: usize = 2;
+pub const CARDS_PER_PAGE: usize = 8;
+const MARGIN_X: f32 = 48.0;
+const MARGIN_Y: f32 = 60.0;
+const GAP: f32 = 30.0;
+// imagen PNG: margen en puntos y pixeles por punto
+const PNG_MARGIN: f32 = 12.0;
+const PNG_SCALE: f32 = 3.0;
+
+// superficie en la que se dibuja la credencial: hoja PDF o imagen PNG
+pub trait CardCanvas {
+    fn text(&mut self, x: f32, y: f32, size: f32, bold: bool, text: &str);
+    fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, gray: f32);
+    fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32);
+}
+
+impl CardCanvas for PdfPage {
+    fn text(&mut self, x: f32, y: f32, size: f32, bold: bool, text: &str) {
+        PdfPage::text(self, x, y, size, bold, text)
+    }
+    fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, gray: f32) {
+        PdfPage::fill_rect(self, x, y, width, height, gray)
+    }
+    fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        PdfPage::stroke_rect(self, x, y, width, height)
+    }
+}
+
+impl CardCanvas for Raster {
+    fn text(&mut self, x: f32, y: f32, size: f32, bold: bool, text: &str) {
+        Raster::text(self, x, y, size, bold, text)
+    }
+    fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, gray: f32) {
+        Raster::fill_rect(self, x, y, width, height, gray)
+    }
+    fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        Raster::stroke_rect(self, x, y, width, height)
+    }
+}
+
+// modulos oscuros del QR como matriz cuadrada
+pub fn qr_matrix(data: &str) -> Result<(usize, Vec<bool>), qrcode::types::QrError> {
+    let code = QrCode::new(data.as_bytes())?;
+    let width = code.width();
+    let modules = code
+        .to_colors()
+        .into_iter()
+        .map(|color| color == Color::Dark)
+        .collect();
+    Ok((width, modules))
+}
+
+// esquina inferior izquierda de la credencial `slot` (0..CARDS_PER_PAGE) en la hoja carta
+pub fn page_slot(slot: usize) -> (f32, f32) {
+    let column = slot % CARDS_PER_ROW;
+    let row = slot / CARDS_PER_ROW;
+    (
+        MARGIN_X + column as f32 * (CARD_WIDTH + GAP),
+        PAGE_HEIGHT - MARGIN_Y - CARD_HEIGHT - row as f32 * (CARD_HEIGHT + GAP / 2.0),
+    )
+}
+
+// imagen PNG con las credenciales en CARDS_PER_ROW columnas; `cards` trae el QR de cada alumno
+pub fn cards_png(cards: &[(&Student, String)]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let columns = cards.len().clamp(1, CARDS_PER_ROW) as f32;
+    let rows = cards.len().div_ceil(CARDS_PER_ROW).max(1) as f32;
+    let width = PNG_MARGIN * 2.0 + columns * CARD_WIDTH + (columns - 1.0) * GAP;
+    let height = PNG_MARGIN * 2.0 + rows * CARD_HEIGHT + (rows - 1.0) * GAP / 2.0;
+
+    let mut raster = Raster::new(width, height, PNG_SCALE);
+    for (slot, (student, qr_data)) in cards.iter().enumerate() {
+        let column = (slot % CARDS_PER_ROW) as f32;
+        let row = (slot / CARDS_PER_ROW) as f32;
+        let x = PNG_MARGIN + column * (CARD_WIDTH + GAP);
+        let y = height - PNG_MARGIN - CARD_HEIGHT - row * (CARD_HEIGHT + GAP / 2.0);
+        draw_card(&mut raster, x, y, student, qr_data)?;
+    }
+    Ok(raster.to_png()?)
+}
+
+// dibujar la credencial con su esquina inferior izquierda en (x, y); sin datos medicos,
+// el tipo de sangre solo se consulta con el resumen medico de emergencia
+pub fn draw_card(
+    page: &mut impl CardCanvas,
+    x: f32,
+    y: f32,
+    student: &Student,
+    qr_data: &str,
+) -> Result<(), qrcode::types::QrError> {
+    page.stroke_rect(x, y, CARD_WIDTH, CARD_HEIGHT);
+    // franja superior
+    page.fill_rect(x, y + CARD_HEIGHT - 24.0, CARD_WIDTH, 24.0, 0.85);
+    page.text(
+        x + 8.0,
+        y + CARD_HEIGHT - 16.0,
+        10.0,
+        true,
+        "CREDENCIAL ESTUDIANTIL",
+    );
+
+    let full_name = format!(
+        "{} {}",
+        student.paternal_last_name,
+        student.maternal_last_name.clone().unwrap_or_default()
+    );
+    let mut line_y = y + CARD_HEIGHT - 42.0;
+    page.text(x + 8.0, line_y, 9.0, true, student.names.trim());
+    line_y -= 12.0;
+    page.text(x + 8.0, line_y, 9.0, true, full_name.trim());
+    line_y -= 18.0;
+    page.text(
+        x + 8.0,
+        line_y,
+        8.0,
+        false,
+        &format!("Grupo: {}", student.group),
+    );
+    line_y -= 11.0;
+    page.text(
+        x + 8.0,
+        line_y,
+        8.0,
+        false,
+        &format!("Especialidad: {}", student.major),
+    );
+    line_y -= 11.0;
+    page.text(
+        x + 8.0,
+        line_y,
+        8.0,
+        false,
+        &format!("No. control: {}", student.id),
+    );
+
+    // QR a la derecha de la credencial
+    let (width, modules) = qr_matrix(qr_data)?;
+    let qr_size = 86.0;
+    let module = qr_size / width as f32;
+    let qr_x = x + CARD_WIDTH - qr_size - 8.0;
+    let qr_y = y + 10.0;
+    for (i, dark) in modules.iter().enumerate() {
+        if *dark {
+            let mx = (i % width) as f32;
+            let my = (i / width) as f32;
+            page.fill_rect(
+                qr_x + mx * module,
+                qr_y + qr_size - (my + 1.0) * module,
+                module,
+                module,
+                0.0,
+            );
+        }
+    }
+    Ok(())
+}
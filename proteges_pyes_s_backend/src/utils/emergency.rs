@@ -0,0 +1,212 @@
+use chrono::Local;
+use sqlx::{Pool, Sqlite};
+
+use crate::models::emergency::{EmergencyCounts, EmergencySession};
+use crate::utils::notifications::{send_push_notification, NotificationKind};
+
+// alumnos (alias `e`) en el plantel en la fecha del parametro: con ENTRADA ese dia y sin
+// SALIDA posterior; los que no llegaron no cuentan como faltantes en la emergencia
+pub const ON_CAMPUS_STUDENT: &str = r#"
+    EXISTS (
+        SELECT 1 FROM asistencias a
+        WHERE a.id_control_escolar = e.id_control_escolar
+        AND a.tipo_registro = 'ENTRADA'
+        AND substr(a.fecha_asistencia, 1, 10) = ?
+        AND NOT EXISTS (
+            SELECT 1 FROM asistencias b
+            WHERE b.id_control_escolar = a.id_control_escolar
+            AND b.tipo_registro = 'SALIDA'
+            AND b.fecha_asistencia > a.fecha_asistencia
+        )
+    )
+"#;
+
+// sesion en curso (evacuacion o reunificacion)
+pub async fn current_session<'e, E>(executor: E) -> Result<Option<EmergencySession>, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = Sqlite>,
+{
+    sqlx::query_as::<_, EmergencySession>(
+        "SELECT * FROM sesiones_emergencia WHERE fase <> 'CERRADA' ORDER BY id DESC LIMIT 1",
+    )
+    .fetch_optional(executor)
+    .await
+}
+
+// sesion en curso o, si no hay, la ultima que se cerro
+pub async fn latest_session(pool: &Pool<Sqlite>) -> Result<Option<EmergencySession>, sqlx::Error> {
+    sqlx::query_as::<_, EmergencySession>(
+        "SELECT * FROM sesiones_emergencia ORDER BY (fase <> 'CERRADA') DESC, id DESC LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+// estado de cada alumno de la sesion (alumno, estado, ubicacion)
+pub async fn student_statuses(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+) -> Result<Vec<(String, String, Option<String>)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id_control_escolar, estado, ubicacion FROM estado_alumnos_emergencia WHERE sesion_id = ?",
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn student_status<'e, E>(
+    executor: E,
+    session_id: i64,
+    student_id: &str,
+) -> Result<Option<String>, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = Sqlite>,
+{
+    sqlx::query_scalar(
+        "SELECT estado FROM estado_alumnos_emergencia WHERE sesion_id = ? AND id_control_escolar = ?",
+    )
+    .bind(session_id)
+    .bind(student_id)
+    .fetch_optional(executor)
+    .await
+}
+
+pub async fn set_student_status<'e, E>(
+    executor: E,
+    session_id: i64,
+    student_id: &str,
+    status: &str,
+    user_id: i64,
+    notes: Option<&str>,
+) -> Result<(), sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO estado_alumnos_emergencia (sesion_id, id_control_escolar, grupo, estado, notas, actualizado_por, actualizado_en, localizado_en)
+        VALUES (
+            ?1, ?2, (SELECT grupo FROM estudiantes WHERE id_control_escolar = ?2),
+            ?3, ?4, ?5, ?6, CASE WHEN ?3 = 'FALTANTE' THEN NULL ELSE ?6 END
+        )
+        ON CONFLICT(sesion_id, id_control_escolar) DO UPDATE SET
+            estado = excluded.estado,
+            notas = COALESCE(excluded.notas, notas),
+            actualizado_por = excluded.actualizado_por,
+            actualizado_en = excluded.actualizado_en,
+            localizado_en = CASE
+                WHEN excluded.estado = 'FALTANTE' THEN NULL
+                ELSE COALESCE(localizado_en, excluded.actualizado_en)
+            END,
+            ubicacion = CASE WHEN excluded.estado = 'FALTANTE' THEN NULL ELSE ubicacion END
+        "#,
+    )
+    .bind(session_id)
+    .bind(student_id)
+    .bind(status)
+    .bind(notes)
+    .bind(user_id)
+    .bind(Local::now().to_rfc3339())
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+pub async fn session_counts(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+) -> Result<EmergencyCounts, sqlx::Error> {
+    let (total, located, injured, released, missing): (i64, i64, i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            COUNT(*),
+            COALESCE(SUM(estado = 'LOCALIZADO'), 0),
+            COALESCE(SUM(estado = 'LESIONADO'), 0),
+            COALESCE(SUM(estado = 'ENTREGADO'), 0),
+            COALESCE(SUM(estado = 'FALTANTE'), 0)
+        FROM estado_alumnos_emergencia
+        WHERE sesion_id = ?
+        "#,
+    )
+    .bind(session_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(EmergencyCounts {
+        total,
+        located,
+        injured,
+        released,
+        missing,
+    })
+}
+
+// aviso general al activar cada protocolo (titulo, cuerpo); tambien son las
+// instrucciones que ve el tutor en el portal mientras dura la emergencia
+pub fn protocol_notice(protocol: &str) -> (&'static str, &'static str) {
+    match protocol {
+        "CONFINAMIENTO" => (
+            "🔒 CONFINAMIENTO EN LA ESCUELA",
+            "La escuela activó el protocolo de confinamiento. No acuda al plantel ni llame a su hijo/a: el personal lo mantiene resguardado en su salón. Le avisaremos cuando sea seguro.",
+        ),
+        "RESGUARDO" => (
+            "🏠 RESGUARDO EN LA ESCUELA",
+            "Los alumnos permanecen resguardados dentro del plantel por un riesgo en el exterior. No acuda a la escuela hasta nuevo aviso.",
+        ),
+        _ => (
+            "⚠️ EMERGENCIA ACTIVADA",
+            "Se ha activado el protocolo de evacuación en la escuela via Proteges-PyEs. Por favor manténgase atento; se le informará el punto de reunificación.",
+        ),
+    }
+}
+
+pub const EMERGENCY_CLOSED_NOTICE: (&str, &str) = (
+    "✅ Emergencia Desactivada",
+    "El protocolo de emergencia ha sido desactivado. Todo vuelve a la normalidad.",
+);
+
+// aviso dirigido a los tutores de un alumno durante la reunificacion: a donde acudir
+// segun el estado del alumno (a los entregados ya no se les avisa)
+pub async fn notify_reunification(
+    pool: &Pool<Sqlite>,
+    session: &EmergencySession,
+    student_id: &str,
+    student_name: &str,
+    status: &str,
+) {
+    let point = session
+        .reunification_point
+        .as_deref()
+        .unwrap_or("el punto de reunificación designado por la escuela");
+    let (title, body) = match status {
+        "LOCALIZADO" => (
+            "Reunificación: recoja a su hijo/a",
+            format!(
+                "{} se encuentra a salvo. Puede recogerlo/a en {}. Presente una identificación oficial.",
+                student_name, point
+            ),
+        ),
+        "LESIONADO" => (
+            "Reunificación: atención médica",
+            format!(
+                "{} recibió atención por una lesión. Preséntese en {} y solicite hablar con el personal médico.",
+                student_name, point
+            ),
+        ),
+        "FALTANTE" => (
+            "Reunificación: búsqueda en curso",
+            format!(
+                "Seguimos localizando a {}. Le avisaremos en cuanto tengamos información.",
+                student_name
+            ),
+        ),
+        _ => return,
+    };
+
+    if let Err(e) =
+        send_push_notification(pool, student_id, NotificationKind::Emergency, title, &body).await
+    {
+        println!("Error enviando aviso de reunificación: {}", e);
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::Local;
+use sqlx::{Pool, Sqlite};
+
+use crate::models::emergency::{EmergencySession, MedicalContact, MedicalSummaryEntry};
+use crate::models::student::Student;
+use crate::utils::guardians::links_for_student;
+use crate::utils::medical::{active_items, describe_items};
+use crate::utils::pdf::{PdfDocument, PAGE_HEIGHT, PAGE_WIDTH};
+
+const MARGIN: f32 = 40.0;
+const ENTRY_HEIGHT: f32 = 82.0;
+// caracteres por renglon en Helvetica 8 dentro del margen
+const LINE_CHARS: usize = 110;
+
+// lesionados primero, despues faltantes; con los datos medicos y a quien llamar
+pub async fn medical_summary(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+) -> Result<Vec<MedicalSummaryEntry>, sqlx::Error> {
+    let rows: Vec<(String, String, Option<String>, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT s.id_control_escolar, s.estado, s.notas, s.ubicacion
+        FROM estado_alumnos_emergencia s
+        JOIN estudiantes e ON e.id_control_escolar = s.id_control_escolar
+        WHERE s.sesion_id = ? AND s.estado IN ('LESIONADO', 'FALTANTE')
+        ORDER BY s.estado = 'FALTANTE', e.grupo, e.apellido_paterno
+        "#,
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut entries = Vec::new();
+    for (student_id, status, notes, location) in rows {
+        let student =
+            sqlx::query_as::<_, Student>("SELECT * FROM estudiantes WHERE id_control_escolar = ?")
+                .bind(&student_id)
+                .fetch_one(pool)
+                .await?;
+
+        let mut contacts: Vec<MedicalContact> = links_for_student(pool, &student_id)
+            .await?
+            .into_iter()
+            .map(|link| MedicalContact {
+                name: link.guardian_name,
+                relationship: link.relationship,
+                phone: link.guardian_phone,
+            })
+            .collect();
+        // alumnos sin tutores vinculados: los telefonos capturados en su ficha
+        if contacts.is_empty() {
+            for (label, phone) in [
+                ("Tutor principal", &student.primary_guardian_phone),
+                ("Tutor secundario", &student.secondary_guardian_phone),
+            ] {
+                if phone.is_some() {
+                    contacts.push(MedicalContact {
+                        name: label.to_string(),
+                        relationship: "NO_ESPECIFICADO".to_string(),
+                        phone: phone.clone(),
+                    });
+                }
+            }
+        }
+
+        // el expediente estructurado manda; la ficha libre queda como respaldo
+        let items = active_items(pool, &student_id).await?;
+        let allergies = describe_items(&items, "ALERGIA").or(student.allergies);
+        let chronic_diseases = describe_items(&items, "CONDICION").or(student.chronic_diseases);
+        let medications = describe_items(&items, "MEDICAMENTO");
+
+        entries.push(MedicalSummaryEntry {
+            name: format!(
+                "{} {} {}",
+                student.names,
+                student.paternal_last_name,
+                student.maternal_last_name.unwrap_or_default()
+            )
+            .trim()
+            .to_string(),
+            student_id,
+            group: student.group,
+            status,
+            status_notes: notes,
+            location,
+            birth_date: student.birth_date,
+            blood_type: student.blood_type,
+            allergies,
+            chronic_diseases,
+            medications,
+            emergency_phone: student.emergency_phone,
+            contacts,
+        });
+    }
+    Ok(entries)
+}
+
+fn or_none(value: &Option<String>) -> &str {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or("Ninguna registrada")
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= LINE_CHARS {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(LINE_CHARS - 3).collect();
+    short.push_str("...");
+    short
+}
+
+// hoja imprimible para paramedicos
+pub fn medical_summary_pdf(session: &EmergencySession, entries: &[MedicalSummaryEntry]) -> Vec<u8> {
+    let mut document = PdfDocument::new();
+    let generated = Local::now().format("%Y-%m-%d %H:%M").to_string();
+    let per_page = ((PAGE_HEIGHT - 2.0 * MARGIN - 50.0) / ENTRY_HEIGHT) as usize;
+
+    let chunks: Vec<&[MedicalSummaryEntry]> = if entries.is_empty() {
+        vec![&[]]
+    } else {
+        entries.chunks(per_page).collect()
+    };
+    let pages = chunks.len();
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let page = document.add_page();
+        let mut y = PAGE_HEIGHT - MARGIN;
+        page.text(MARGIN, y - 12.0, 13.0, true, "RESUMEN MEDICO - EMERGENCIA");
+        page.text(
+            MARGIN,
+            y - 28.0,
+            8.0,
+            false,
+            &format!(
+                "Sesion {} ({}) - generado {} - hoja {} de {} - CONFIDENCIAL",
+                session.id,
+                session.kind,
+                generated,
+                index + 1,
+                pages
+            ),
+        );
+        y -= 50.0;
+
+        if chunk.is_empty() {
+            page.text(
+                MARGIN,
+                y - 12.0,
+                10.0,
+                false,
+                "No hay alumnos lesionados ni faltantes.",
+            );
+        }
+
+        for entry in chunk {
+            let top = y;
+            page.stroke_rect(
+                MARGIN,
+                top - ENTRY_HEIGHT + 6.0,
+                PAGE_WIDTH - 2.0 * MARGIN,
+                ENTRY_HEIGHT - 6.0,
+            );
+            page.fill_rect(MARGIN, top - 16.0, PAGE_WIDTH - 2.0 * MARGIN, 16.0, 0.88);
+            page.text(
+                MARGIN + 6.0,
+                top - 12.0,
+                10.0,
+                true,
+                &truncate(&format!(
+                    "{} - {} - {} - {}",
+                    entry.status, entry.name, entry.group, entry.student_id
+                )),
+            );
+            let contacts = entry
+                .contacts
+                .iter()
+                .map(|contact| {
+                    format!(
+                        "{} ({}) {}",
+                        contact.name,
+                        contact.relationship,
+                        contact.phone.as_deref().unwrap_or("sin telefono")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("; ");
+            let lines = [
+                format!(
+                    "Sangre: {}   Nacimiento: {}   Tel. emergencia: {}",
+                    entry.blood_type.as_deref().unwrap_or("?"),
+                    entry.birth_date,
+                    entry.emergency_phone.as_deref().unwrap_or("-")
+                ),
+                format!("Alergias: {}", or_none(&entry.allergies)),
+                format!(
+                    "Enfermedades cronicas: {}   Medicamentos: {}",
+                    or_none(&entry.chronic_diseases),
+                    or_none(&entry.medications)
+                ),
+                format!(
+                    "Contactos: {}",
+                    if contacts.is_empty() { "-" } else { &contacts }
+                ),
+                format!(
+                    "Notas: {}   Ubicacion: {}",
+                    entry.status_notes.as_deref().unwrap_or("-"),
+                    entry.location.as_deref().unwrap_or("-")
+                ),
+            ];
+            for (line_index, line) in lines.iter().enumerate() {
+                page.text(
+                    MARGIN + 6.0,
+                    top - 28.0 - line_index as f32 * 11.0,
+                    8.0,
+                    line_index == 1,
+                    &truncate(line),
+                );
+            }
+            y -= ENTRY_HEIGHT;
+        }
+    }
+    document.to_bytes()
+}
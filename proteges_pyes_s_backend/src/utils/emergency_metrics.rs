@@ -0,0 +1,198 @@
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
+use sqlx::{Pool, Sqlite};
+
+use crate::models::emergency::{
+    DrillComparison, DrillComparisonRow, EmergencySession, GroupMetric, SessionMetrics,
+    StudentFound,
+};
+use crate::utils::emergency::session_counts;
+
+// grupos y alumnos que se muestran en el reporte
+const SLOWEST_GROUPS: usize = 5;
+const LAST_FOUND: usize = 10;
+
+// las sesiones guardan hora local RFC 3339; las migradas vienen de CURRENT_TIMESTAMP (UTC)
+pub fn parse_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value).ok().or_else(|| {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .map(|naive| naive.and_utc().fixed_offset())
+    })
+}
+
+pub fn seconds_between(from: &str, to: &str) -> Option<i64> {
+    Some((parse_timestamp(to)? - parse_timestamp(from)?).num_seconds())
+}
+
+// segundos hasta tener localizado el `percent` % del total; None si no se alcanzo
+fn seconds_to_percent(sorted_seconds: &[i64], total: i64, percent: i64) -> Option<i64> {
+    if total == 0 {
+        return None;
+    }
+    let needed = (total * percent + 99) / 100;
+    sorted_seconds.get((needed - 1) as usize).copied()
+}
+
+pub async fn session_metrics(
+    pool: &Pool<Sqlite>,
+    session: EmergencySession,
+) -> Result<SessionMetrics, sqlx::Error> {
+    let counts = session_counts(pool, session.id).await?;
+
+    let rows: Vec<(String, String, String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT s.id_control_escolar, e.nombres || ' ' || e.apellido_paterno, COALESCE(s.grupo, e.grupo), s.localizado_en
+        FROM estado_alumnos_emergencia s
+        JOIN estudiantes e ON e.id_control_escolar = s.id_control_escolar
+        WHERE s.sesion_id = ?
+        "#,
+    )
+    .bind(session.id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut found: Vec<StudentFound> = rows
+        .iter()
+        .filter_map(|(student_id, name, group, found_at)| {
+            let found_at = found_at.as_ref()?;
+            Some(StudentFound {
+                student_id: student_id.clone(),
+                name: name.clone(),
+                group: group.clone(),
+                found_at: found_at.clone(),
+                seconds: seconds_between(&session.started_at, found_at)?.max(0),
+            })
+        })
+        .collect();
+    found.sort_by_key(|student| student.seconds);
+    let sorted_seconds: Vec<i64> = found.iter().map(|student| student.seconds).collect();
+
+    // por grupo: tiempo hasta que aparecio el ultimo de sus alumnos
+    let mut groups: Vec<GroupMetric> = Vec::new();
+    for (student_id, _, group, _) in &rows {
+        let seconds = found
+            .iter()
+            .find(|student| student.student_id == *student_id)
+            .map(|student| student.seconds);
+        match groups.iter_mut().find(|metric| metric.group == *group) {
+            Some(metric) => {
+                metric.total += 1;
+                metric.accounted += seconds.is_some() as i64;
+                metric.seconds_to_complete = match (metric.seconds_to_complete, seconds) {
+                    (Some(current), Some(seconds)) => Some(current.max(seconds)),
+                    _ => None,
+                };
+            }
+            None => groups.push(GroupMetric {
+                group: group.clone(),
+                total: 1,
+                accounted: seconds.is_some() as i64,
+                seconds_to_complete: seconds,
+            }),
+        }
+    }
+    // los grupos incompletos primero, despues los que tardaron mas
+    groups.sort_by_key(|metric| {
+        (
+            metric.seconds_to_complete.is_some(),
+            std::cmp::Reverse(metric.seconds_to_complete),
+        )
+    });
+    groups.truncate(SLOWEST_GROUPS);
+
+    let last_found: Vec<StudentFound> = found.into_iter().rev().take(LAST_FOUND).collect();
+
+    Ok(SessionMetrics {
+        evacuation_seconds: session
+            .reunification_at
+            .as_deref()
+            .or(session.closed_at.as_deref())
+            .and_then(|end| seconds_between(&session.started_at, end)),
+        seconds_to_50: seconds_to_percent(&sorted_seconds, counts.total, 50),
+        seconds_to_90: seconds_to_percent(&sorted_seconds, counts.total, 90),
+        seconds_to_100: seconds_to_percent(&sorted_seconds, counts.total, 100),
+        slowest_groups: groups,
+        last_found,
+        counts,
+        session,
+    })
+}
+
+// comparativo de simulacros en orden cronologico; cada uno contra el anterior del mismo tipo
+pub async fn drill_comparison(
+    pool: &Pool<Sqlite>,
+    kind: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<DrillComparison, sqlx::Error> {
+    let sessions = sqlx::query_as::<_, EmergencySession>(
+        r#"
+        SELECT * FROM sesiones_emergencia
+        WHERE tipo LIKE 'SIMULACRO%'
+        AND (?1 IS NULL OR tipo = ?1)
+        AND (?2 IS NULL OR substr(iniciada_en, 1, 10) >= ?2)
+        AND (?3 IS NULL OR substr(iniciada_en, 1, 10) <= ?3)
+        ORDER BY iniciada_en
+        "#,
+    )
+    .bind(kind)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    let mut drills: Vec<DrillComparisonRow> = Vec::new();
+    for session in sessions {
+        let metrics = session_metrics(pool, session).await?;
+        let previous = drills
+            .iter()
+            .rev()
+            .find(|row| row.kind == metrics.session.kind)
+            .and_then(|row| row.seconds_to_90);
+        drills.push(DrillComparisonRow {
+            session_id: metrics.session.id,
+            kind: metrics.session.kind,
+            started_at: metrics.session.started_at,
+            total: metrics.counts.total,
+            accounted: metrics.counts.total - metrics.counts.missing,
+            seconds_to_50: metrics.seconds_to_50,
+            seconds_to_90: metrics.seconds_to_90,
+            seconds_to_100: metrics.seconds_to_100,
+            change_to_90: previous
+                .zip(metrics.seconds_to_90)
+                .map(|(previous, current)| current - previous),
+        });
+    }
+
+    let times: Vec<i64> = drills.iter().filter_map(|row| row.seconds_to_90).collect();
+    Ok(DrillComparison {
+        average_seconds_to_90: (!times.is_empty())
+            .then(|| times.iter().sum::<i64>() / times.len() as i64),
+        best_seconds_to_90: times.iter().min().copied(),
+        drills,
+    })
+}
+
+fn csv_field(value: Option<i64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+pub fn drill_comparison_csv(comparison: &DrillComparison) -> String {
+    let mut csv =
+        String::from("sesion,tipo,fecha,total,localizados,seg_50,seg_90,seg_100,cambio_seg_90\n");
+    for row in &comparison.drills {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{}\n",
+            row.session_id,
+            row.kind,
+            row.started_at,
+            row.total,
+            row.accounted,
+            csv_field(row.seconds_to_50),
+            csv_field(row.seconds_to_90),
+            csv_field(row.seconds_to_100),
+            csv_field(row.change_to_90),
+        ));
+    }
+    csv
+}
@@ -0,0 +1,611 @@
+use sqlx::{Pool, Sqlite};
+
+use crate::models::emergency::{
+    AfterActionReport, EmergencySession, GroupAccount, NeverFoundStudent, NotificationDelivery,
+    NotificationSummary, StaffScans, TimelineEvent,
+};
+use crate::utils::emergency::session_counts;
+use crate::utils::emergency_metrics::{parse_timestamp, seconds_between};
+use crate::utils::pdf::{PdfDocument, PAGE_HEIGHT};
+use crate::utils::presence::people_counts;
+
+async fn user_name(
+    pool: &Pool<Sqlite>,
+    user_id: Option<i64>,
+) -> Result<Option<String>, sqlx::Error> {
+    let Some(user_id) = user_id else {
+        return Ok(None);
+    };
+    sqlx::query_scalar("SELECT nombre_mostrado FROM usuarios WHERE id_usuario = ?")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await
+}
+
+// linea de tiempo: fases de la sesion, avisos generales, primer y ultimo alumno localizado,
+// reportes de salon y entregas
+async fn timeline(
+    pool: &Pool<Sqlite>,
+    session: &EmergencySession,
+    activated_by: &Option<String>,
+    deactivated_by: &Option<String>,
+) -> Result<Vec<TimelineEvent>, sqlx::Error> {
+    let mut events = vec![TimelineEvent {
+        at: session.started_at.clone(),
+        event: "ACTIVACION".to_string(),
+        actor: activated_by.clone(),
+        detail: Some(format!("{} - protocolo {}", session.kind, session.protocol)),
+    }];
+    if let Some(at) = &session.reunification_at {
+        events.push(TimelineEvent {
+            at: at.clone(),
+            event: "REUNIFICACION".to_string(),
+            actor: None,
+            detail: session.reunification_point.clone(),
+        });
+    }
+    if let Some(at) = &session.closed_at {
+        events.push(TimelineEvent {
+            at: at.clone(),
+            event: "CIERRE".to_string(),
+            actor: deactivated_by.clone(),
+            detail: None,
+        });
+    }
+
+    let (first, last): (Option<String>, Option<String>) = sqlx::query_as(
+        "SELECT MIN(localizado_en), MAX(localizado_en) FROM estado_alumnos_emergencia WHERE sesion_id = ? AND localizado_en IS NOT NULL",
+    )
+    .bind(session.id)
+    .fetch_one(pool)
+    .await?;
+    if let Some(at) = first {
+        events.push(TimelineEvent {
+            at,
+            event: "PRIMER_ALUMNO_LOCALIZADO".to_string(),
+            actor: None,
+            detail: None,
+        });
+    }
+    if let Some(at) = last {
+        events.push(TimelineEvent {
+            at,
+            event: "ULTIMO_ALUMNO_LOCALIZADO".to_string(),
+            actor: None,
+            detail: None,
+        });
+    }
+
+    let notices: Vec<(String, String, i64, i64)> = sqlx::query_as(
+        "SELECT enviado_en, titulo, dispositivos, entregados FROM envios_notificaciones WHERE sesion_id = ? AND id_control_escolar IS NULL",
+    )
+    .bind(session.id)
+    .fetch_all(pool)
+    .await?;
+    for (at, title, devices, delivered) in notices {
+        events.push(TimelineEvent {
+            at,
+            event: "AVISO_GENERAL".to_string(),
+            actor: None,
+            detail: Some(format!(
+                "{} ({} de {} entregados)",
+                title, delivered, devices
+            )),
+        });
+    }
+
+    let rooms: Vec<(String, String, Option<String>, i64)> = sqlx::query_as(
+        r#"
+        SELECT r.reportado_en, r.salon, u.nombre_mostrado, r.alumnos
+        FROM reportes_aula r
+        LEFT JOIN usuarios u ON u.id_usuario = r.reportado_por
+        WHERE r.sesion_id = ?
+        "#,
+    )
+    .bind(session.id)
+    .fetch_all(pool)
+    .await?;
+    for (at, room, actor, students) in rooms {
+        events.push(TimelineEvent {
+            at,
+            event: "REPORTE_SALON".to_string(),
+            actor,
+            detail: Some(format!("{}: {} alumnos", room, students)),
+        });
+    }
+
+    let releases: Vec<(String, Option<String>, String, String)> = sqlx::query_as(
+        r#"
+        SELECT r.fecha_entrega, u.nombre_mostrado, e.nombres || ' ' || e.apellido_paterno, r.recibido_por
+        FROM entregas_estudiantes r
+        JOIN estudiantes e ON e.id_control_escolar = r.id_control_escolar
+        LEFT JOIN usuarios u ON u.id_usuario = r.entregado_por
+        WHERE r.sesion_id = ?
+        "#,
+    )
+    .bind(session.id)
+    .fetch_all(pool)
+    .await?;
+    for (at, actor, student, received_by) in releases {
+        events.push(TimelineEvent {
+            at,
+            event: "ENTREGA".to_string(),
+            actor,
+            detail: Some(format!("{} entregado a {}", student, received_by)),
+        });
+    }
+
+    events.sort_by_key(|event| parse_timestamp(&event.at));
+    Ok(events)
+}
+
+pub async fn after_action_report(
+    pool: &Pool<Sqlite>,
+    session: EmergencySession,
+) -> Result<AfterActionReport, sqlx::Error> {
+    let activated_by = user_name(pool, Some(session.started_by)).await?;
+    let deactivated_by = user_name(pool, session.closed_by).await?;
+
+    let groups = sqlx::query_as::<_, GroupAccount>(
+        r#"
+        SELECT
+            COALESCE(s.grupo, e.grupo) AS "group",
+            COUNT(*) AS total,
+            COALESCE(SUM(s.estado = 'LOCALIZADO'), 0) AS located,
+            COALESCE(SUM(s.estado = 'LESIONADO'), 0) AS injured,
+            COALESCE(SUM(s.estado = 'ENTREGADO'), 0) AS released,
+            COALESCE(SUM(s.estado = 'FALTANTE'), 0) AS missing
+        FROM estado_alumnos_emergencia s
+        JOIN estudiantes e ON e.id_control_escolar = s.id_control_escolar
+        WHERE s.sesion_id = ?
+        GROUP BY COALESCE(s.grupo, e.grupo)
+        ORDER BY COALESCE(s.grupo, e.grupo)
+        "#,
+    )
+    .bind(session.id)
+    .fetch_all(pool)
+    .await?;
+
+    let never_found = sqlx::query_as::<_, NeverFoundStudent>(
+        r#"
+        SELECT
+            s.id_control_escolar AS student_id,
+            e.nombres || ' ' || e.apellido_paterno AS name,
+            COALESCE(s.grupo, e.grupo) AS "group",
+            s.estado AS final_status
+        FROM estado_alumnos_emergencia s
+        JOIN estudiantes e ON e.id_control_escolar = s.id_control_escolar
+        WHERE s.sesion_id = ?1
+        AND (
+            s.estado = 'FALTANTE'
+            OR NOT EXISTS (
+                SELECT 1 FROM historial_consulta h
+                WHERE h.sesion_id = ?1 AND h.estudiante_consultado = s.id_control_escolar
+            )
+        )
+        ORDER BY s.estado = 'FALTANTE' DESC, COALESCE(s.grupo, e.grupo), e.apellido_paterno
+        "#,
+    )
+    .bind(session.id)
+    .fetch_all(pool)
+    .await?;
+
+    let deliveries = sqlx::query_as::<_, NotificationDelivery>(
+        r#"
+        SELECT id_control_escolar AS student_id, titulo AS title, dispositivos AS devices,
+            entregados AS delivered, fallidos AS failed, error, enviado_en AS sent_at
+        FROM envios_notificaciones
+        WHERE sesion_id = ?
+        ORDER BY id
+        "#,
+    )
+    .bind(session.id)
+    .fetch_all(pool)
+    .await?;
+    let notifications = NotificationSummary {
+        sends: deliveries.len() as i64,
+        devices: deliveries.iter().map(|delivery| delivery.devices).sum(),
+        delivered: deliveries.iter().map(|delivery| delivery.delivered).sum(),
+        failed: deliveries.iter().map(|delivery| delivery.failed).sum(),
+        deliveries,
+    };
+
+    let scans_by_staff = sqlx::query_as::<_, StaffScans>(
+        r#"
+        SELECT
+            h.consultado_por AS user_id,
+            COALESCE(u.nombre_mostrado, 'Usuario ' || h.consultado_por) AS name,
+            COUNT(*) AS scans,
+            MIN(h.fecha_consulta) AS first_scan,
+            MAX(h.fecha_consulta) AS last_scan
+        FROM historial_consulta h
+        LEFT JOIN usuarios u ON u.id_usuario = h.consultado_por
+        WHERE h.sesion_id = ?
+        GROUP BY h.consultado_por
+        ORDER BY scans DESC
+        "#,
+    )
+    .bind(session.id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(AfterActionReport {
+        timeline: timeline(pool, &session, &activated_by, &deactivated_by).await?,
+        duration_seconds: session
+            .closed_at
+            .as_deref()
+            .and_then(|end| seconds_between(&session.started_at, end)),
+        counts: session_counts(pool, session.id).await?,
+        people: people_counts(pool, session.id).await?,
+        activated_by,
+        deactivated_by,
+        groups,
+        never_found,
+        notifications,
+        scans_by_staff,
+        session,
+    })
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_row(csv: &mut String, fields: &[&str]) {
+    let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    csv.push_str(&row.join(","));
+    csv.push('\n');
+}
+
+// un CSV por secciones (separadas por una linea en blanco), facil de abrir en una hoja de calculo
+pub fn after_action_csv(report: &AfterActionReport) -> String {
+    let session = &report.session;
+    let mut csv = String::new();
+
+    csv_row(&mut csv, &["seccion", "campo", "valor"]);
+    for (field, value) in [
+        ("sesion", session.id.to_string()),
+        ("tipo", session.kind.clone()),
+        ("protocolo", session.protocol.clone()),
+        ("inicio", session.started_at.clone()),
+        (
+            "activada_por",
+            report.activated_by.clone().unwrap_or_default(),
+        ),
+        ("cierre", session.closed_at.clone().unwrap_or_default()),
+        (
+            "desactivada_por",
+            report.deactivated_by.clone().unwrap_or_default(),
+        ),
+        (
+            "duracion_seg",
+            report
+                .duration_seconds
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+        ),
+        ("alumnos", report.counts.total.to_string()),
+        ("alumnos_faltantes", report.counts.missing.to_string()),
+        ("personal_y_visitantes", report.people.total.to_string()),
+        (
+            "personal_y_visitantes_faltantes",
+            report.people.missing.to_string(),
+        ),
+    ] {
+        csv_row(&mut csv, &["resumen", field, &value]);
+    }
+
+    csv.push('\n');
+    csv_row(
+        &mut csv,
+        &["linea_de_tiempo", "fecha", "evento", "persona", "detalle"],
+    );
+    for event in &report.timeline {
+        csv_row(
+            &mut csv,
+            &[
+                "linea_de_tiempo",
+                &event.at,
+                &event.event,
+                event.actor.as_deref().unwrap_or(""),
+                event.detail.as_deref().unwrap_or(""),
+            ],
+        );
+    }
+
+    csv.push('\n');
+    csv_row(
+        &mut csv,
+        &[
+            "grupos",
+            "grupo",
+            "total",
+            "localizados",
+            "lesionados",
+            "entregados",
+            "faltantes",
+        ],
+    );
+    for group in &report.groups {
+        csv_row(
+            &mut csv,
+            &[
+                "grupos",
+                &group.group,
+                &group.total.to_string(),
+                &group.located.to_string(),
+                &group.injured.to_string(),
+                &group.released.to_string(),
+                &group.missing.to_string(),
+            ],
+        );
+    }
+
+    csv.push('\n');
+    csv_row(
+        &mut csv,
+        &[
+            "nunca_escaneados",
+            "alumno",
+            "nombre",
+            "grupo",
+            "estado_final",
+        ],
+    );
+    for student in &report.never_found {
+        csv_row(
+            &mut csv,
+            &[
+                "nunca_escaneados",
+                &student.student_id,
+                &student.name,
+                &student.group,
+                &student.final_status,
+            ],
+        );
+    }
+
+    csv.push('\n');
+    csv_row(
+        &mut csv,
+        &[
+            "notificaciones",
+            "fecha",
+            "alumno",
+            "titulo",
+            "dispositivos",
+            "entregados",
+            "fallidos",
+            "error",
+        ],
+    );
+    for delivery in &report.notifications.deliveries {
+        csv_row(
+            &mut csv,
+            &[
+                "notificaciones",
+                &delivery.sent_at,
+                delivery.student_id.as_deref().unwrap_or("TODOS"),
+                &delivery.title,
+                &delivery.devices.to_string(),
+                &delivery.delivered.to_string(),
+                &delivery.failed.to_string(),
+                delivery.error.as_deref().unwrap_or(""),
+            ],
+        );
+    }
+
+    csv.push('\n');
+    csv_row(
+        &mut csv,
+        &[
+            "escaneos_por_personal",
+            "usuario",
+            "nombre",
+            "escaneos",
+            "primero",
+            "ultimo",
+        ],
+    );
+    for staff in &report.scans_by_staff {
+        csv_row(
+            &mut csv,
+            &[
+                "escaneos_por_personal",
+                &staff.user_id.to_string(),
+                &staff.name,
+                &staff.scans.to_string(),
+                &staff.first_scan,
+                &staff.last_scan,
+            ],
+        );
+    }
+    csv
+}
+
+const MARGIN: f32 = 48.0;
+const LINE_HEIGHT: f32 = 12.0;
+// caracteres por renglon en Helvetica 9 dentro del margen
+const LINE_CHARS: usize = 100;
+
+// escribe renglones hacia abajo y agrega hojas cuando se acaba el espacio
+struct ReportWriter {
+    document: PdfDocument,
+    y: f32,
+}
+
+impl ReportWriter {
+    fn new() -> Self {
+        Self {
+            document: PdfDocument::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    // baja `height` puntos (cambia de hoja si no caben) y regresa la linea base
+    fn advance(&mut self, height: f32) -> f32 {
+        if self.y - height < MARGIN {
+            self.document.add_page();
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+        self.y -= height;
+        self.y
+    }
+
+    fn line(&mut self, text: &str) {
+        let text: String = if text.chars().count() > LINE_CHARS {
+            let mut short: String = text.chars().take(LINE_CHARS - 3).collect();
+            short.push_str("...");
+            short
+        } else {
+            text.to_string()
+        };
+        let y = self.advance(LINE_HEIGHT);
+        self.document.last_page().text(MARGIN, y, 9.0, false, &text);
+    }
+
+    fn heading(&mut self, text: &str) {
+        let y = self.advance(LINE_HEIGHT * 2.0);
+        self.document.last_page().text(MARGIN, y, 11.0, true, text);
+    }
+}
+
+fn format_seconds(seconds: Option<i64>) -> String {
+    match seconds {
+        Some(seconds) => format!("{} min {} s", seconds / 60, seconds % 60),
+        None => "-".to_string(),
+    }
+}
+
+pub fn after_action_pdf(report: &AfterActionReport) -> Vec<u8> {
+    let session = &report.session;
+    let mut writer = ReportWriter::new();
+
+    let y = writer.advance(16.0);
+    writer.document.last_page().text(
+        MARGIN,
+        y,
+        14.0,
+        true,
+        &format!("REPORTE POSTERIOR A LA EMERGENCIA - SESION {}", session.id),
+    );
+    writer.line(&format!(
+        "Tipo: {}   Protocolo: {}   Fase: {}",
+        session.kind, session.protocol, session.phase
+    ));
+    writer.line(&format!(
+        "Activada: {} por {}",
+        session.started_at,
+        report.activated_by.as_deref().unwrap_or("-")
+    ));
+    writer.line(&format!(
+        "Desactivada: {} por {}",
+        session.closed_at.as_deref().unwrap_or("en curso"),
+        report.deactivated_by.as_deref().unwrap_or("-")
+    ));
+    writer.line(&format!(
+        "Duracion: {}",
+        format_seconds(report.duration_seconds)
+    ));
+    writer.line(&format!(
+        "Alumnos: {} - localizados {}, lesionados {}, entregados {}, faltantes {}",
+        report.counts.total,
+        report.counts.located,
+        report.counts.injured,
+        report.counts.released,
+        report.counts.missing
+    ));
+    writer.line(&format!(
+        "Personal y visitantes: {} - localizados {}, lesionados {}, retirados {}, faltantes {}",
+        report.people.total,
+        report.people.located,
+        report.people.injured,
+        report.people.left,
+        report.people.missing
+    ));
+    if let Some(notes) = &session.notes {
+        writer.line(&format!("Notas: {}", notes));
+    }
+
+    writer.heading("Linea de tiempo");
+    for event in &report.timeline {
+        writer.line(&format!(
+            "{}  {}{}{}",
+            event.at,
+            event.event,
+            event
+                .actor
+                .as_deref()
+                .map(|actor| format!(" - {}", actor))
+                .unwrap_or_default(),
+            event
+                .detail
+                .as_deref()
+                .map(|detail| format!(" - {}", detail))
+                .unwrap_or_default()
+        ));
+    }
+
+    writer.heading("Conteo por grupo");
+    writer.line("Grupo        Total  Localizados  Lesionados  Entregados  Faltantes");
+    for group in &report.groups {
+        writer.line(&format!(
+            "{:<12} {:>5}  {:>11}  {:>10}  {:>10}  {:>9}",
+            group.group, group.total, group.located, group.injured, group.released, group.missing
+        ));
+    }
+
+    writer.heading("Alumnos nunca escaneados");
+    if report.never_found.is_empty() {
+        writer.line("Todos los alumnos fueron escaneados.");
+    }
+    for student in &report.never_found {
+        writer.line(&format!(
+            "{} - {} ({}) - estado final: {}",
+            student.student_id, student.name, student.group, student.final_status
+        ));
+    }
+
+    writer.heading("Notificaciones");
+    writer.line(&format!(
+        "{} envios a {} dispositivos: {} entregados, {} fallidos",
+        report.notifications.sends,
+        report.notifications.devices,
+        report.notifications.delivered,
+        report.notifications.failed
+    ));
+    for delivery in report
+        .notifications
+        .deliveries
+        .iter()
+        .filter(|delivery| delivery.failed > 0 || delivery.devices == 0)
+    {
+        writer.line(&format!(
+            "{} - {} - {}: {} de {} entregados{}",
+            delivery.sent_at,
+            delivery.student_id.as_deref().unwrap_or("TODOS"),
+            delivery.title,
+            delivery.delivered,
+            delivery.devices,
+            delivery
+                .error
+                .as_deref()
+                .map(|error| format!(" ({})", error))
+                .unwrap_or_default()
+        ));
+    }
+
+    writer.heading("Escaneos por personal");
+    for staff in &report.scans_by_staff {
+        writer.line(&format!(
+            "{} - {} escaneos ({} a {})",
+            staff.name, staff.scans, staff.first_scan, staff.last_scan
+        ));
+    }
+
+    writer.document.to_bytes()
+}
@@ -0,0 +1,59 @@
+use chrono::Local;
+use sqlx::{Pool, Sqlite};
+
+use crate::models::student::EnrollmentChange;
+
+// estatus de `e.id_control_escolar` en la fecha `date` (expresion SQL: `?` o p. ej. la de hoy)
+pub fn status_on_date(date: &str) -> String {
+    format!(
+        r#"COALESCE((
+            SELECT h.estatus FROM historial_inscripcion h
+            WHERE h.id_control_escolar = e.id_control_escolar AND h.desde <= {}
+            ORDER BY h.desde DESC, h.id DESC
+            LIMIT 1
+        ), 'ACTIVO')"#,
+        date
+    )
+}
+
+// condicion para filtrar alumnos (alias `e`) inscritos en la fecha del parametro
+pub fn active_on_date() -> String {
+    format!("{} = 'ACTIVO'", status_on_date("?"))
+}
+
+pub fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+pub async fn status_on(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+    date: &str,
+) -> Result<String, sqlx::Error> {
+    sqlx::query_scalar(&format!(
+        "SELECT {} FROM estudiantes e WHERE e.id_control_escolar = ?",
+        status_on_date("?")
+    ))
+    .bind(date)
+    .bind(student_id)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn enrollment_history(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+) -> Result<Vec<EnrollmentChange>, sqlx::Error> {
+    sqlx::query_as::<_, EnrollmentChange>(
+        r#"
+        SELECT h.*, u.nombre_mostrado AS recorded_by_name
+        FROM historial_inscripcion h
+        LEFT JOIN usuarios u ON u.id_usuario = h.registrado_por
+        WHERE h.id_control_escolar = ?
+        ORDER BY h.desde DESC, h.id DESC
+        "#,
+    )
+    .bind(student_id)
+    .fetch_all(pool)
+    .await
+}
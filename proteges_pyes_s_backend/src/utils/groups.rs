@@ -0,0 +1,41 @@
+use sqlx::{Pool, Sqlite};
+
+use crate::models::group::Group;
+use crate::utils::enrollment::status_on_date;
+
+// grupos con el nombre del tutor y sus alumnos inscritos hoy
+pub fn group_select() -> String {
+    format!(
+        r#"
+        SELECT g.*, u.nombre_mostrado AS tutor_name,
+            (SELECT count(*) FROM estudiantes e WHERE e.grupo = g.id_nomenclatura AND {} = 'ACTIVO') AS student_count
+        FROM grupos g
+        LEFT JOIN usuarios u ON u.id_usuario = g.id_tutor
+        "#,
+        status_on_date("date('now', 'localtime')")
+    )
+}
+
+pub async fn find_group(pool: &Pool<Sqlite>, id: &str) -> Result<Option<Group>, sqlx::Error> {
+    sqlx::query_as::<_, Group>(&format!("{} WHERE g.id_nomenclatura = ?", group_select()))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+// mensaje de error si no se pueden inscribir alumnos en el grupo (no existe o esta archivado)
+pub async fn group_unavailable(
+    pool: &Pool<Sqlite>,
+    id: &str,
+) -> Result<Option<&'static str>, sqlx::Error> {
+    let archived: Option<bool> =
+        sqlx::query_scalar("SELECT archivado FROM grupos WHERE id_nomenclatura = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(match archived {
+        None => Some("Grupo no encontrado"),
+        Some(true) => Some("El grupo está archivado"),
+        Some(false) => None,
+    })
+}
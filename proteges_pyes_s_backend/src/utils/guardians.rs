@@ -0,0 +1,96 @@
+use sqlx::{Pool, Sqlite};
+use tracing::warn;
+
+use crate::models::guardian::GuardianLink;
+
+// vinculos tutor-estudiante con los nombres de ambos
+pub const GUARDIAN_LINK_SELECT: &str = r#"
+    SELECT
+        t.id_tutor AS guardian_id,
+        t.nombre AS guardian_name,
+        t.telefono AS guardian_phone,
+        e.id_control_escolar AS student_id,
+        (e.nombres || ' ' || e.apellido_paterno) AS student_name,
+        e.grupo AS "group",
+        te.parentesco AS relationship,
+        te.es_principal AS "primary",
+        te.puede_recoger AS can_pick_up,
+        te.recibe_notificaciones AS receives_notifications,
+        te.entrega_restringida AS release_restricted,
+        te.restriccion_custodia AS custody_notes
+    FROM tutores_estudiantes te
+    JOIN tutores t ON t.id_tutor = te.id_tutor
+    JOIN estudiantes e ON e.id_control_escolar = te.id_control_escolar
+"#;
+
+pub async fn links_for_guardian(
+    pool: &Pool<Sqlite>,
+    guardian_id: i64,
+) -> Result<Vec<GuardianLink>, sqlx::Error> {
+    sqlx::query_as::<_, GuardianLink>(&format!(
+        "{} WHERE te.id_tutor = ? ORDER BY e.nombres",
+        GUARDIAN_LINK_SELECT
+    ))
+    .bind(guardian_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn links_for_student(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+) -> Result<Vec<GuardianLink>, sqlx::Error> {
+    sqlx::query_as::<_, GuardianLink>(&format!(
+        "{} WHERE te.id_control_escolar = ? ORDER BY te.es_principal DESC, t.nombre",
+        GUARDIAN_LINK_SELECT
+    ))
+    .bind(student_id)
+    .fetch_all(pool)
+    .await
+}
+
+// mantener ligados los telefonos capturados en la ficha del estudiante: si el telefono no
+// pertenece a un tutor se crea uno; nunca se desvincula (eso se hace desde /guardians)
+async fn link_guardian_phone(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+    phone: Option<&str>,
+    primary: bool,
+) -> Result<(), sqlx::Error> {
+    let Some(phone) = phone.map(str::trim).filter(|phone| !phone.is_empty()) else {
+        return Ok(());
+    };
+
+    sqlx::query("INSERT OR IGNORE INTO tutores (nombre, telefono) VALUES ('Tutor sin nombre', ?)")
+        .bind(phone)
+        .execute(pool)
+        .await?;
+
+    sqlx::query(
+        "INSERT OR IGNORE INTO tutores_estudiantes (id_tutor, id_control_escolar, es_principal) SELECT id_tutor, ?, ? FROM tutores WHERE telefono = ?",
+    )
+    .bind(student_id)
+    .bind(primary)
+    .bind(phone)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+// telefonos principal y secundario de la ficha; un error aqui no debe impedir guardar al alumno
+pub async fn sync_guardian_phones(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+    primary_phone: Option<&str>,
+    secondary_phone: Option<&str>,
+) {
+    for (phone, primary) in [(primary_phone, true), (secondary_phone, false)] {
+        if let Err(e) = link_guardian_phone(pool, student_id, phone, primary).await {
+            warn!(
+                "No se pudo vincular el tutor {:?} al estudiante {}: {}",
+                phone, student_id, e
+            );
+        }
+    }
+}
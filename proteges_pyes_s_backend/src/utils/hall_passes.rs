@@ -0,0 +1,117 @@
+use chrono::{DateTime, Local};
+use sqlx::{Pool, Sqlite};
+
+use crate::models::emergency::EmergencySession;
+use crate::models::hall_pass::HallPass;
+use crate::utils::emergency_metrics::parse_timestamp;
+
+// pases con el nombre del alumno y de quien lo emitio
+pub const HALL_PASS_SELECT: &str = r#"
+    SELECT p.*, e.nombres || ' ' || e.apellido_paterno AS student_name, e.grupo AS "group",
+        u.nombre_mostrado AS issued_by_name
+    FROM pases p
+    JOIN estudiantes e ON e.id_control_escolar = p.id_control_escolar
+    LEFT JOIN usuarios u ON u.id_usuario = p.emitido_por
+"#;
+
+// vencido: sigue abierto despues de su vigencia
+pub fn mark_overdue(pass: &mut HallPass, now: DateTime<Local>) {
+    pass.overdue = pass.closed_at.is_none()
+        && parse_timestamp(&pass.expires_at).is_some_and(|expires| expires < now);
+}
+
+pub async fn open_pass<'e, E>(
+    executor: E,
+    student_id: &str,
+) -> Result<Option<HallPass>, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = Sqlite>,
+{
+    let mut pass = sqlx::query_as::<_, HallPass>(&format!(
+        "{} WHERE p.id_control_escolar = ? AND p.cerrado_en IS NULL",
+        HALL_PASS_SELECT
+    ))
+    .bind(student_id)
+    .fetch_optional(executor)
+    .await?;
+    if let Some(pass) = &mut pass {
+        mark_overdue(pass, Local::now());
+    }
+    Ok(pass)
+}
+
+// cierra el pase abierto del alumno (si tiene); `closure` es REGRESO o SALIDA_PLANTEL
+pub async fn close_open_pass<'e, E>(
+    executor: E,
+    student_id: &str,
+    user_id: i64,
+    closure: &str,
+) -> Result<bool, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = Sqlite>,
+{
+    let result = sqlx::query(
+        "UPDATE pases SET cerrado_en = ?, cerrado_por = ?, cierre = ? WHERE id_control_escolar = ? AND cerrado_en IS NULL",
+    )
+    .bind(Local::now().to_rfc3339())
+    .bind(user_id)
+    .bind(closure)
+    .bind(student_id)
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+// pases que estaban abiertos durante la sesion (o ahora mismo, sin sesion): el ultimo lugar
+// conocido de esos alumnos. Se compara en Rust porque las fechas traen distinto desfase
+pub async fn passes_during_session(
+    pool: &Pool<Sqlite>,
+    session: Option<&EmergencySession>,
+) -> Result<Vec<HallPass>, sqlx::Error> {
+    let now = Local::now();
+    let Some(session) = session else {
+        let mut passes = sqlx::query_as::<_, HallPass>(&format!(
+            "{} WHERE p.cerrado_en IS NULL",
+            HALL_PASS_SELECT
+        ))
+        .fetch_all(pool)
+        .await?;
+        for pass in &mut passes {
+            mark_overdue(pass, now);
+        }
+        return Ok(passes);
+    };
+
+    let (Some(started), ended) = (
+        parse_timestamp(&session.started_at),
+        session.closed_at.as_deref().and_then(parse_timestamp),
+    ) else {
+        return Ok(Vec::new());
+    };
+    let ended = ended.unwrap_or_else(|| now.fixed_offset());
+
+    // los pases duran a lo mucho unas horas: basta con los de los dias de la sesion
+    let mut passes: Vec<HallPass> = sqlx::query_as::<_, HallPass>(&format!(
+        "{} WHERE substr(p.emitido_en, 1, 10) >= ? ORDER BY p.emitido_en",
+        HALL_PASS_SELECT
+    ))
+    .bind(
+        (started - chrono::Duration::days(1))
+            .format("%Y-%m-%d")
+            .to_string(),
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .filter(|pass| {
+        let issued = parse_timestamp(&pass.issued_at);
+        let closed = pass.closed_at.as_deref().and_then(parse_timestamp);
+        issued.is_some_and(|issued| issued <= ended)
+            && closed.is_none_or(|closed| closed >= started)
+    })
+    .collect();
+    for pass in &mut passes {
+        mark_overdue(pass, now);
+    }
+    Ok(passes)
+}
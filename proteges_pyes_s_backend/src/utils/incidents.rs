@@ -0,0 +1,81 @@
+use sqlx::{Pool, Sqlite};
+
+use crate::models::incident::{Incident, IncidentAction, IncidentDetail, IncidentStudent};
+
+// incidentes con el nombre de quien los reporto
+pub const INCIDENT_SELECT: &str = r#"
+    SELECT i.*, u.nombre_mostrado AS reported_by_name
+    FROM incidentes i
+    LEFT JOIN usuarios u ON u.id_usuario = i.reportado_por
+"#;
+
+pub async fn find_incident(pool: &Pool<Sqlite>, id: i64) -> Result<Option<Incident>, sqlx::Error> {
+    sqlx::query_as::<_, Incident>(&format!("{} WHERE i.id = ?", INCIDENT_SELECT))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn incident_students(
+    pool: &Pool<Sqlite>,
+    incident_id: i64,
+) -> Result<Vec<IncidentStudent>, sqlx::Error> {
+    sqlx::query_as::<_, IncidentStudent>(
+        r#"
+        SELECT
+            ie.id_control_escolar AS student_id,
+            (e.nombres || ' ' || e.apellido_paterno) AS name,
+            e.grupo AS "group",
+            ie.acuse_tutor AS acknowledged_by,
+            t.nombre AS acknowledged_by_name,
+            ie.acuse_en AS acknowledged_at
+        FROM incidentes_estudiantes ie
+        JOIN estudiantes e ON e.id_control_escolar = ie.id_control_escolar
+        LEFT JOIN tutores t ON t.id_tutor = ie.acuse_tutor
+        WHERE ie.id_incidente = ?
+        ORDER BY e.grupo, e.apellido_paterno
+        "#,
+    )
+    .bind(incident_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn incident_actions(
+    pool: &Pool<Sqlite>,
+    incident_id: i64,
+) -> Result<Vec<IncidentAction>, sqlx::Error> {
+    sqlx::query_as::<_, IncidentAction>(
+        r#"
+        SELECT a.*, u.nombre_mostrado AS recorded_by_name
+        FROM acciones_incidente a
+        LEFT JOIN usuarios u ON u.id_usuario = a.registrado_por
+        WHERE a.id_incidente = ?
+        ORDER BY a.registrado_en, a.id
+        "#,
+    )
+    .bind(incident_id)
+    .fetch_all(pool)
+    .await
+}
+
+// incidente completo: alumnos con su acuse, seguimiento y evidencias
+pub async fn incident_detail(
+    pool: &Pool<Sqlite>,
+    incident: Incident,
+) -> Result<IncidentDetail, sqlx::Error> {
+    let students = incident_students(pool, incident.id).await?;
+    let actions = incident_actions(pool, incident.id).await?;
+    let evidence_ids = sqlx::query_scalar(
+        "SELECT evidencia_id FROM incidentes_evidencias WHERE id_incidente = ? ORDER BY evidencia_id",
+    )
+    .bind(incident.id)
+    .fetch_all(pool)
+    .await?;
+    Ok(IncidentDetail {
+        incident,
+        students,
+        actions,
+        evidence_ids,
+    })
+}
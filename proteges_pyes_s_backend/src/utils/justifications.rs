@@ -0,0 +1,85 @@
+use chrono::Local;
+use sqlx::{Sqlite, SqliteConnection};
+
+use crate::models::justification::Justification;
+
+// justificante aprobado que cubre esa fecha del alumno (id, motivo)
+pub async fn approved_justification<'e, E>(
+    executor: E,
+    student_id: &str,
+    date: &str,
+) -> Result<Option<(i64, String)>, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = Sqlite>,
+{
+    sqlx::query_as(
+        "SELECT id, motivo FROM justificantes WHERE estudiante_id = ? AND ? BETWEEN fecha_justificacion AND fecha_fin AND estado = 'APROBADO' ORDER BY id DESC LIMIT 1",
+    )
+    .bind(student_id)
+    .bind(date)
+    .fetch_optional(executor)
+    .await
+}
+
+// marcar como justificadas las faltas y retardos del alumno en los dias del justificante
+// que no tenga ya otro justificante; regresa cuantos registros se actualizaron
+pub async fn apply_justification(
+    conn: &mut SqliteConnection,
+    justification: &Justification,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE asistencias
+        SET justificacion = ?, id_justificante = ?, fecha_modificacion = ?
+        WHERE id_control_escolar = ?
+        AND substr(fecha_asistencia, 1, 10) BETWEEN ? AND ?
+        AND (tipo_registro = 'FALTA' OR clasificacion = 'RETARDO')
+        AND id_justificante IS NULL
+        "#,
+    )
+    .bind(&justification.motivo)
+    .bind(justification.id)
+    .bind(Local::now().to_rfc3339())
+    .bind(&justification.estudiante_id)
+    .bind(&justification.fecha_justificacion)
+    .bind(
+        justification
+            .fecha_fin
+            .as_deref()
+            .unwrap_or(&justification.fecha_justificacion),
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+// deshacer lo anterior cuando se revoca la aprobacion; si otro justificante aprobado cubre
+// el dia, el registro queda a su nombre (el estado del revocado ya debe estar actualizado)
+pub async fn revert_justification(
+    conn: &mut SqliteConnection,
+    justification_id: i64,
+) -> Result<u64, sqlx::Error> {
+    let records: Vec<(i64, String, String)> = sqlx::query_as(
+        "SELECT id_asistencia, id_control_escolar, substr(fecha_asistencia, 1, 10) FROM asistencias WHERE id_justificante = ?",
+    )
+    .bind(justification_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let now = Local::now().to_rfc3339();
+    for (record_id, student_id, date) in &records {
+        let other = approved_justification(&mut *conn, student_id, date).await?;
+        sqlx::query(
+            "UPDATE asistencias SET justificacion = ?, id_justificante = ?, fecha_modificacion = ? WHERE id_asistencia = ?",
+        )
+        .bind(other.as_ref().map(|(_, reason)| reason.clone()))
+        .bind(other.as_ref().map(|(id, _)| *id))
+        .bind(&now)
+        .bind(record_id)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(records.len() as u64)
+}
@@ -0,0 +1,61 @@
+use sqlx::{Pool, Sqlite};
+
+use crate::models::medical::{InfirmaryVisit, MedicalItem};
+
+// visitas a la enfermeria que se muestran en el expediente
+pub const RECENT_VISITS: i64 = 20;
+
+// consulta base de visitas con los nombres del alumno y de quien lo atendio
+pub const VISIT_SELECT: &str = r#"
+    SELECT v.*, e.nombres || ' ' || e.apellido_paterno AS student_name, u.nombre_mostrado AS seen_by_name
+    FROM visitas_enfermeria v
+    JOIN estudiantes e ON e.id_control_escolar = v.id_control_escolar
+    LEFT JOIN usuarios u ON u.id_usuario = v.atendido_por
+"#;
+
+// antecedentes vigentes del alumno (los graves primero)
+pub async fn active_items(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+) -> Result<Vec<MedicalItem>, sqlx::Error> {
+    sqlx::query_as::<_, MedicalItem>(
+        "SELECT * FROM antecedentes_medicos WHERE id_control_escolar = ? AND activo ORDER BY tipo, severidad = 'GRAVE' DESC, nombre",
+    )
+    .bind(student_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn recent_visits(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+) -> Result<Vec<InfirmaryVisit>, sqlx::Error> {
+    sqlx::query_as::<_, InfirmaryVisit>(&format!(
+        "{} WHERE v.id_control_escolar = ? ORDER BY v.llegada DESC LIMIT ?",
+        VISIT_SELECT
+    ))
+    .bind(student_id)
+    .bind(RECENT_VISITS)
+    .fetch_all(pool)
+    .await
+}
+
+// lista legible para el resumen de emergencia: "Penicilina (GRAVE: anafilaxia)"
+pub fn describe_items(items: &[MedicalItem], kind: &str) -> Option<String> {
+    let described: Vec<String> = items
+        .iter()
+        .filter(|item| item.kind == kind)
+        .map(|item| {
+            let extra: Vec<&str> = [item.severity.as_deref(), item.detail.as_deref()]
+                .into_iter()
+                .flatten()
+                .collect();
+            if extra.is_empty() {
+                item.name.clone()
+            } else {
+                format!("{} ({})", item.name, extra.join(": "))
+            }
+        })
+        .collect();
+    (!described.is_empty()).then(|| described.join(", "))
+}
@@ -1 +1,30 @@
+pub mod absences;
+pub mod assembly;
+pub mod attendance_summary;
+pub mod audit;
+pub mod auth;
+pub mod calendar;
+pub mod cards;
+pub mod emergency;
+pub mod emergency_medical;
+pub mod emergency_metrics;
+pub mod emergency_report;
+pub mod enrollment;
+pub mod groups;
+pub mod guardians;
+pub mod hall_passes;
+pub mod incidents;
+pub mod justifications;
+pub mod medical;
 pub mod notifications;
+pub mod pdf;
+pub mod pickups;
+pub mod presence;
+pub mod raster;
+pub mod rollover;
+pub mod schedules;
+pub mod school_day;
+pub mod sms;
+pub mod students;
+pub mod tokens;
+pub mod uploads;
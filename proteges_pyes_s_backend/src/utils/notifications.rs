@@ -1,120 +1,247 @@
 use serde_json::json;
 use sqlx::{Pool, Row, Sqlite};
+use tracing::warn;
+
+use crate::utils::sms::{mask_phone, send_sms};
+
+// Routine gate records respect the guardian's preference; everything else always goes out.
+// Emergency notices (where to pick the student up) skip guardians with restricted release
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotificationKind {
+    GateRecord,
+    Important,
+    Emergency,
+}
 
 pub async fn send_push_notification(
     pool: &Pool<Sqlite>,
     student_id: &str,
+    kind: NotificationKind,
     title: &str,
     body: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // 1. Get tokens for student
-    let rows = sqlx::query("SELECT token FROM push_tokens WHERE student_id = ?")
-        .bind(student_id)
-        .fetch_all(pool)
-        .await?;
-
-    if rows.is_empty() {
-        return Ok(());
+    // 1. Get tokens of the student's guardians that accept push notices
+    let rows = sqlx::query(
+        r#"
+        SELECT DISTINCT p.token
+        FROM push_tokens p
+        JOIN tutores t ON t.id_tutor = p.tutor_id
+        JOIN tutores_estudiantes te ON te.id_tutor = p.tutor_id
+        WHERE te.id_control_escolar = ?
+        AND te.recibe_notificaciones
+        AND t.notificar_push
+        AND (? OR t.avisos_entrada_salida)
+        AND NOT (? AND te.entrega_restringida)
+        "#,
+    )
+    .bind(student_id)
+    .bind(kind != NotificationKind::GateRecord)
+    .bind(kind == NotificationKind::Emergency)
+    .fetch_all(pool)
+    .await?;
+
+    let messages: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| {
+            let token: String = row.get("token");
+            json!({
+                "to": token,
+                "sound": "default",
+                "title": title,
+                "body": body,
+                "data": { "student_id": student_id }
+            })
+        })
+        .collect();
+
+    // during an emergency every notice to guardians is kept for the after-action report
+    let session_id: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM sesiones_emergencia WHERE fase <> 'CERRADA' ORDER BY id DESC LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let delivery = send_to_expo(&messages).await;
+    if delivery.failed > 0 || delivery.error.is_some() {
+        println!(
+            "Error enviando notificaciones para estudiante {}: {:?}",
+            student_id, delivery.error
+        );
+    } else if !messages.is_empty() {
+        println!("Notificaciones enviadas para estudiante {}", student_id);
     }
-
-    let client = reqwest::Client::new();
-    let mut messages = Vec::new();
-
-    for row in rows {
-        let token: String = row.get("token");
-        // Check if token is valid expo token (starts with ExponentPushToken or similar)
-        // Expo simplified sending:
-        messages.push(json!({
-            "to": token,
-            "sound": "default",
-            "title": title,
-            "body": body,
-            "data": { "student_id": student_id }
-        }));
+    if session_id.is_some() {
+        record_delivery(pool, session_id, Some(student_id), title, &delivery).await?;
     }
 
-    if messages.is_empty() {
-        return Ok(());
-    }
+    send_sms_notices(pool, student_id, kind, title, body).await?;
 
-    // 2. Send to Expo
-    // Limit batch size if needed, but for prototype simpler is fine.
-    let res = client
-        .post("https://exp.host/--/api/v2/push/send")
-        .json(&messages)
-        .send()
-        .await?;
+    Ok(())
+}
 
-    if res.status().is_success() {
-        println!("Notificaciones enviadas para estudiante {}", student_id);
-    } else {
-        println!(
-            "Error enviando notificaciones para estudiante {}: {:?}",
-            student_id,
-            res.status()
-        );
+// the same notice by SMS to guardians who asked for it, with the same filters as push
+async fn send_sms_notices(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+    kind: NotificationKind,
+    title: &str,
+    body: &str,
+) -> Result<(), sqlx::Error> {
+    let phones: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT t.telefono
+        FROM tutores t
+        JOIN tutores_estudiantes te ON te.id_tutor = t.id_tutor
+        WHERE te.id_control_escolar = ?
+        AND te.recibe_notificaciones
+        AND t.notificar_sms
+        AND t.telefono IS NOT NULL
+        AND (? OR t.avisos_entrada_salida)
+        AND NOT (? AND te.entrega_restringida)
+        "#,
+    )
+    .bind(student_id)
+    .bind(kind != NotificationKind::GateRecord)
+    .bind(kind == NotificationKind::Emergency)
+    .fetch_all(pool)
+    .await?;
+
+    let message = format!("{}: {}", title, body);
+    for phone in phones {
+        if let Err(e) = send_sms(&phone, &message).await {
+            warn!("No se pudo enviar el SMS a {}: {}", mask_phone(&phone), e);
+        }
     }
-
     Ok(())
 }
 
 pub async fn broadcast_emergency_notification(
     pool: &Pool<Sqlite>,
-    active: bool,
+    session_id: i64,
+    title: &str,
+    body: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Get ALL tokens
-    let rows = sqlx::query("SELECT token FROM push_tokens")
-        .fetch_all(pool)
-        .await?;
+    // Every guardian device, except guardians whose only links have restricted release
+    let rows = sqlx::query(
+        r#"
+        SELECT p.token FROM push_tokens p
+        WHERE EXISTS (
+            SELECT 1 FROM tutores_estudiantes te
+            WHERE te.id_tutor = p.tutor_id AND NOT te.entrega_restringida
+        )
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let messages: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| {
+            let token: String = row.get("token");
+            json!({
+                "to": token,
+                "sound": "default",
+                "title": title,
+                "body": body,
+                "priority": "high",
+                "channelId": "emergency-alerts",
+            })
+        })
+        .collect();
 
-    if rows.is_empty() {
-        return Ok(());
+    // Send in one batch (Expo handles up to 100 per request, we assume prototype scale < 100)
+    let delivery = send_to_expo(&messages).await;
+    match &delivery.error {
+        None => println!(
+            "Notificaciones de emergencia enviadas a {} dispositivos",
+            messages.len()
+        ),
+        Some(e) => println!("Error enviando notificaciones de emergencia: {}", e),
     }
+    record_delivery(pool, Some(session_id), None, title, &delivery).await?;
+
+    Ok(())
+}
 
-    let client = reqwest::Client::new();
-    let mut messages = Vec::new();
+#[derive(Debug, Default)]
+pub struct Delivery {
+    pub devices: i64,
+    pub delivered: i64,
+    pub failed: i64,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+}
 
-    let title = if active {
-        "⚠️ EMERGENCIA ACTIVADA"
-    } else {
-        "✅ Emergencia Desactivada"
-    };
-    let body = if active {
-        "Se ha activado el protocolo de emergencia en la escuela via Proteges-PyEs. Por favor manténgase atento."
-    } else {
-        "El protocolo de emergencia ha sido desactivado. Todo vuelve a la normalidad."
+// enviar a Expo y contar los tickets: cada mensaje regresa {"status": "ok"} o
+// {"status": "error", "message": ...} en el mismo orden
+async fn send_to_expo(messages: &[serde_json::Value]) -> Delivery {
+    let mut delivery = Delivery {
+        devices: messages.len() as i64,
+        ..Default::default()
     };
-
-    for row in rows {
-        let token: String = row.get("token");
-        messages.push(json!({
-            "to": token,
-            "sound": "default",
-            "title": title,
-            "body": body,
-            "priority": "high",
-            "channelId": "emergency-alerts",
-        }));
+    if messages.is_empty() {
+        return delivery;
     }
 
-    // Send in one batch (Expo handles up to 100 per request, we assume prototype scale < 100)
-    let res = client
+    let response = reqwest::Client::new()
         .post("https://exp.host/--/api/v2/push/send")
-        .json(&messages)
+        .json(messages)
         .send()
-        .await?;
+        .await;
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            delivery.failed = delivery.devices;
+            delivery.error = Some(e.to_string());
+            return delivery;
+        }
+    };
 
-    if res.status().is_success() {
-        println!(
-            "Notificaciones de emergencia enviadas a {} dispositivos",
-            messages.len()
-        );
-    } else {
-        println!(
-            "Error enviando notificaciones de emergencia: {:?}",
-            res.status()
-        );
+    delivery.http_status = Some(response.status().as_u16());
+    if !response.status().is_success() {
+        delivery.failed = delivery.devices;
+        delivery.error = Some(format!("HTTP {}", response.status()));
+        return delivery;
     }
 
+    let tickets = response
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|body| body.get("data").and_then(|data| data.as_array()).cloned())
+        .unwrap_or_default();
+    delivery.delivered = tickets
+        .iter()
+        .filter(|ticket| ticket.get("status").and_then(|s| s.as_str()) == Some("ok"))
+        .count() as i64;
+    delivery.failed = delivery.devices - delivery.delivered;
+    delivery.error = tickets
+        .iter()
+        .find_map(|ticket| ticket.get("message").and_then(|m| m.as_str()))
+        .map(str::to_string);
+    delivery
+}
+
+async fn record_delivery(
+    pool: &Pool<Sqlite>,
+    session_id: Option<i64>,
+    student_id: Option<&str>,
+    title: &str,
+    delivery: &Delivery,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO envios_notificaciones (sesion_id, id_control_escolar, titulo, dispositivos, entregados, fallidos, estado_http, error, enviado_en) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(session_id)
+    .bind(student_id)
+    .bind(title)
+    .bind(delivery.devices)
+    .bind(delivery.delivered)
+    .bind(delivery.failed)
+    .bind(delivery.http_status.map(i64::from))
+    .bind(&delivery.error)
+    .bind(chrono::Local::now().to_rfc3339())
+    .execute(pool)
+    .await?;
     Ok(())
 }
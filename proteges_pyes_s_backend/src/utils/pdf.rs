@@ -0,0 +1,142 @@
+// generador minimo de PDF (tamaño carta) con texto Helvetica y rectangulos,
+// suficiente para credenciales y reportes imprimibles sin dependencias extra
+
+pub const PAGE_WIDTH: f32 = 612.0;
+pub const PAGE_HEIGHT: f32 = 792.0;
+
+#[derive(Default)]
+pub struct PdfPage {
+    content: Vec<u8>,
+}
+
+impl PdfPage {
+    // texto en coordenadas PDF (origen abajo a la izquierda)
+    pub fn text(&mut self, x: f32, y: f32, size: f32, bold: bool, text: &str) {
+        let font = if bold { "F2" } else { "F1" };
+        self.content
+            .extend_from_slice(format!("BT /{} {} Tf {} {} Td (", font, size, x, y).as_bytes());
+        self.content.extend_from_slice(&encode_text(text));
+        self.content.extend_from_slice(b") Tj ET\n");
+    }
+
+    // rectangulo relleno con un tono de gris (0.0 negro, 1.0 blanco)
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, gray: f32) {
+        self.content.extend_from_slice(
+            format!("{} g {} {} {} {} re f 0 g\n", gray, x, y, width, height).as_bytes(),
+        );
+    }
+
+    // solo el contorno del rectangulo
+    pub fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        self.content
+            .extend_from_slice(format!("0.5 w {} {} {} {} re S\n", x, y, width, height).as_bytes());
+    }
+}
+
+#[derive(Default)]
+pub struct PdfDocument {
+    pages: Vec<PdfPage>,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_page(&mut self) -> &mut PdfPage {
+        self.pages.push(PdfPage::default());
+        self.pages.last_mut().unwrap()
+    }
+
+    // hoja en la que se esta escribiendo (crea la primera si no hay)
+    pub fn last_page(&mut self) -> &mut PdfPage {
+        if self.pages.is_empty() {
+            return self.add_page();
+        }
+        self.pages.last_mut().unwrap()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // objetos fijos: 1 catalogo, 2 arbol de paginas, 3 y 4 fuentes;
+        // despues cada pagina ocupa dos objetos (pagina y contenido)
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        let kids: Vec<String> = (0..self.pages.len())
+            .map(|i| format!("{} 0 R", 5 + i * 2))
+            .collect();
+
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objects.push(
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                self.pages.len()
+            )
+            .into_bytes(),
+        );
+        objects.push(
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+        );
+        objects.push(
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+        );
+
+        for (i, page) in self.pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    PAGE_WIDTH,
+                    PAGE_HEIGHT,
+                    6 + i * 2
+                )
+                .into_bytes(),
+            );
+            let mut stream = format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+            stream.extend_from_slice(&page.content);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut out: Vec<u8> = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n", objects.len() + 1).as_bytes());
+        out.extend_from_slice(b"0000000000 65535 f \n");
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref_offset
+            )
+            .as_bytes(),
+        );
+        out
+    }
+}
+
+// convertir a WinAnsi (latin-1 cubre acentos y ñ) escapando los caracteres especiales
+fn encode_text(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                bytes.push(b'\\');
+                bytes.push(c as u8);
+            }
+            c if (c as u32) < 256 => bytes.push(c as u32 as u8),
+            _ => bytes.push(b'?'),
+        }
+    }
+    bytes
+}
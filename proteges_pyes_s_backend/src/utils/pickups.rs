@@ -0,0 +1,52 @@
+use chrono::Local;
+use sqlx::{Pool, Sqlite};
+
+use crate::models::pickup::Release;
+
+// entregas con el nombre del alumno y de quien lo entrego
+pub const RELEASE_SELECT: &str = r#"
+    SELECT
+        r.id_entrega AS id,
+        r.id_control_escolar AS student_id,
+        (e.nombres || ' ' || e.apellido_paterno) AS student_name,
+        r.id_tutor AS guardian_id,
+        r.id_persona AS authorized_person_id,
+        r.recibido_por AS received_by,
+        r.entregado_por AS released_by,
+        u.nombre_mostrado AS released_by_name,
+        r.identificacion_verificada AS id_verified,
+        r.durante_emergencia AS during_emergency,
+        r.notas AS notes,
+        r.fecha_entrega AS released_at
+    FROM entregas_estudiantes r
+    JOIN estudiantes e ON e.id_control_escolar = r.id_control_escolar
+    LEFT JOIN usuarios u ON u.id_usuario = r.entregado_por
+"#;
+
+// entrega del alumno en el dia de hoy, si ya salio
+pub async fn release_today(
+    pool: &Pool<Sqlite>,
+    student_id: &str,
+) -> Result<Option<Release>, sqlx::Error> {
+    sqlx::query_as::<_, Release>(&format!(
+        "{} WHERE r.id_control_escolar = ? AND substr(r.fecha_entrega, 1, 10) = ? ORDER BY r.fecha_entrega DESC LIMIT 1",
+        RELEASE_SELECT
+    ))
+    .bind(student_id)
+    .bind(Local::now().format("%Y-%m-%d").to_string())
+    .fetch_optional(pool)
+    .await
+}
+
+// alumnos entregados durante la sesion de emergencia (alumno, recibido por)
+pub async fn emergency_releases(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT id_control_escolar, recibido_por FROM entregas_estudiantes WHERE sesion_id = ? ORDER BY fecha_entrega",
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,157 @@
+use chrono::Local;
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+use crate::models::emergency::{EmergencyPerson, PeopleCounts};
+
+// personal con entrada abierta hoy y visitantes sin salida registrada hoy: quienes estan en el
+// plantel. Las entradas de dias anteriores sin salida se consideran olvidadas
+const ON_CAMPUS_PEOPLE: &str = r#"
+    SELECT 'PERSONAL' AS tipo, u.id_usuario AS id_persona, u.nombre_mostrado AS nombre
+    FROM presencia_personal p
+    JOIN usuarios u ON u.id_usuario = p.id_usuario
+    WHERE p.salida IS NULL AND substr(p.entrada, 1, 10) = ?1
+    GROUP BY u.id_usuario
+    UNION ALL
+    SELECT 'VISITANTE', id, nombre
+    FROM visitantes
+    WHERE salida IS NULL AND substr(entrada, 1, 10) = ?1
+"#;
+
+pub fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+// al iniciar la emergencia todos los que estan en el plantel empiezan como faltantes
+pub async fn add_people_to_session(
+    conn: &mut SqliteConnection,
+    session_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!(
+        "INSERT OR IGNORE INTO estado_personas_emergencia (sesion_id, tipo, id_persona, nombre, estado, actualizado_en) SELECT ?2, tipo, id_persona, nombre, 'FALTANTE', ?3 FROM ({})",
+        ON_CAMPUS_PEOPLE
+    ))
+    .bind(today())
+    .bind(session_id)
+    .bind(Local::now().to_rfc3339())
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// alta o cambio de estado de una persona en la sesion (quien llega durante la emergencia
+// se agrega con el estado indicado)
+#[allow(clippy::too_many_arguments)]
+pub async fn set_person_status<'e, E>(
+    executor: E,
+    session_id: i64,
+    kind: &str,
+    person_id: i64,
+    name: &str,
+    status: &str,
+    user_id: i64,
+    notes: Option<&str>,
+) -> Result<(), sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = Sqlite>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO estado_personas_emergencia (sesion_id, tipo, id_persona, nombre, estado, notas, actualizado_por, actualizado_en, localizado_en)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, CASE WHEN ?5 = 'FALTANTE' THEN NULL ELSE ?8 END)
+        ON CONFLICT(sesion_id, tipo, id_persona) DO UPDATE SET
+            estado = excluded.estado,
+            notas = COALESCE(excluded.notas, notas),
+            actualizado_por = excluded.actualizado_por,
+            actualizado_en = excluded.actualizado_en,
+            localizado_en = CASE
+                WHEN excluded.estado = 'FALTANTE' THEN NULL
+                ELSE COALESCE(localizado_en, excluded.actualizado_en)
+            END
+        "#,
+    )
+    .bind(session_id)
+    .bind(kind)
+    .bind(person_id)
+    .bind(name)
+    .bind(status)
+    .bind(notes)
+    .bind(user_id)
+    .bind(Local::now().to_rfc3339())
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+// quien escanea alumnos esta en el plantel: si estaba como faltante queda localizado
+pub async fn mark_staff_located(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+    user_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE estado_personas_emergencia SET estado = 'LOCALIZADO', actualizado_por = ?1, actualizado_en = ?2, localizado_en = ?2 WHERE sesion_id = ?3 AND tipo = 'PERSONAL' AND id_persona = ?1 AND estado = 'FALTANTE'",
+    )
+    .bind(user_id)
+    .bind(Local::now().to_rfc3339())
+    .bind(session_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn session_people(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+) -> Result<Vec<EmergencyPerson>, sqlx::Error> {
+    sqlx::query_as::<_, EmergencyPerson>(
+        "SELECT * FROM estado_personas_emergencia WHERE sesion_id = ? ORDER BY estado = 'FALTANTE' DESC, tipo, nombre",
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn person_status(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+    kind: &str,
+    person_id: i64,
+) -> Result<Option<EmergencyPerson>, sqlx::Error> {
+    sqlx::query_as::<_, EmergencyPerson>(
+        "SELECT * FROM estado_personas_emergencia WHERE sesion_id = ? AND tipo = ? AND id_persona = ?",
+    )
+    .bind(session_id)
+    .bind(kind)
+    .bind(person_id)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn people_counts(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+) -> Result<PeopleCounts, sqlx::Error> {
+    let (total, located, injured, left, missing): (i64, i64, i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            COUNT(*),
+            COALESCE(SUM(estado = 'LOCALIZADO'), 0),
+            COALESCE(SUM(estado = 'LESIONADO'), 0),
+            COALESCE(SUM(estado = 'RETIRADO'), 0),
+            COALESCE(SUM(estado = 'FALTANTE'), 0)
+        FROM estado_personas_emergencia
+        WHERE sesion_id = ?
+        "#,
+    )
+    .bind(session_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(PeopleCounts {
+        total,
+        located,
+        injured,
+        left,
+        missing,
+    })
+}
@@ -0,0 +1,161 @@
+// lienzo PNG en escala de grises con las mismas primitivas que PdfPage
+// (puntos, origen abajo a la izquierda) y una fuente de mapa de bits 5x7
+
+// filas de cada glifo, 5 bits por fila (el bit 4 es la columna izquierda)
+fn glyph(c: char) -> [u8; 7] {
+    match c {
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'Ñ' => [0x0E, 0x00, 0x11, 0x19, 0x15, 0x13, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        ' ' => [0x00; 7],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
+
+// la fuente solo tiene mayusculas; los acentos se pierden salvo la Ñ
+fn normalize(c: char) -> char {
+    match c {
+        'á' | 'Á' | 'à' | 'À' => 'A',
+        'é' | 'É' | 'è' | 'È' => 'E',
+        'í' | 'Í' | 'ì' | 'Ì' => 'I',
+        'ó' | 'Ó' | 'ò' | 'Ò' => 'O',
+        'ú' | 'Ú' | 'ù' | 'Ù' | 'ü' | 'Ü' => 'U',
+        'ñ' => 'Ñ',
+        _ => c.to_ascii_uppercase(),
+    }
+}
+
+pub struct Raster {
+    width: usize,
+    height: usize,
+    // pixeles por punto
+    scale: f32,
+    pixels: Vec<u8>,
+}
+
+impl Raster {
+    // lienzo blanco de `width` x `height` puntos
+    pub fn new(width: f32, height: f32, scale: f32) -> Self {
+        let width = (width * scale).round() as usize;
+        let height = (height * scale).round() as usize;
+        Self {
+            width,
+            height,
+            scale,
+            pixels: vec![255; width * height],
+        }
+    }
+
+    // rectangulo en pixeles (origen arriba a la izquierda), recortado al lienzo
+    fn fill_pixels(&mut self, x0: i64, y0: i64, x1: i64, y1: i64, value: u8) {
+        let x0 = x0.clamp(0, self.width as i64) as usize;
+        let x1 = x1.clamp(0, self.width as i64) as usize;
+        let y0 = y0.clamp(0, self.height as i64) as usize;
+        let y1 = y1.clamp(0, self.height as i64) as usize;
+        for y in y0..y1 {
+            self.pixels[y * self.width + x0..y * self.width + x1.max(x0)].fill(value);
+        }
+    }
+
+    fn px(&self, points: f32) -> i64 {
+        (points * self.scale).round() as i64
+    }
+
+    // convierte la y en puntos (desde abajo) a la fila de pixeles
+    fn row(&self, y: f32) -> i64 {
+        self.height as i64 - self.px(y)
+    }
+
+    // texto con la linea base en `y`; la altura de mayusculas es ~0.7 del tamaño
+    pub fn text(&mut self, x: f32, y: f32, size: f32, bold: bool, text: &str) {
+        let dot = ((size * 0.7 * self.scale) / 7.0).round().max(1.0) as i64;
+        let mut left = self.px(x);
+        let top = self.row(y) - 7 * dot;
+        for c in text.chars().map(normalize) {
+            for (r, bits) in glyph(c).iter().enumerate() {
+                for col in 0..5 {
+                    if bits & (0x10 >> col) == 0 {
+                        continue;
+                    }
+                    let gx = left + col * dot;
+                    let gy = top + r as i64 * dot;
+                    let extra = if bold { dot.max(2) / 2 } else { 0 };
+                    self.fill_pixels(gx, gy, gx + dot + extra, gy + dot, 0);
+                }
+            }
+            left += 6 * dot;
+        }
+    }
+
+    // rectangulo relleno con un tono de gris (0.0 negro, 1.0 blanco)
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, gray: f32) {
+        let value = (gray.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let (x0, x1) = (self.px(x), self.px(x + width));
+        let (y0, y1) = (self.row(y + height), self.row(y));
+        self.fill_pixels(x0, y0, x1, y1, value);
+    }
+
+    // solo el contorno del rectangulo
+    pub fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        let line = self.px(0.5).max(1);
+        let (x0, x1) = (self.px(x), self.px(x + width));
+        let (y0, y1) = (self.row(y + height), self.row(y));
+        self.fill_pixels(x0, y0, x1, y0 + line, 0);
+        self.fill_pixels(x0, y1 - line, x1, y1, 0);
+        self.fill_pixels(x0, y0, x0 + line, y1, 0);
+        self.fill_pixels(x1 - line, y0, x1, y1, 0);
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut out = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut out, self.width as u32, self.height as u32);
+            encoder.set_color(png::ColorType::Grayscale);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&self.pixels)?;
+        }
+        Ok(out)
+    }
+}
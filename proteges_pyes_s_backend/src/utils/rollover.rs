@@ -0,0 +1,254 @@
+use chrono::Local;
+use sqlx::{Pool, Sqlite};
+use std::collections::HashSet;
+
+use crate::constants;
+use crate::models::calendar::Term;
+use crate::models::group::{
+    RolloverGroup, RolloverPlan, RolloverRequest, RolloverSkippedGroup, RolloverStudent,
+};
+use crate::utils::enrollment::{active_on_date, today};
+
+// grupo siguiente por nomenclatura: 5APM -> 6APM; None en el ultimo semestre
+fn next_group_id(id: &str, semester: i64) -> Option<String> {
+    let rest = id.strip_prefix(&semester.to_string())?;
+    Some(format!("{}{}", semester + 1, rest))
+}
+
+pub async fn rollover_done(pool: &Pool<Sqlite>, term_id: i64) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM promociones WHERE id_periodo = ?)")
+        .bind(term_id)
+        .fetch_one(pool)
+        .await
+}
+
+// que pasaria con cada grupo y alumno inscrito hoy; no escribe nada
+pub async fn plan_rollover(
+    pool: &Pool<Sqlite>,
+    payload: &RolloverRequest,
+    term: Term,
+) -> Result<RolloverPlan, sqlx::Error> {
+    let groups: Vec<(String, Option<i64>)> = sqlx::query_as(
+        "SELECT id_nomenclatura, semestre FROM grupos WHERE NOT archivado ORDER BY semestre DESC, id_nomenclatura",
+    )
+    .fetch_all(pool)
+    .await?;
+    let existing: HashSet<String> = sqlx::query_scalar("SELECT id_nomenclatura FROM grupos")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+    let students: Vec<(String, String, String)> = sqlx::query_as(&format!(
+        r#"
+        SELECT e.id_control_escolar, e.nombres || ' ' || e.apellido_paterno, e.grupo
+        FROM estudiantes e
+        WHERE {}
+        ORDER BY e.grupo, e.apellido_paterno, e.nombres
+        "#,
+        active_on_date()
+    ))
+    .bind(today())
+    .fetch_all(pool)
+    .await?;
+
+    let repeaters: HashSet<&str> = payload.repeaters.iter().map(String::as_str).collect();
+    let graduates: HashSet<&str> = payload.graduates.iter().map(String::as_str).collect();
+
+    let mut plan = RolloverPlan {
+        dry_run: payload.dry_run,
+        term,
+        groups: Vec::new(),
+        skipped_groups: Vec::new(),
+        students: Vec::new(),
+    };
+
+    for (group, semester) in &groups {
+        let target = match (payload.targets.get(group), semester) {
+            (Some(target), _) => Ok(Some(target.trim().to_uppercase())),
+            (None, Some(semester)) if *semester >= constants::MAX_SEMESTER => Ok(None),
+            (None, Some(semester)) => next_group_id(group, *semester).map(Some).ok_or(
+                "La nomenclatura no empieza con el semestre; indica el grupo destino en targets",
+            ),
+            (None, None) => Err("El grupo no tiene semestre; indica el grupo destino en targets"),
+        };
+        let group_students = students.iter().filter(|(_, _, g)| g == group);
+
+        let target = match target {
+            Ok(Some(target)) if &target == group => Err("El grupo destino es el mismo grupo"),
+            other => other,
+        };
+        let target = match target {
+            Ok(target) => target,
+            Err(reason) => {
+                // sus alumnos se quedan donde estan, salvo los que egresan explicitamente
+                plan.skipped_groups.push(RolloverSkippedGroup {
+                    group: group.clone(),
+                    reason: reason.to_string(),
+                });
+                for (student_id, name, _) in
+                    group_students.filter(|(id, _, _)| graduates.contains(id.as_str()))
+                {
+                    plan.students.push(RolloverStudent {
+                        student_id: student_id.clone(),
+                        name: name.clone(),
+                        from_group: group.clone(),
+                        to_group: None,
+                        result: "EGRESADO".to_string(),
+                    });
+                }
+                continue;
+            }
+        };
+
+        let mut entry = RolloverGroup {
+            from: group.clone(),
+            created: target
+                .as_ref()
+                .is_some_and(|target| !existing.contains(target)),
+            to: target.clone(),
+            promoted: 0,
+            repeaters: 0,
+            graduates: 0,
+            archived: false,
+        };
+        for (student_id, name, _) in group_students {
+            let (to_group, result) = if graduates.contains(student_id.as_str()) {
+                entry.graduates += 1;
+                (None, "EGRESADO")
+            } else if repeaters.contains(student_id.as_str()) {
+                entry.repeaters += 1;
+                (Some(group.clone()), "REPITE")
+            } else if let Some(target) = &target {
+                entry.promoted += 1;
+                (Some(target.clone()), "PROMOVIDO")
+            } else {
+                entry.graduates += 1;
+                (None, "EGRESADO")
+            };
+            plan.students.push(RolloverStudent {
+                student_id: student_id.clone(),
+                name: name.clone(),
+                from_group: group.clone(),
+                to_group,
+                result: result.to_string(),
+            });
+        }
+        // sin alumnos que promover no se crea ni se reactiva el grupo siguiente
+        if entry.promoted == 0 {
+            entry.to = None;
+            entry.created = false;
+        }
+        plan.groups.push(entry);
+    }
+
+    // se archivan los grupos que se quedan sin alumnos y no reciben a otro grupo
+    let targets: HashSet<String> = plan.groups.iter().filter_map(|g| g.to.clone()).collect();
+    for entry in plan.groups.iter_mut() {
+        entry.archived = entry.repeaters == 0 && !targets.contains(&entry.from);
+    }
+
+    Ok(plan)
+}
+
+// aplica el plan en una sola transaccion; falla si el periodo ya tuvo promocion
+pub async fn apply_rollover(
+    pool: &Pool<Sqlite>,
+    plan: &RolloverPlan,
+    user_id: i64,
+) -> Result<i64, sqlx::Error> {
+    let now = Local::now().to_rfc3339();
+    let mut tx = pool.begin().await?;
+
+    let rollover_id = sqlx::query(
+        "INSERT INTO promociones (id_periodo, ejecutado_por, ejecutado_en) VALUES (?, ?, ?)",
+    )
+    .bind(plan.term.id)
+    .bind(user_id)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?
+    .last_insert_rowid();
+
+    let mut created = HashSet::new();
+    for entry in &plan.groups {
+        let Some(target) = &entry.to else {
+            continue;
+        };
+        if entry.created {
+            if !created.insert(target.clone()) {
+                continue; // dos grupos que se fusionan en uno nuevo
+            }
+            // el grupo de origen es la plantilla; el tutor se asigna en cada periodo
+            sqlx::query(
+                r#"
+                INSERT INTO grupos (id_nomenclatura, especialidad, descripcion, turno, punto_reunion, semestre, grado)
+                SELECT ?, especialidad, descripcion, turno, punto_reunion, semestre + 1, (semestre + 2) / 2
+                FROM grupos WHERE id_nomenclatura = ?
+                "#,
+            )
+            .bind(target)
+            .bind(&entry.from)
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            // reutilizado de un ciclo anterior
+            sqlx::query(
+                "UPDATE grupos SET archivado = FALSE, archivado_en = NULL WHERE id_nomenclatura = ?",
+            )
+            .bind(target)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    let reason = format!("Promoción {}", plan.term.name);
+    for student in &plan.students {
+        match student.result.as_str() {
+            "PROMOVIDO" => {
+                sqlx::query("UPDATE estudiantes SET grupo = ? WHERE id_control_escolar = ?")
+                    .bind(&student.to_group)
+                    .bind(&student.student_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            "EGRESADO" => {
+                // conserva su ultimo grupo para el historial
+                sqlx::query(
+                    "INSERT INTO historial_inscripcion (id_control_escolar, estatus, desde, motivo, registrado_por, registrado_en) VALUES (?, 'EGRESADO', ?, ?, ?, ?)",
+                )
+                .bind(&student.student_id)
+                .bind(&plan.term.start_date)
+                .bind(&reason)
+                .bind(user_id)
+                .bind(&now)
+                .execute(&mut *tx)
+                .await?;
+            }
+            _ => {}
+        }
+
+        sqlx::query(
+            "INSERT INTO promociones_alumnos (id_promocion, id_control_escolar, grupo_anterior, grupo_nuevo, resultado) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(rollover_id)
+        .bind(&student.student_id)
+        .bind(&student.from_group)
+        .bind(&student.to_group)
+        .bind(&student.result)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for entry in plan.groups.iter().filter(|entry| entry.archived) {
+        sqlx::query(
+            "UPDATE grupos SET archivado = TRUE, archivado_en = ? WHERE id_nomenclatura = ?",
+        )
+        .bind(&now)
+        .bind(&entry.from)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(rollover_id)
+}
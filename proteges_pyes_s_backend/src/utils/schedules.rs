@@ -0,0 +1,27 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime};
+use sqlx::{Pool, Sqlite};
+
+use crate::models::schedule::Schedule;
+
+// validar hora en formato HH:MM
+pub fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+// periodo de clase del grupo en curso al momento indicado
+pub async fn current_schedule_for_group(
+    pool: &Pool<Sqlite>,
+    group: &str,
+    at: DateTime<Local>,
+) -> Result<Option<Schedule>, sqlx::Error> {
+    let time = at.format("%H:%M").to_string();
+    sqlx::query_as::<_, Schedule>(
+        "SELECT * FROM horarios WHERE grupo = ? AND dia_semana = ? AND hora_inicio <= ? AND hora_fin > ? LIMIT 1",
+    )
+    .bind(group)
+    .bind(at.weekday().number_from_monday() as i64)
+    .bind(&time)
+    .bind(&time)
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,51 @@
+use chrono::{Duration, NaiveTime};
+use sqlx::{Pool, Sqlite};
+
+use crate::models::attendance::SchoolDayRule;
+use crate::utils::schedules::parse_time;
+
+// regla de la jornada para el turno del grupo, o la GENERAL si no tiene
+pub async fn rule_for_group(
+    pool: &Pool<Sqlite>,
+    group: &str,
+) -> Result<Option<SchoolDayRule>, sqlx::Error> {
+    sqlx::query_as::<_, SchoolDayRule>(
+        r#"
+        SELECT r.* FROM reglas_jornada r
+        WHERE r.turno = COALESCE((SELECT turno FROM grupos WHERE id_nomenclatura = ?), 'GENERAL')
+        OR r.turno = 'GENERAL'
+        ORDER BY r.turno = 'GENERAL'
+        LIMIT 1
+        "#,
+    )
+    .bind(group)
+    .fetch_optional(pool)
+    .await
+}
+
+// clasificar un registro del acceso; regresa la clasificacion y los minutos de diferencia
+pub fn classify(record_type: &str, at: NaiveTime, rule: &SchoolDayRule) -> (&'static str, i64) {
+    match record_type {
+        "ENTRADA" => {
+            let Some(entry) = parse_time(&rule.entry_time) else {
+                return ("A_TIEMPO", 0);
+            };
+            let limit = entry + Duration::minutes(rule.grace_minutes);
+            if at > limit {
+                ("RETARDO", (at - entry).num_minutes())
+            } else {
+                ("A_TIEMPO", 0)
+            }
+        }
+        _ => {
+            let Some(dismissal) = parse_time(&rule.dismissal_time) else {
+                return ("A_TIEMPO", 0);
+            };
+            if at < dismissal {
+                ("SALIDA_ANTICIPADA", (dismissal - at).num_minutes())
+            } else {
+                ("A_TIEMPO", 0)
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use tracing::{info, warn};
+
+// envio de SMS: si PROTEGES_SMS_WEBHOOK esta configurado se hace POST {to, message} a esa
+// url (pasarela del proveedor); sin proveedor solo queda en el log
+pub async fn send_sms(phone: &str, message: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let Ok(webhook) = std::env::var("PROTEGES_SMS_WEBHOOK") else {
+        warn!(
+            "Sin proveedor de SMS configurado, mensaje a {} no enviado",
+            mask_phone(phone)
+        );
+        // en desarrollo el mensaje se muestra para poder probar el flujo
+        if cfg!(debug_assertions) {
+            info!("SMS a {}: {}", phone, message);
+        }
+        return Ok(());
+    };
+
+    let res = reqwest::Client::new()
+        .post(webhook)
+        .json(&serde_json::json!({ "to": phone, "message": message }))
+        .send()
+        .await?;
+
+    if !res.status().is_success() {
+        return Err(format!("El proveedor de SMS respondió {}", res.status()).into());
+    }
+    Ok(())
+}
+
+// solo los ultimos 4 digitos en los logs
+pub fn mask_phone(phone: &str) -> String {
+    let digits: Vec<char> = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    let visible: String = digits[digits.len().saturating_sub(4)..].iter().collect();
+    format!("***{}", visible)
+}
+
+// los ultimos 10 digitos: permite comparar "+52 645 765 4321" con "6457654321"
+pub fn normalize_phone(phone: &str) -> String {
+    let digits: Vec<char> = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    digits[digits.len().saturating_sub(10)..].iter().collect()
+}
@@ -0,0 +1,20 @@
+use sqlx::{Pool, Sqlite};
+
+use crate::utils::tokens::parse_card_token;
+
+// resolver el id_control_escolar a partir de lo que leyo el lector:
+// codigo QR de la credencial, id_control_escolar o UID de la tarjeta RFID
+pub async fn resolve_student_code(
+    pool: &Pool<Sqlite>,
+    code: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    let code = parse_card_token(code).unwrap_or_else(|| code.trim().to_string());
+
+    sqlx::query_scalar(
+        "SELECT id_control_escolar FROM estudiantes WHERE id_control_escolar = ? OR card_uid = ?",
+    )
+    .bind(&code)
+    .bind(&code)
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,84 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::OnceLock;
+use tracing::warn;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// prefijo de los codigos QR impresos en las credenciales
+const CARD_PREFIX: &str = "PYES";
+
+// llave publica en el repositorio: con ella cualquiera puede falsificar credenciales y enlaces
+const DEV_SECRET: &str = "proteges-pyes-s-dev-secret";
+
+fn configured_secret() -> Option<String> {
+    std::env::var("PROTEGES_SECRET")
+        .ok()
+        .filter(|secret| !secret.trim().is_empty())
+}
+
+// la llave se toma de PROTEGES_SECRET; la de desarrollo solo se acepta en builds de debug
+fn secret() -> &'static [u8] {
+    static SECRET: OnceLock<Vec<u8>> = OnceLock::new();
+    SECRET.get_or_init(|| {
+        configured_secret()
+            .unwrap_or_else(|| DEV_SECRET.to_string())
+            .into_bytes()
+    })
+}
+
+// se llama al arrancar: en release el servidor no inicia sin PROTEGES_SECRET
+pub fn check_secret() -> Result<(), String> {
+    if configured_secret().is_some() {
+        return Ok(());
+    }
+    if cfg!(debug_assertions) {
+        warn!(
+            "PROTEGES_SECRET no esta configurado: se usa la llave de desarrollo y cualquiera puede falsificar credenciales, enlaces y sesiones"
+        );
+        Ok(())
+    } else {
+        Err("Configura PROTEGES_SECRET antes de iniciar el servidor".to_string())
+    }
+}
+
+// firma HMAC-SHA256 en hexadecimal
+pub fn sign(message: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret()).expect("HMAC acepta llaves de cualquier tamaño");
+    mac.update(message.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+// codigo para el QR de la credencial: PYES.<id_control_escolar>.<firma>
+pub fn card_token(student_id: &str) -> String {
+    let payload = format!("{}.{}", CARD_PREFIX, student_id);
+    // 16 caracteres de firma son suficientes y mantienen el QR pequeño
+    let signature = sign(&payload);
+    format!("{}.{}", payload, &signature[..16])
+}
+
+// regresa el id_control_escolar si el codigo es una credencial valida
+pub fn parse_card_token(token: &str) -> Option<String> {
+    let mut parts = token.trim().splitn(3, '.');
+    let (prefix, student_id, signature) = (parts.next()?, parts.next()?, parts.next()?);
+    if prefix != CARD_PREFIX || signature.len() != 16 {
+        return None;
+    }
+    let expected = sign(&format!("{}.{}", CARD_PREFIX, student_id));
+    constant_time_eq(&expected[..16], signature).then(|| student_id.to_string())
+}
+
+// valida una firma completa generada con `sign`
+pub fn verify(message: &str, signature: &str) -> bool {
+    constant_time_eq(&sign(message), signature)
+}
+
+// comparar sin cortocircuito para no filtrar cuantos caracteres coinciden
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
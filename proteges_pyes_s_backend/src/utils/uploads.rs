@@ -0,0 +1,240 @@
+use axum::{extract::multipart::Field, http::StatusCode, Json};
+use sha2::{Digest, Sha256};
+use sqlx::Sqlite;
+use std::path::PathBuf;
+use tokio::{fs, io::AsyncWriteExt};
+
+use crate::models::evidence::EvidenceLink;
+use crate::utils::tokens::{sign, verify};
+
+// carpeta donde se guardan las evidencias; solo el servidor decide los nombres
+pub const UPLOAD_DIR: &str = "uploads";
+// tamaño maximo por archivo (5 MB)
+pub const MAX_UPLOAD_BYTES: usize = 5 * 1024 * 1024;
+
+// tipos permitidos: (firma, mime, extension)
+const ALLOWED_TYPES: [(&[u8], &str, &str); 3] = [
+    (&[0xFF, 0xD8, 0xFF], "image/jpeg", "jpg"),
+    (
+        &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A],
+        "image/png",
+        "png",
+    ),
+    (b"%PDF-", "application/pdf", "pdf"),
+];
+// bytes necesarios para reconocer cualquiera de las firmas
+const SNIFF_LEN: usize = 8;
+// vigencia de los enlaces de descarga
+const LINK_TTL_SECS: i64 = 10 * 60;
+
+#[derive(Debug)]
+pub enum UploadError {
+    Empty,
+    TooLarge,
+    UnsupportedType,
+    Read(String),
+    Io(std::io::Error),
+}
+
+impl From<std::io::Error> for UploadError {
+    fn from(e: std::io::Error) -> Self {
+        UploadError::Io(e)
+    }
+}
+
+impl From<UploadError> for (StatusCode, Json<serde_json::Value>) {
+    fn from(e: UploadError) -> Self {
+        let (status, message) = match e {
+            UploadError::Empty => (StatusCode::BAD_REQUEST, "El archivo está vacío".to_string()),
+            UploadError::TooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "El archivo excede el máximo de {} MB",
+                    MAX_UPLOAD_BYTES / (1024 * 1024)
+                ),
+            ),
+            UploadError::UnsupportedType => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "Solo se aceptan imágenes JPEG, PNG o documentos PDF".to_string(),
+            ),
+            UploadError::Read(e) => (
+                StatusCode::BAD_REQUEST,
+                format!("Error al leer el archivo: {}", e),
+            ),
+            UploadError::Io(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Error al guardar el archivo: {}", e),
+            ),
+        };
+        (status, Json(serde_json::json!({ "error": message })))
+    }
+}
+
+#[derive(Debug)]
+pub struct StoredUpload {
+    pub file_name: String, // nombre generado dentro de UPLOAD_DIR
+    pub original_name: Option<String>,
+    pub mime: &'static str,
+    pub size: i64,
+    pub sha256: String,
+}
+
+impl StoredUpload {
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(UPLOAD_DIR).join(&self.file_name)
+    }
+
+    // borrar el archivo si al final no se registro (p. ej. faltaron campos)
+    pub async fn discard(&self) {
+        let _ = fs::remove_file(self.path()).await;
+    }
+
+    // registrar el archivo en la tabla de evidencias; regresa su id
+    pub async fn save<'e, E>(&self, executor: E) -> Result<i64, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = Sqlite>,
+    {
+        let result = sqlx::query(
+            "INSERT INTO evidencias (nombre_archivo, nombre_original, tipo_mime, tamano_bytes, sha256) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&self.file_name)
+        .bind(&self.original_name)
+        .bind(self.mime)
+        .bind(self.size)
+        .bind(&self.sha256)
+        .execute(executor)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+}
+
+pub async fn ensure_upload_dir() -> std::io::Result<()> {
+    fs::create_dir_all(UPLOAD_DIR).await
+}
+
+fn sniff(head: &[u8]) -> Option<(&'static str, &'static str)> {
+    ALLOWED_TYPES
+        .iter()
+        .find(|(magic, _, _)| head.starts_with(magic))
+        .map(|(_, mime, extension)| (*mime, *extension))
+}
+
+async fn next_chunk(field: &mut Field<'_>) -> Result<Option<axum::body::Bytes>, UploadError> {
+    field.chunk().await.map_err(|e| {
+        // el limite del cuerpo de la peticion tambien corta aqui
+        if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            UploadError::TooLarge
+        } else {
+            UploadError::Read(e.body_text())
+        }
+    })
+}
+
+// guardar el campo en disco por partes: valida el tipo por su contenido (no por el nombre
+// ni el content-type del cliente), corta al pasar el limite y calcula el sha256
+pub async fn store_upload(mut field: Field<'_>) -> Result<StoredUpload, UploadError> {
+    let original_name = field
+        .file_name()
+        .map(|name| name.chars().take(255).collect::<String>());
+
+    // juntar lo suficiente para reconocer la firma antes de tocar el disco
+    let mut head: Vec<u8> = Vec::new();
+    while head.len() < SNIFF_LEN {
+        match next_chunk(&mut field).await? {
+            Some(chunk) => head.extend_from_slice(&chunk),
+            None => break,
+        }
+    }
+    if head.is_empty() {
+        return Err(UploadError::Empty);
+    }
+    if head.len() > MAX_UPLOAD_BYTES {
+        return Err(UploadError::TooLarge);
+    }
+    let (mime, extension) = sniff(&head).ok_or(UploadError::UnsupportedType)?;
+
+    // nombre aleatorio generado por el servidor; create_new nunca sobreescribe
+    let file_name = format!("{}.{}", uuid::Uuid::new_v4().simple(), extension);
+    let path = PathBuf::from(UPLOAD_DIR).join(&file_name);
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .await?;
+
+    let mut hasher = Sha256::new();
+    let mut size = head.len();
+    hasher.update(&head);
+
+    let result: Result<(), UploadError> = async {
+        file.write_all(&head).await?;
+        while let Some(chunk) = next_chunk(&mut field).await? {
+            size += chunk.len();
+            if size > MAX_UPLOAD_BYTES {
+                return Err(UploadError::TooLarge);
+            }
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        Ok(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        drop(file);
+        let _ = fs::remove_file(&path).await;
+        return Err(e);
+    }
+
+    Ok(StoredUpload {
+        file_name,
+        original_name,
+        mime,
+        size: size as i64,
+        sha256: hex::encode(hasher.finalize()),
+    })
+}
+
+// ruta relativa y firmada para descargar una evidencia; `viewer` identifica a quien se le
+// entrego el enlace (queda en la bitacora al descargar)
+fn signed_evidence_path(evidence_id: i64, viewer: &str, expires: i64) -> String {
+    let signature = sign(&format!("evidencia.{}.{}.{}", evidence_id, viewer, expires));
+    format!(
+        "/files/evidence/{}?viewer={}&expires={}&signature={}",
+        evidence_id, viewer, expires, signature
+    )
+}
+
+// enlace vigente por LINK_TTL_SECS
+pub fn evidence_link(evidence_id: i64, viewer: &str) -> EvidenceLink {
+    let expires = chrono::Utc::now().timestamp() + LINK_TTL_SECS;
+    EvidenceLink {
+        url: signed_evidence_path(evidence_id, viewer, expires),
+        expires_at: chrono::DateTime::from_timestamp(expires, 0)
+            .unwrap_or_default()
+            .to_rfc3339(),
+    }
+}
+
+pub fn verify_evidence_signature(
+    evidence_id: i64,
+    viewer: &str,
+    expires: i64,
+    signature: &str,
+) -> bool {
+    verify(
+        &format!("evidencia.{}.{}.{}", evidence_id, viewer, expires),
+        signature,
+    )
+}
+
+// los nombres los genera el servidor, pero los registros migrados vienen de nombres del
+// cliente: nunca servir algo que salga de la carpeta
+pub fn evidence_path(file_name: &str) -> Option<PathBuf> {
+    let valid = !file_name.is_empty()
+        && !file_name.starts_with('.')
+        && !file_name.contains(['/', '\\'])
+        && !file_name.contains("..");
+    valid.then(|| PathBuf::from(UPLOAD_DIR).join(file_name))
+}
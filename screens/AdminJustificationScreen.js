@@ -13,6 +13,7 @@ export default function AdminJustificationScreen({ navigation }) {
     const [modalVisible, setModalVisible] = useState(false);
     const [comment, setComment] = useState('');
     const [processing, setProcessing] = useState(false);
+    const [evidenceUrl, setEvidenceUrl] = useState(null);
 
     useEffect(() => {
         loadJustifications();
@@ -49,10 +50,18 @@ export default function AdminJustificationScreen({ navigation }) {
         }
     };
 
-    const openModal = (item) => {
+    const openModal = async (item) => {
         setSelectedItem(item);
         setComment('');
+        setEvidenceUrl(null);
         setModalVisible(true);
+        if (item.evidencia_id) {
+            try {
+                setEvidenceUrl(await api.getEvidenceUrl(item.id));
+            } catch (error) {
+                console.error(error);
+            }
+        }
     };
 
     const renderItem = ({ item }) => (
@@ -113,11 +122,11 @@ export default function AdminJustificationScreen({ navigation }) {
                                 <Text style={styles.detailLabel}>Motivo:</Text>
                                 <Text style={styles.detailText}>{selectedItem.motivo}</Text>
 
-                                {selectedItem.evidencia_url && (
+                                {evidenceUrl && (
                                     <View>
                                         <Text style={styles.detailLabel}>Evidencia:</Text>
                                         <Image
-                                            source={{ uri: api.getEvidenceUrl(selectedItem.evidencia_url) }}
+                                            source={{ uri: evidenceUrl }}
                                             style={styles.evidenceImage}
                                         />
                                     </View>
@@ -28,7 +28,7 @@ export default function AdminScreen() {
     const [username, setUsername] = useState('');
     const [password, setPassword] = useState('');
     const [nombre, setNombre] = useState('');
-    const [role, setRole] = useState('Regular');
+    const [role, setRole] = useState('Docente');
     const [color, setColor] = useState(TEACHER_COLORS[0]);
 
     // --- Group Form State ---
@@ -43,8 +43,6 @@ export default function AdminScreen() {
     const [studentDob, setStudentDob] = useState('');
     const [studentBlood, setStudentBlood] = useState('');
     const [studentGroup, setStudentGroup] = useState('');
-    const [studentAllergies, setStudentAllergies] = useState('');
-    const [studentChronic, setStudentChronic] = useState('');
     const [studentTutorPhone, setStudentTutorPhone] = useState('');
     const [studentPhone, setStudentPhone] = useState('');
 
@@ -196,9 +194,7 @@ export default function AdminScreen() {
                 paternal_last_name: studentApPat,
                 maternal_last_name: studentApMat || '',
                 birth_date: studentDob || '2010-01-01',
-                blood_type: studentBlood || 'O+',
-                allergies: studentAllergies || null,
-                chronic_conditions: studentChronic || null,
+                blood_type: studentBlood || null,
                 phone_number: studentPhone || null,
                 guardian_phone: studentTutorPhone || null,
                 group: studentGroup || null
@@ -207,7 +203,7 @@ export default function AdminScreen() {
             await api.createStudent(payload);
             Alert.alert('Éxito', 'Estudiante registrado correctamente');
             setStudentId(''); setStudentName(''); setStudentApPat(''); setStudentApMat('');
-            setStudentAllergies(''); setStudentChronic(''); setStudentPhone(''); setStudentTutorPhone(''); setStudentBlood(''); setStudentDob('');
+            setStudentPhone(''); setStudentTutorPhone(''); setStudentBlood(''); setStudentDob('');
             fetchStudents();
         } catch (e) { Alert.alert('Error', 'Fallo al registrar estudiante'); }
     };
@@ -220,10 +216,9 @@ export default function AdminScreen() {
         setStudentApPat(student.paternal_last_name);
         setStudentApMat(student.maternal_last_name || '');
         setStudentDob(student.birth_date);
-        setStudentBlood(student.blood_type || '');
+        // Blood type is not sent with the student; leaving it empty keeps what is on record
+        setStudentBlood('');
         setStudentGroup(student.group);
-        setStudentAllergies(student.allergies || '');
-        setStudentChronic(student.chronic_diseases || '');
         setStudentTutorPhone(student.primary_guardian_phone || '');
         setStudentPhone(student.personal_phone || '');
 
@@ -241,8 +236,6 @@ export default function AdminScreen() {
                 major: selectedStudent.major || 'General', // Keep existing or default
                 group: studentGroup,
                 blood_type: studentBlood || null,
-                allergies: studentAllergies || null,
-                chronic_conditions: studentChronic || null,
                 domicile: selectedStudent.domicile || null, // Keep existing if not editing
                 personal_phone: studentPhone || null,
                 primary_guardian_phone: studentTutorPhone || null,
@@ -257,7 +250,7 @@ export default function AdminScreen() {
 
             // Clear form
             setStudentId(''); setStudentName(''); setStudentApPat(''); setStudentApMat('');
-            setStudentAllergies(''); setStudentChronic(''); setStudentPhone(''); setStudentTutorPhone(''); setStudentBlood(''); setStudentDob(''); setStudentGroup('');
+            setStudentPhone(''); setStudentTutorPhone(''); setStudentBlood(''); setStudentDob(''); setStudentGroup('');
         } catch (e) { Alert.alert('Error', 'Fallo al actualizar estudiante'); }
     };
 
@@ -374,8 +367,6 @@ export default function AdminScreen() {
                                     </View>
                                 </View>
 
-                                <Input label="Alergias" value={studentAllergies} onChangeText={setStudentAllergies} placeholder="Ej: Nueces, ninguna..." multiline />
-                                <Input label="Enfermedades Crónicas" value={studentChronic} onChangeText={setStudentChronic} placeholder="Ej: Asma, ninguna..." multiline />
                                 <Input label="Teléfono Tutor" value={studentTutorPhone} onChangeText={setStudentTutorPhone} placeholder="Ej: 555-1234" keyboardType="phone-pad" />
 
                                 {renderGroupSelector()}
@@ -395,7 +386,7 @@ export default function AdminScreen() {
                                             </View>
                                             <View style={{ flex: 1 }}>
                                                 <Text style={styles.itemName}>{item.names} {item.paternal_last_name}</Text>
-                                                <Text style={styles.itemSub}>ID: {item.id} • {item.blood_type || 'N/A'}</Text>
+                                                <Text style={styles.itemSub}>ID: {item.id}</Text>
                                             </View>
                                             <MaterialCommunityIcons name="pencil" size={20} color={COLORS.textSecondary} />
                                         </TouchableOpacity>
@@ -513,14 +504,12 @@ export default function AdminScreen() {
                                         <Input label="Fecha Nacimiento" value={studentDob} onChangeText={setStudentDob} placeholder="AAAA-MM-DD" />
                                     </View>
                                     <View style={{ flex: 1 }}>
-                                        <Input label="Tipo Sangre" value={studentBlood} onChangeText={setStudentBlood} />
+                                        <Input label="Tipo Sangre" value={studentBlood} onChangeText={setStudentBlood} placeholder="Sin cambios" />
                                     </View>
                                 </View>
 
                                 {renderGroupSelector()}
 
-                                <Input label="Alergias" value={studentAllergies} onChangeText={setStudentAllergies} multiline />
-                                <Input label="Enf. Crónicas" value={studentChronic} onChangeText={setStudentChronic} multiline />
                                 <Input label="Tel. Tutor" value={studentTutorPhone} onChangeText={setStudentTutorPhone} keyboardType="phone-pad" />
                                 <Input label="Tel. Personal" value={studentPhone} onChangeText={setStudentPhone} keyboardType="phone-pad" />
                             </View>
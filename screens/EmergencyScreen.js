@@ -165,7 +165,7 @@ export default function EmergencyScreen({ navigation }) {
   const confirmTriggerEmergency = async () => {
     try {
       console.log("Iniciando emergencia para usuario:", user.id);
-      const result = await api.triggerEmergency(true, user.id);
+      const result = await api.triggerEmergency(true);
       console.log("Respuesta del servidor:", result);
       setEmergencyActive(true);
       fetchData();
@@ -182,8 +182,14 @@ export default function EmergencyScreen({ navigation }) {
   const confirmEndEmergency = async () => {
     try {
       console.log("Finalizando emergencia para usuario:", user.id);
-      const result = await api.triggerEmergency(false, user.id);
+      const result = await api.triggerEmergency(false);
       console.log("Respuesta del servidor:", result);
+      if (result.phase === "REUNIFICACION") {
+        // Evacuation over: the session stays open until every student is resolved
+        fetchData();
+        Alert.alert("Reunificación", result.message);
+        return;
+      }
       setEmergencyActive(false);
       setGroups([]);
       setRawStudents([]);
@@ -584,9 +590,40 @@ export default function EmergencyScreen({ navigation }) {
             {student.names} {student.paternal_last_name}
           </Text>
           <Text style={styles.details}>ID: {student.id}</Text>
+          {student.released && (
+            <Text style={styles.details}>Entregado a: {student.released_to}</Text>
+          )}
+          {student.wrong_point && (
+            <Text style={[styles.details, { color: COLORS.warning }]}>
+              En {student.assembly_point || student.zone}; le corresponde {student.expected_assembly_point}
+            </Text>
+          )}
+          {student.pass_destination && (
+            <Text style={[styles.details, { color: COLORS.warning }]}>
+              Con pase a {student.pass_destination} desde las{" "}
+              {new Date(student.pass_issued_at).toLocaleTimeString("es-ES", { hour: "2-digit", minute: "2-digit" })}
+            </Text>
+          )}
         </View>
         <View style={{ alignItems: "flex-end" }}>
-          {student.scanned ? (
+          {student.released ? (
+            <View
+              style={[
+                styles.badge,
+                { backgroundColor: `${COLORS.primary}15`, borderColor: COLORS.primary },
+              ]}
+            >
+              <MaterialCommunityIcons
+                name="account-arrow-right"
+                size={16}
+                color={COLORS.primary}
+                style={{ marginRight: 4 }}
+              />
+              <Text style={[styles.badgeText, { color: COLORS.primary }]}>
+                ENTREGADO
+              </Text>
+            </View>
+          ) : student.scanned ? (
             <View>
               <View
                 style={[
@@ -5,6 +5,8 @@ import { MaterialCommunityIcons } from '@expo/vector-icons';
 import ScreenWrapper from '../components/ScreenWrapper';
 import * as ImagePicker from 'expo-image-picker';
 import api from '../services/api';
+import { getData } from '../utils/storage';
+import { SESSION_CONFIG } from '../constants/appConstants';
 
 export default function JustificationScreen({ route, navigation }) {
     const { studentId, studentName } = route.params || {};
@@ -75,7 +77,9 @@ export default function JustificationScreen({ route, navigation }) {
                 });
             }
 
-            await api.submitJustification(formData);
+            // Only a signed-in guardian can request a justificante
+            const portal = await getData(SESSION_CONFIG.PORTAL_STORAGE_KEY);
+            await api.submitJustification(formData, portal?.token);
             Alert.alert('Éxito', 'Justificante enviado correctamente', [
                 { text: 'OK', onPress: () => navigation.goBack() }
             ]);
@@ -1,16 +1,21 @@
-import React, { useState } from 'react';
+import React, { useState, useEffect } from 'react';
 import { View, Text, StyleSheet, TextInput, TouchableOpacity, ScrollView, ActivityIndicator, Alert } from 'react-native';
 import { COLORS, SPACING, FONTS, LAYOUT, SHADOWS } from '../theme';
 import { MaterialCommunityIcons } from '@expo/vector-icons';
 import ScreenWrapper from '../components/ScreenWrapper';
 import api from '../services/api';
+import { storeData, getData, removeData } from '../utils/storage';
+import { SESSION_CONFIG } from '../constants/appConstants';
 
 import { usePushNotifications } from '../hooks/usePushNotifications';
 import * as Device from 'expo-device';
 
 export default function ParentPortalScreen({ navigation }) {
-    const [studentId, setStudentId] = useState('');
-    const [studentData, setStudentData] = useState(null);
+    const [phone, setPhone] = useState('');
+    const [code, setCode] = useState('');
+    const [codeSent, setCodeSent] = useState(false);
+    const [sessionToken, setSessionToken] = useState(null);
+    const [children, setChildren] = useState([]);
     const [loading, setLoading] = useState(false);
     const [error, setError] = useState('');
 
@@ -18,48 +23,201 @@ export default function ParentPortalScreen({ navigation }) {
     const { expoPushToken, registerTokenBackend } = usePushNotifications();
     const [subscribing, setSubscribing] = useState(false);
 
-    const handleSubscribe = async () => {
-        if (!studentData?.student?.id_control_escolar) return;
+    // Restore a previous portal session
+    useEffect(() => {
+        (async () => {
+            const stored = await getData(SESSION_CONFIG.PORTAL_STORAGE_KEY);
+            if (stored?.token) {
+                setSessionToken(stored.token);
+                loadChildren(stored.token);
+            }
+        })();
+    }, []);
 
-        setSubscribing(true);
-        // Wait a bit for token if not ready (simple retry logic could be added)
-        if (!expoPushToken) {
-            Alert.alert("Error", "No se pudo obtener el token del dispositivo. Asegúrese de permitir notificaciones.");
-            setSubscribing(false);
+    const loadChildren = async (token) => {
+        setLoading(true);
+        setError('');
+        try {
+            const data = await api.getPortalStudents(token);
+            setChildren(data);
+        } catch (e) {
+            if (e.statusCode === 401) {
+                // Session expired or revoked
+                await removeData(SESSION_CONFIG.PORTAL_STORAGE_KEY);
+                setSessionToken(null);
+                setChildren([]);
+                setError('Su sesión expiró, ingrese de nuevo');
+            } else {
+                setError('No se pudo cargar la información');
+            }
+            console.error(e);
+        } finally {
+            setLoading(false);
+        }
+    };
+
+    const requestCode = async () => {
+        if (!phone.trim()) {
+            setError('Por favor ingrese su número de teléfono');
             return;
         }
 
+        setLoading(true);
+        setError('');
         try {
-            await registerTokenBackend(studentData.student.id_control_escolar, Device.modelName);
-            Alert.alert("Éxito", "Dispositivo suscrito para recibir notificaciones de este estudiante.");
+            await api.requestPortalCode(phone.trim());
+            setCodeSent(true);
         } catch (e) {
-            Alert.alert("Error", "No se pudo suscribir.");
+            setError(e.message || 'No se pudo enviar el código');
+            console.error(e);
         } finally {
-            setSubscribing(false);
+            setLoading(false);
         }
     };
 
-    const searchStudent = async () => {
-        if (!studentId.trim()) {
-            setError('Por favor ingrese una matrícula');
+    const verifyCode = async () => {
+        if (!code.trim()) {
+            setError('Ingrese el código que recibió por SMS');
             return;
         }
 
         setLoading(true);
         setError('');
-        setStudentData(null);
+        try {
+            const session = await api.verifyPortalCode(phone.trim(), code.trim(), Device.modelName);
+            await storeData(SESSION_CONFIG.PORTAL_STORAGE_KEY, { token: session.token, expires_at: session.expires_at });
+            setSessionToken(session.token);
+            setCode('');
+            setCodeSent(false);
+            await loadChildren(session.token);
+        } catch (e) {
+            setError('Código inválido o vencido');
+            console.error(e);
+            setLoading(false);
+        }
+    };
 
+    const logout = async () => {
         try {
-            const data = await api.getStudentPortalInfo(studentId.trim());
-            setStudentData(data);
+            await api.portalLogout(sessionToken);
         } catch (e) {
-            setError('No se encontró el estudiante o hubo un error');
             console.error(e);
+        }
+        await removeData(SESSION_CONFIG.PORTAL_STORAGE_KEY);
+        setSessionToken(null);
+        setChildren([]);
+    };
+
+    const handleSubscribe = async () => {
+        if (!sessionToken) return;
+
+        setSubscribing(true);
+        // Wait a bit for token if not ready (simple retry logic could be added)
+        if (!expoPushToken) {
+            Alert.alert("Error", "No se pudo obtener el token del dispositivo. Asegúrese de permitir notificaciones.");
+            setSubscribing(false);
+            return;
+        }
+
+        try {
+            await registerTokenBackend(sessionToken, Device.modelName);
+            Alert.alert("Éxito", "Dispositivo suscrito para recibir notificaciones de sus hijos.");
+        } catch (e) {
+            Alert.alert("Error", "No se pudo suscribir.");
         } finally {
-            setLoading(false);
+            setSubscribing(false);
         }
     };
 
+    const renderStudent = (studentData) => (
+        <View key={studentData.student.id_control_escolar} style={styles.resultsSection}>
+            {/* Student Info Card */}
+            <View style={styles.card}>
+                <View style={styles.cardHeader}>
+                    <MaterialCommunityIcons name="account-circle" size={40} color={COLORS.primary} />
+                    <View style={{ flex: 1, marginLeft: SPACING.m }}>
+                        <Text style={styles.studentName}>{studentData.student.nombres} {studentData.student.apellido_paterno}</Text>
+                        <Text style={styles.studentDetail}>ID: {studentData.student.id_control_escolar}</Text>
+                        <Text style={styles.studentDetail}>Grupo: {studentData.student.grupo}</Text>
+                    </View>
+                </View>
+            </View>
+
+            {/* Emergency Status */}
+            {studentData.emergency_status && (
+                <View style={[styles.card, { backgroundColor: studentData.emergency_status.scanned ? COLORS.success + '10' : COLORS.danger + '10', borderColor: studentData.emergency_status.scanned ? COLORS.success : COLORS.danger }]}>
+                    <View style={styles.emergencyHeader}>
+                        <MaterialCommunityIcons
+                            name={studentData.emergency_status.scanned ? "shield-check" : "alert-octagon"}
+                            size={32}
+                            color={studentData.emergency_status.scanned ? COLORS.success : COLORS.danger}
+                        />
+                        <Text style={[styles.emergencyTitle, { color: studentData.emergency_status.scanned ? COLORS.success : COLORS.danger }]}>
+                            EMERGENCIA ACTIVA
+                        </Text>
+                    </View>
+                    <Text style={[styles.emergencyStatus, { color: studentData.emergency_status.scanned ? COLORS.success : COLORS.danger }]}>
+                        Estado: {studentData.emergency_status.released ? 'ENTREGADO ✓' : studentData.emergency_status.scanned ? 'SEGURO ✓' : 'NO LOCALIZADO'}
+                    </Text>
+                    {!studentData.emergency_status.scanned && !studentData.emergency_status.released && (
+                        <Text style={styles.emergencyNote}>Si su hijo/a se encuentra con usted, favor de comunicarse con la escuela.</Text>
+                    )}
+                    {studentData.emergency_status.phase !== 'REUNIFICACION' && !!studentData.emergency_status.instructions && (
+                        <Text style={styles.emergencyNote}>{studentData.emergency_status.instructions}</Text>
+                    )}
+                    {!!studentData.emergency_status.reunification_point && (
+                        <Text style={styles.emergencyNote}>Punto de reunificación: {studentData.emergency_status.reunification_point}</Text>
+                    )}
+                </View>
+            )}
+
+            {/* Release (pickup) */}
+            {studentData.release && (
+                <View style={styles.card}>
+                    <View style={styles.emergencyHeader}>
+                        <MaterialCommunityIcons name="account-arrow-right" size={28} color={COLORS.primary} />
+                        <Text style={[styles.emergencyTitle, { color: COLORS.primary }]}>ENTREGADO</Text>
+                    </View>
+                    <Text style={styles.studentDetail}>
+                        Recogido por {studentData.release.received_by} a las {new Date(studentData.release.released_at).toLocaleTimeString('es-ES', { hour: '2-digit', minute: '2-digit' })}
+                    </Text>
+                </View>
+            )}
+
+            {/* Attendance History */}
+            <View style={styles.card}>
+                <Text style={styles.sectionTitle}>Historial de Asistencias (últimos 30 días)</Text>
+                {studentData.attendance && studentData.attendance.length > 0 ? (
+                    studentData.attendance.slice(0, 10).map((record, index) => (
+                        <View key={index} style={styles.attendanceItem}>
+                            <View style={[styles.statusDot, { backgroundColor: record.presente ? COLORS.success : COLORS.warning }]} />
+                            <View style={{ flex: 1 }}>
+                                <Text style={styles.attendanceDate}>
+                                    {new Date(record.fecha_asistencia).toLocaleDateString('es-ES', {
+                                        day: '2-digit',
+                                        month: 'long',
+                                        year: 'numeric'
+                                    })}
+                                </Text>
+                                <Text style={styles.attendanceTime}>
+                                    {new Date(record.fecha_asistencia).toLocaleTimeString('es-ES', {
+                                        hour: '2-digit',
+                                        minute: '2-digit'
+                                    })} - {record.salon_clase}
+                                </Text>
+                            </View>
+                            <View style={[styles.statusBadge, { backgroundColor: record.presente ? COLORS.success : COLORS.warning }]}>
+                                <Text style={styles.statusText}>{record.presente ? 'ENTRADA' : 'SALIDA'}</Text>
+                            </View>
+                        </View>
+                    ))
+                ) : (
+                    <Text style={styles.noDataText}>No hay registros de asistencia</Text>
+                )}
+            </View>
+        </View>
+    );
+
     return (
         <ScreenWrapper>
             <View style={styles.header}>
@@ -70,108 +228,83 @@ export default function ParentPortalScreen({ navigation }) {
             </View>
 
             <ScrollView style={styles.container} contentContainerStyle={styles.contentContainer}>
-                <View style={styles.searchSection}>
-                    <MaterialCommunityIcons name="account-search" size={60} color={COLORS.primary} />
-                    <Text style={styles.subtitle}>Consulte información de su hijo/a</Text>
-                    <Text style={styles.description}>Ingrese el ID del estudiante para ver su información</Text>
-
-                    <View style={styles.searchBox}>
-                        <TextInput
-                            style={styles.input}
-                            value={studentId}
-                            onChangeText={setStudentId}
-                            placeholder="ID del estudiante (ej: 2765432101)"
-                            placeholderTextColor={COLORS.textSecondary}
-                            keyboardType="default"
-                        />
-                        <TouchableOpacity
-                            style={styles.searchButton}
-                            onPress={searchStudent}
-                            disabled={loading}
-                        >
-                            {loading ? (
-                                <ActivityIndicator color={COLORS.white} />
-                            ) : (
-                                <MaterialCommunityIcons name="magnify" size={24} color={COLORS.white} />
-                            )}
-                        </TouchableOpacity>
-                    </View>
+                {!sessionToken ? (
+                    <View style={styles.searchSection}>
+                        <MaterialCommunityIcons name="cellphone-key" size={60} color={COLORS.primary} />
+                        <Text style={styles.subtitle}>Consulte información de sus hijos</Text>
+                        <Text style={styles.description}>
+                            {codeSent
+                                ? 'Si el número está registrado en la escuela recibirá un código por SMS'
+                                : 'Ingrese el teléfono que tiene registrado en la escuela'}
+                        </Text>
 
-                    {error ? (
-                        <View style={styles.errorBox}>
-                            <MaterialCommunityIcons name="alert-circle" size={20} color={COLORS.danger} />
-                            <Text style={styles.errorText}>{error}</Text>
+                        <View style={styles.searchBox}>
+                            <TextInput
+                                style={styles.input}
+                                value={codeSent ? code : phone}
+                                onChangeText={codeSent ? setCode : setPhone}
+                                placeholder={codeSent ? 'Código de 6 dígitos' : 'Teléfono (10 dígitos)'}
+                                placeholderTextColor={COLORS.textSecondary}
+                                keyboardType="phone-pad"
+                                maxLength={codeSent ? 6 : 20}
+                            />
+                            <TouchableOpacity
+                                style={styles.searchButton}
+                                onPress={codeSent ? verifyCode : requestCode}
+                                disabled={loading}
+                            >
+                                {loading ? (
+                                    <ActivityIndicator color={COLORS.white} />
+                                ) : (
+                                    <MaterialCommunityIcons name={codeSent ? 'check' : 'send'} size={24} color={COLORS.white} />
+                                )}
+                            </TouchableOpacity>
                         </View>
-                    ) : null}
-                </View>
 
-                {studentData && (
-                    <View style={styles.resultsSection}>
-                        {/* Student Info Card */}
-                        <View style={styles.card}>
-                            <View style={styles.cardHeader}>
-                                <MaterialCommunityIcons name="account-circle" size={40} color={COLORS.primary} />
-                                <View style={{ flex: 1, marginLeft: SPACING.m }}>
-                                    <Text style={styles.studentName}>{studentData.student.nombres} {studentData.student.apellido_paterno}</Text>
-                                    <Text style={styles.studentDetail}>ID: {studentData.student.id_control_escolar}</Text>
-                                    <Text style={styles.studentDetail}>Grupo: {studentData.student.grupo}</Text>
-                                </View>
+                        {codeSent && (
+                            <TouchableOpacity onPress={() => { setCodeSent(false); setCode(''); }}>
+                                <Text style={styles.linkText}>Cambiar número o reenviar código</Text>
+                            </TouchableOpacity>
+                        )}
+
+                        {error ? (
+                            <View style={styles.errorBox}>
+                                <MaterialCommunityIcons name="alert-circle" size={20} color={COLORS.danger} />
+                                <Text style={styles.errorText}>{error}</Text>
                             </View>
+                        ) : null}
+                    </View>
+                ) : (
+                    <View style={styles.resultsSection}>
+                        <View style={styles.sessionActions}>
+                            <TouchableOpacity style={styles.subscribeButton} onPress={handleSubscribe} disabled={subscribing}>
+                                {subscribing ? (
+                                    <ActivityIndicator color={COLORS.white} size="small" />
+                                ) : (
+                                    <MaterialCommunityIcons name="bell-ring" size={18} color={COLORS.white} />
+                                )}
+                                <Text style={styles.subscribeText}>Recibir notificaciones</Text>
+                            </TouchableOpacity>
+                            <TouchableOpacity style={styles.justificationButton} onPress={logout}>
+                                <MaterialCommunityIcons name="logout" size={18} color={COLORS.primary} />
+                                <Text style={styles.justificationText}>Cerrar sesión</Text>
+                            </TouchableOpacity>
                         </View>
 
-                        {/* Emergency Status */}
-                        {studentData.emergency_status && (
-                            <View style={[styles.card, { backgroundColor: studentData.emergency_status.scanned ? COLORS.success + '10' : COLORS.danger + '10', borderColor: studentData.emergency_status.scanned ? COLORS.success : COLORS.danger }]}>
-                                <View style={styles.emergencyHeader}>
-                                    <MaterialCommunityIcons
-                                        name={studentData.emergency_status.scanned ? "shield-check" : "alert-octagon"}
-                                        size={32}
-                                        color={studentData.emergency_status.scanned ? COLORS.success : COLORS.danger}
-                                    />
-                                    <Text style={[styles.emergencyTitle, { color: studentData.emergency_status.scanned ? COLORS.success : COLORS.danger }]}>
-                                        EMERGENCIA ACTIVA
-                                    </Text>
-                                </View>
-                                <Text style={[styles.emergencyStatus, { color: studentData.emergency_status.scanned ? COLORS.success : COLORS.danger }]}>
-                                    Estado: {studentData.emergency_status.scanned ? 'SEGURO ✓' : 'NO LOCALIZADO'}
-                                </Text>
-                                {!studentData.emergency_status.scanned && (
-                                    <Text style={styles.emergencyNote}>Si su hijo/a se encuentra con usted, favor de comunicarse con la escuela.</Text>
-                                )}
+                        {error ? (
+                            <View style={styles.errorBox}>
+                                <MaterialCommunityIcons name="alert-circle" size={20} color={COLORS.danger} />
+                                <Text style={styles.errorText}>{error}</Text>
                             </View>
-                        )}
+                        ) : null}
 
-                        {/* Attendance History */}
-                        <View style={styles.card}>
-                            <Text style={styles.sectionTitle}>Historial de Asistencias (últimos 30 días)</Text>
-                            {studentData.attendance && studentData.attendance.length > 0 ? (
-                                studentData.attendance.slice(0, 10).map((record, index) => (
-                                    <View key={index} style={styles.attendanceItem}>
-                                        <View style={[styles.statusDot, { backgroundColor: record.presente ? COLORS.success : COLORS.warning }]} />
-                                        <View style={{ flex: 1 }}>
-                                            <Text style={styles.attendanceDate}>
-                                                {new Date(record.fecha_asistencia).toLocaleDateString('es-ES', {
-                                                    day: '2-digit',
-                                                    month: 'long',
-                                                    year: 'numeric'
-                                                })}
-                                            </Text>
-                                            <Text style={styles.attendanceTime}>
-                                                {new Date(record.fecha_asistencia).toLocaleTimeString('es-ES', {
-                                                    hour: '2-digit',
-                                                    minute: '2-digit'
-                                                })} - {record.salon_clase}
-                                            </Text>
-                                        </View>
-                                        <View style={[styles.statusBadge, { backgroundColor: record.presente ? COLORS.success : COLORS.warning }]}>
-                                            <Text style={styles.statusText}>{record.presente ? 'ENTRADA' : 'SALIDA'}</Text>
-                                        </View>
-                                    </View>
-                                ))
-                            ) : (
-                                <Text style={styles.noDataText}>No hay registros de asistencia</Text>
-                            )}
-                        </View>
+                        {loading ? (
+                            <ActivityIndicator color={COLORS.primary} />
+                        ) : children.length > 0 ? (
+                            children.map(renderStudent)
+                        ) : (
+                            <Text style={styles.noDataText}>No hay estudiantes vinculados a este teléfono</Text>
+                        )}
                     </View>
                 )}
             </ScrollView>
@@ -259,6 +392,17 @@ const styles = StyleSheet.create({
     resultsSection: {
         gap: SPACING.m,
     },
+    sessionActions: {
+        flexDirection: 'row',
+        justifyContent: 'space-between',
+        flexWrap: 'wrap',
+        gap: SPACING.s,
+    },
+    linkText: {
+        color: COLORS.primary,
+        ...FONTS.medium,
+        marginTop: SPACING.m,
+    },
     card: {
         backgroundColor: COLORS.surface,
         borderRadius: LAYOUT.radius.m,
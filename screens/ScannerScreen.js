@@ -47,7 +47,9 @@ export default function ScannerScreen({ navigation }) {
         try {
             if (scanMode === 'medical') {
                 const student = await api.getStudent(data);
-                navigation.navigate('StudentDetail', { student });
+                // Only medical staff get the record; everyone else sees the student without it
+                const medical = await api.getMedicalRecord(data).catch(() => null);
+                navigation.navigate('StudentDetail', { student, medical });
                 setScanned(false);
                 setScanMode(null); // Reset after success
             } else if (scanMode === 'emergency') {
@@ -7,7 +7,9 @@ import { COLORS, SPACING, FONTS, SHADOWS, LAYOUT } from '../theme';
 import { MaterialCommunityIcons } from '@expo/vector-icons';
 
 export default function StudentDetailScreen({ navigation, route }) {
-    const { student } = route.params;
+    const { student, medical } = route.params;
+    const describeItems = (items) => items?.map((item) => item.severity ? `${item.name} (${item.severity})` : item.name).join(', ');
+    const medicalValue = (value) => medical ? value : 'Solo personal médico';
 
     const InfoRow = ({ label, value, icon, isMedical = false }) => (
         <View style={styles.infoRow}>
@@ -55,7 +57,7 @@ export default function StudentDetailScreen({ navigation, route }) {
                     </View>
                     <InfoRow label="Nombre Completo" value={`${student.names} ${student.paternal_last_name} ${student.maternal_last_name || ''}`} icon="account" />
                     <InfoRow label="Fecha de Nacimiento" value={student.birth_date} icon="calendar" />
-                    <InfoRow label="Tipo de Sangre" value={student.blood_type} icon="water" isMedical />
+                    <InfoRow label="Tipo de Sangre" value={medicalValue(medical?.blood_type)} icon="water" isMedical />
                 </Card>
 
                 <Card style={styles.card}>
@@ -63,8 +65,8 @@ export default function StudentDetailScreen({ navigation, route }) {
                         <MaterialCommunityIcons name="medical-bag" size={24} color={COLORS.danger} />
                         <Text style={[styles.sectionTitle, { color: COLORS.danger }]}>Información Médica</Text>
                     </View>
-                    <InfoRow label="Alergias" value={student.allergies} icon="alert-circle-outline" isMedical />
-                    <InfoRow label="Enfermedades Crónicas" value={student.chronic_conditions} icon="hospital-box-outline" isMedical />
+                    <InfoRow label="Alergias" value={medicalValue(describeItems(medical?.allergies))} icon="alert-circle-outline" isMedical />
+                    <InfoRow label="Enfermedades Crónicas" value={medicalValue(describeItems(medical?.conditions))} icon="hospital-box-outline" isMedical />
                 </Card>
 
                 <Card style={styles.card}>
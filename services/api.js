@@ -11,18 +11,30 @@ import { parseResponse, handleNetworkError, logRequest, logResponse } from './er
  * Handles all API requests with consistent error handling
  */
 class ApiService {
+    /**
+     * Staff session token from the login, sent on every request without a portal token
+     * @param {string|null} token
+     */
+    setSessionToken(token) {
+        this.sessionToken = token || null;
+    }
+
     /**
      * Make an API request
      * @param {string} endpoint - API endpoint
      * @param {string} method - HTTP method (GET, POST, PUT, DELETE)
      * @param {Object|null} body - Request body
+     * @param {string|null} token - Parent portal session token (defaults to the staff session)
      * @returns {Promise<Object>} Response data
      * @throws {ApiError|NetworkError} On request failure
      */
-    async request(endpoint, method = 'GET', body = null) {
+    async request(endpoint, method = 'GET', body = null, token = null) {
+        const authToken = token || this.sessionToken;
         const config = {
             method,
-            headers: DEFAULT_HEADERS,
+            headers: authToken
+                ? { ...DEFAULT_HEADERS, Authorization: `Bearer ${authToken}` }
+                : DEFAULT_HEADERS,
         };
 
         if (body) {
@@ -87,12 +99,19 @@ class ApiService {
      * Login with username and password
      * @param {string} username - Username
      * @param {string} password - Password
-     * @returns {Promise<Object>} User data
+     * @returns {Promise<Object>} User data with the session `token`
      */
     async login(username, password) {
         return this.post(API_ENDPOINTS.LOGIN, { username, password });
     }
 
+    /**
+     * Revoke the staff session token
+     */
+    async logout() {
+        return this.post(API_ENDPOINTS.LOGOUT);
+    }
+
     // ========== Student APIs ==========
 
     /**
@@ -104,6 +123,15 @@ class ApiService {
         return this.get(API_ENDPOINTS.STUDENT(id));
     }
 
+    /**
+     * Medical record of a student (medical staff only; every read is logged)
+     * @param {string} id - Student ID
+     * @returns {Promise<Object>} { blood_type, allergies, conditions, medications, visits }
+     */
+    async getMedicalRecord(id) {
+        return this.get(`/medical/students/${id}`);
+    }
+
     // ========== Scan APIs ==========
 
     /**
@@ -142,11 +170,10 @@ class ApiService {
     /**
      * Trigger or deactivate emergency
      * @param {boolean} active - Emergency status
-     * @param {string} userId - User ID
      * @returns {Promise<Object>} Emergency result
      */
-    async triggerEmergency(active, userId) {
-        return this.post(API_ENDPOINTS.EMERGENCY_TRIGGER, { active, user_id: userId });
+    async triggerEmergency(active) {
+        return this.post(API_ENDPOINTS.EMERGENCY_TRIGGER, { active });
     }
 
     /**
@@ -244,38 +271,71 @@ class ApiService {
         return this.get(API_ENDPOINTS.GROUPS);
     }
 
+    // ========== Parent Portal APIs ==========
+
     /**
-     * Get student portal info (public endpoint for parents)
+     * Ask for a one-time code sent by SMS to the guardian phone on record
+     * @param {string} phone
      */
-    async getStudentPortalInfo(studentId) {
-        return this.get(`/public/student/${studentId}`);
+    async requestPortalCode(phone) {
+        return this.post(API_ENDPOINTS.PORTAL_REQUEST_CODE, { phone });
     }
 
     /**
-     * Register push notification token
-     * @param {string} studentId
+     * Exchange the code for a portal session
+     * @param {string} phone
+     * @param {string} code
+     * @param {string} deviceName
+     * @returns {Promise<Object>} { token, expires_at, guardian }
+     */
+    async verifyPortalCode(phone, code, deviceName) {
+        return this.post(API_ENDPOINTS.PORTAL_VERIFY_CODE, { phone, code, device_name: deviceName });
+    }
+
+    /**
+     * Close the portal session
+     * @param {string} token
+     */
+    async portalLogout(token) {
+        return this.request(API_ENDPOINTS.PORTAL_LOGOUT, 'POST', null, token);
+    }
+
+    /**
+     * Attendance and emergency status of all the guardian's children
      * @param {string} token
+     */
+    async getPortalStudents(token) {
+        return this.request(API_ENDPOINTS.PORTAL_STUDENTS, 'GET', null, token);
+    }
+
+    /**
+     * Register this device for push notifications of all the guardian's children
+     * @param {string} token - Portal session token
+     * @param {string} pushToken - Expo push token
      * @param {string} deviceName
      */
-    async registerPushToken(studentId, token, deviceName) {
-        return this.post(API_ENDPOINTS.REGISTER_PUSH_TOKEN, {
-            student_id: studentId,
-            token,
-            device_name: deviceName
-        });
+    async registerPortalDevice(token, pushToken, deviceName) {
+        return this.request(
+            API_ENDPOINTS.PORTAL_DEVICES,
+            'POST',
+            { token: pushToken, device_name: deviceName },
+            token
+        );
     }
 
     /**
      * Submit medical justification
      * @param {FormData} formData
+     * @param {string} token - Parent portal session token
      */
-    async submitJustification(formData) {
+    async submitJustification(formData, token) {
         // We use fetch directly for FormData to handle multipart/form-data correctly
         // ApiService.request sets JSON headers by default which conflicts with FormData
         const url = getApiUrl(API_ENDPOINTS.SUBMIT_JUSTIFICATION);
         try {
             const response = await fetchWithTimeout(url, {
                 method: 'POST',
+                headers: { Authorization: `Bearer ${token}` },
                 body: formData,
                 // Let browser/engine set Content-Type header with boundary
             });
@@ -286,11 +346,14 @@ class ApiService {
         }
     }
 
-    getEvidenceUrl(path) {
-        if (!path) return null;
-        // path is like "uploads/filename.jpg"
-        // we need backend url
-        return `${this.baseURL}/${path}`;
+    /**
+     * Get a short-lived signed URL for a justificante's evidence (staff only)
+     * @param {number} id - Justificante ID
+     * @returns {Promise<string>} Absolute download URL
+     */
+    async getEvidenceUrl(id) {
+        const link = await this.get(`/justifications/${id}/evidence`);
+        return `${this.baseURL}${link.url}`;
     }
 
     async getPendingJustifications() {
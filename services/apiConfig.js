@@ -45,8 +45,12 @@ export const API_ENDPOINTS = {
   USERS: "/user/all", // Changed from /users to /user/all to match backend routes
   GROUPS: "/groups",
 
-  // Notifications
-  REGISTER_PUSH_TOKEN: "/notifications/register-token",
+  // Parent portal (guardian session via Bearer token)
+  PORTAL_REQUEST_CODE: "/portal/auth/request-code",
+  PORTAL_VERIFY_CODE: "/portal/auth/verify",
+  PORTAL_LOGOUT: "/portal/auth/logout",
+  PORTAL_STUDENTS: "/portal/students",
+  PORTAL_DEVICES: "/portal/devices",
 
   // Justifications
   SUBMIT_JUSTIFICATION: "/justifications/submit",